@@ -84,6 +84,7 @@ pub fn benchmark_data_loader(c: &mut Criterion) {
                     env: Arc::new(Env {}),
                     file: Arc::new(File {}),
                     cache: Arc::new(Cache {}),
+                    oauth2_tokens: Default::default(),
                     extensions: Arc::new(vec![]),
                     cmd_worker: None,
                     worker: None,
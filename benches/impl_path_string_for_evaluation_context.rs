@@ -248,6 +248,7 @@ fn request_context() -> RequestContext {
         env: Arc::new(Env {}),
         file: Arc::new(File {}),
         cache: Arc::new(InMemoryCache::default()),
+        oauth2_tokens: Default::default(),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
@@ -47,6 +47,14 @@ pub enum Command {
         #[arg(short, long)]
         schema: bool,
 
+        /// Display the .proto of the gRPC service generated from the schema
+        #[arg(long)]
+        proto: bool,
+
+        /// Display the OpenAPI document of the @rest endpoints
+        #[arg(long)]
+        openapi: bool,
+
         /// Controls SSL/TLS certificate verification for remote config files
         /// Set to false to skip certificate verification (not recommended for
         /// production)
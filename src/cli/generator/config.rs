@@ -93,6 +93,20 @@ pub enum Source<Status = UnResolved> {
     Config {
         src: Location<Status>,
     },
+    #[serde(rename_all = "camelCase")]
+    OpenApi {
+        src: Location<Status>,
+        /// Base URL of the operations, defaults to the first server of the
+        /// document.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Introspection {
+        /// URL of the GraphQL server to introspect and proxy.
+        src: Location<Status>,
+        headers: Headers,
+    },
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -236,6 +250,14 @@ impl Source<UnResolved> {
                 let resolved_path = src.into_resolved(parent_dir);
                 Ok(Source::Config { src: resolved_path })
             }
+            Source::OpenApi { src, url } => {
+                let resolved_path = src.into_resolved(parent_dir);
+                Ok(Source::OpenApi { src: resolved_path, url })
+            }
+            Source::Introspection { src, headers } => {
+                let resolved_path = src.into_resolved(parent_dir);
+                Ok(Source::Introspection { src: resolved_path, headers })
+            }
         }
     }
 }
@@ -7,12 +7,12 @@ use inquire::Confirm;
 use pathdiff::diff_paths;
 use tailcall_valid::{ValidateInto, Validator};
 
-use super::config::{Config, LLMConfig, Resolved, Source};
+use super::config::{Config, Headers, LLMConfig, Resolved, Source};
 use super::source::ConfigSource;
 use crate::cli::llm::InferTypeName;
 use crate::core::config::transformer::{Preset, RenameTypes};
 use crate::core::config::{self, ConfigModule, ConfigReaderContext};
-use crate::core::generator::{Generator as ConfigGenerator, Input};
+use crate::core::generator::{Generator as ConfigGenerator, Input, INTROSPECTION_QUERY};
 use crate::core::proto_reader::ProtoReader;
 use crate::core::resource_reader::{Resource, ResourceReader};
 use crate::core::runtime::TargetRuntime;
@@ -113,13 +113,7 @@ impl Generator {
                     if !req_body.is_null() {
                         request.body_mut().replace(req_body.to_string().into());
                     }
-                    if let Some(headers_inner) = headers.as_btree_map() {
-                        let mut header_map = HeaderMap::new();
-                        for (key, value) in headers_inner {
-                            let header_name = HeaderName::try_from(key)?;
-                            let header_value = HeaderValue::try_from(value.to_string())?;
-                            header_map.insert(header_name, header_value);
-                        }
+                    if let Some(header_map) = to_header_map(&headers)? {
                         *request.headers_mut() = header_map;
                     }
 
@@ -151,6 +145,29 @@ impl Generator {
                     let schema = reader.read_file(path).await?.content;
                     input_samples.push(Input::Config { schema, source });
                 }
+                Source::OpenApi { src, url } => {
+                    let content = reader.read_file(src.0).await?.content;
+                    // YAML is a superset of JSON, so both formats are parsed alike
+                    let spec = serde_yaml_ng::from_str(&content)?;
+                    input_samples.push(Input::OpenApi { spec, url });
+                }
+                Source::Introspection { src, headers } => {
+                    let url = src.0;
+                    let mut request = reqwest::Request::new(reqwest::Method::POST, url.parse()?);
+                    let body = serde_json::json!({ "query": INTROSPECTION_QUERY });
+                    request.body_mut().replace(body.to_string().into());
+
+                    let header_map = to_header_map(&headers)?.unwrap_or_default();
+                    *request.headers_mut() = header_map;
+                    request.headers_mut().insert(
+                        reqwest::header::CONTENT_TYPE,
+                        HeaderValue::from_static("application/json"),
+                    );
+
+                    let response = reader.read_file(Resource::from(request)).await?;
+                    let response = serde_json::from_str(&response.content)?;
+                    input_samples.push(Input::Introspection { url, response });
+                }
             }
         }
 
@@ -196,6 +213,22 @@ impl Generator {
     }
 }
 
+/// Converts the headers of a source into a [HeaderMap], if any are set.
+fn to_header_map(headers: &Headers) -> anyhow::Result<Option<HeaderMap>> {
+    let Some(headers) = headers.as_btree_map() else {
+        return Ok(None);
+    };
+
+    let mut header_map = HeaderMap::new();
+    for (key, value) in headers {
+        let header_name = HeaderName::try_from(key)?;
+        let header_value = HeaderValue::try_from(value.to_string())?;
+        header_map.insert(header_name, header_value);
+    }
+
+    Ok(Some(header_map))
+}
+
 /// Checks if file or folder already exists or not.
 fn is_exists(path: &str) -> bool {
     fs::metadata(path).is_ok()
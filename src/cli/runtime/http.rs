@@ -1,6 +1,7 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use http_cache_reqwest::{Cache, CacheMode, HttpCache, HttpCacheOptions};
 use hyper::body::Bytes;
 use once_cell::sync::Lazy;
@@ -13,13 +14,16 @@ use opentelemetry_semantic_conventions::trace::{
 };
 use reqwest::Client;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use rustls_pki_types::CertificateDer;
 use tailcall_http_cache::HttpCacheManager;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
+use super::http_stream::StreamClient;
 use super::HttpIO;
 use crate::core::blueprint::telemetry::Telemetry;
-use crate::core::blueprint::Upstream;
+use crate::core::blueprint::{ClientIdentity, Upstream};
 use crate::core::http::Response;
+use crate::core::BodyStream;
 
 static HTTP_CLIENT_REQUEST_COUNT: Lazy<Counter<u64>> = Lazy::new(|| {
     let meter = opentelemetry::global::meter("http_request");
@@ -57,6 +61,15 @@ impl RequestCounter {
             HTTP_CLIENT_REQUEST_COUNT.add(1, attributes);
         }
     }
+
+    fn update_stream(&mut self, response: &Result<Response<BodyStream>>) {
+        if let Some(ref mut attributes) = self.attributes {
+            let status_code = response.as_ref().map_or(0, |resp| resp.status.as_u16());
+            attributes.push(KeyValue::new(HTTP_RESPONSE_STATUS_CODE, status_code as i64));
+
+            HTTP_CLIENT_REQUEST_COUNT.add(1, attributes);
+        }
+    }
 }
 
 fn get_response_status(response: &reqwest_middleware::Result<reqwest::Response>) -> KeyValue {
@@ -67,25 +80,64 @@ fn get_response_status(response: &reqwest_middleware::Result<reqwest::Response>)
     KeyValue::new(HTTP_RESPONSE_STATUS_CODE, status_code as i64)
 }
 
+/// Certificates of the CA bundles linked for the upstream, trusted by the
+/// clients in addition to the webpki roots.
+pub(super) fn ca_certs(upstream: &Upstream) -> Result<Vec<CertificateDer<'static>>> {
+    let mut certs = Vec::new();
+    for ca in upstream.ca_certs.iter() {
+        let bundle = rustls_pemfile::certs(&mut ca.content.as_bytes())
+            .with_context(|| format!("Failed to read upstream CA bundle: {}", ca.path))?;
+        certs.extend(bundle.into_iter().map(CertificateDer::from));
+    }
+
+    Ok(certs)
+}
+
 #[derive(Clone)]
 pub struct NativeHttp {
     client: ClientWithMiddleware,
+    /// Clients presenting a host specific certificate to upstreams that
+    /// require mutual TLS.
+    host_clients: HashMap<String, ClientWithMiddleware>,
+    /// Clients of streamed responses, per host like `host_clients`.
+    stream_client: StreamClient,
+    host_stream_clients: HashMap<String, StreamClient>,
     http2_only: bool,
     enable_telemetry: bool,
 }
 
-impl Default for NativeHttp {
-    fn default() -> Self {
-        Self {
-            client: ClientBuilder::new(Client::new()).build(),
-            http2_only: false,
-            enable_telemetry: false,
+impl NativeHttp {
+    pub fn init(upstream: &Upstream, telemetry: &Telemetry) -> Result<Self> {
+        let default_cert = upstream
+            .client_certs
+            .iter()
+            .find(|client_cert| client_cert.hosts.is_empty());
+
+        let mut host_clients = HashMap::new();
+        let mut host_stream_clients = HashMap::new();
+        for client_cert in upstream.client_certs.iter() {
+            let client = Self::build_client(upstream, Some(client_cert))?;
+            let stream_client = StreamClient::try_new(upstream, Some(client_cert))?;
+            for host in client_cert.hosts.iter() {
+                host_clients.insert(host.clone(), client.clone());
+                host_stream_clients.insert(host.clone(), stream_client.clone());
+            }
         }
+
+        Ok(Self {
+            client: Self::build_client(upstream, default_cert)?,
+            host_clients,
+            stream_client: StreamClient::try_new(upstream, default_cert)?,
+            host_stream_clients,
+            http2_only: upstream.http2_only,
+            enable_telemetry: telemetry.export.is_some(),
+        })
     }
-}
 
-impl NativeHttp {
-    pub fn init(upstream: &Upstream, telemetry: &Telemetry) -> Self {
+    fn build_client(
+        upstream: &Upstream,
+        client_cert: Option<&ClientIdentity>,
+    ) -> Result<ClientWithMiddleware> {
         let mut builder = Client::builder()
             .tcp_keepalive(Some(Duration::from_secs(upstream.tcp_keep_alive)))
             .timeout(Duration::from_secs(upstream.timeout))
@@ -107,11 +159,30 @@ impl NativeHttp {
         if let Some(ref proxy) = upstream.proxy {
             builder = builder.proxy(
                 reqwest::Proxy::http(proxy.url.clone())
-                    .expect("Failed to set proxy in http client"),
+                    .with_context(|| format!("Failed to set proxy: {}", proxy.url))?,
             );
         }
 
-        let mut client = ClientBuilder::new(builder.build().expect("Failed to build client"));
+        // Trust the linked CA bundles in addition to the webpki roots
+        for cert in ca_certs(upstream)? {
+            builder = builder.add_root_certificate(reqwest::Certificate::from_der(&cert)?);
+        }
+
+        // Present a client certificate for mutual TLS
+        if let Some(client_cert) = client_cert {
+            let identity =
+                reqwest::Identity::from_pem(client_cert.pem().as_bytes()).with_context(|| {
+                    format!(
+                        "Failed to read upstream client certificate: {} with key: {}",
+                        client_cert.cert.path, client_cert.key.path
+                    )
+                })?;
+            // the identity is parsed for rustls, which isn't the default backend when
+            // native-tls is enabled as well
+            builder = builder.use_rustls_tls().identity(identity);
+        }
+
+        let mut client = ClientBuilder::new(builder.build()?);
 
         if upstream.http_cache > 0 {
             client = client.with(Cache(HttpCache {
@@ -120,11 +191,24 @@ impl NativeHttp {
                 options: HttpCacheOptions::default(),
             }))
         }
-        Self {
-            client: client.build(),
-            http2_only: upstream.http2_only,
-            enable_telemetry: telemetry.export.is_some(),
-        }
+
+        Ok(client.build())
+    }
+
+    fn client_for(&self, request: &reqwest::Request) -> &ClientWithMiddleware {
+        request
+            .url()
+            .host_str()
+            .and_then(|host| self.host_clients.get(host))
+            .unwrap_or(&self.client)
+    }
+
+    fn stream_client_for(&self, request: &reqwest::Request) -> &StreamClient {
+        request
+            .url()
+            .host_str()
+            .and_then(|host| self.host_stream_clients.get(host))
+            .unwrap_or(&self.stream_client)
     }
 }
 
@@ -166,7 +250,7 @@ impl HttpIO for NativeHttp {
             request.version()
         );
         tracing::debug!("request: {:?}", request);
-        let response = self.client.execute(request).await;
+        let response = self.client_for(&request).execute(request).await;
         tracing::debug!("response: {:?}", response);
 
         req_counter.update(&response);
@@ -183,6 +267,21 @@ impl HttpIO for NativeHttp {
         )
         .await?)
     }
+
+    async fn execute_stream(&self, request: reqwest::Request) -> Result<Response<BodyStream>> {
+        let mut req_counter = RequestCounter::new(self.enable_telemetry, &request);
+
+        tracing::info!(
+            "{} {} {:?} (streaming)",
+            request.method(),
+            request.url(),
+            request.version()
+        );
+        let response = self.stream_client_for(&request).execute(request).await;
+        req_counter.update_stream(&response);
+
+        response
+    }
 }
 
 #[cfg(test)]
@@ -191,12 +290,33 @@ mod tests {
     use tokio;
 
     use super::*;
+    use crate::core::blueprint::Pem;
     use crate::core::http::Response;
 
     fn start_mock_server() -> httpmock::MockServer {
         httpmock::MockServer::start()
     }
 
+    fn pem(path: &str) -> Pem {
+        Pem {
+            path: path.to_string(),
+            content: std::fs::read_to_string(path).unwrap(),
+        }
+    }
+
+    fn identity(hosts: &[&str]) -> ClientIdentity {
+        ClientIdentity {
+            cert: pem(tailcall_fixtures::mtls::IDENTITY),
+            key: pem(tailcall_fixtures::mtls::IDENTITY_KEY),
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+        }
+    }
+
+    fn client_for<'a>(native_http: &'a NativeHttp, url: &str) -> &'a ClientWithMiddleware {
+        let request = reqwest::Request::new(Method::GET, url.parse().unwrap());
+        native_http.client_for(&request)
+    }
+
     async fn make_request(request_url: &str, native_http: &NativeHttp) -> Response<Bytes> {
         let request = reqwest::Request::new(Method::GET, request_url.parse().unwrap());
         let result = native_http.execute(request).await;
@@ -212,7 +332,7 @@ mod tests {
             then.status(200).body("Hello");
         });
 
-        let native_http = NativeHttp::init(&Default::default(), &Default::default());
+        let native_http = NativeHttp::init(&Default::default(), &Default::default()).unwrap();
         let port = server.port();
         // Build a GET request to the mock server
         let request_url = format!("http://localhost:{}/test", port);
@@ -254,7 +374,7 @@ mod tests {
         });
 
         let upstream = Upstream { http_cache: 2, ..Default::default() };
-        let native_http = NativeHttp::init(&upstream, &Default::default());
+        let native_http = NativeHttp::init(&upstream, &Default::default()).unwrap();
         let port = server.port();
 
         let url1 = format!("http://localhost:{}/test-1", port);
@@ -282,4 +402,63 @@ mod tests {
         let resp = make_request(&url1, &native_http).await;
         assert_eq!(resp.headers.get("x-cache-lookup").unwrap(), "MISS");
     }
+
+    #[test]
+    fn test_client_for_host() {
+        let upstream = Upstream::default().client_certs(vec![identity(&["payments.internal"])]);
+        let native_http = NativeHttp::init(&upstream, &Default::default()).unwrap();
+
+        assert!(std::ptr::eq(
+            client_for(&native_http, "https://payments.internal/charges"),
+            &native_http.host_clients["payments.internal"]
+        ));
+        assert!(std::ptr::eq(
+            client_for(&native_http, "https://orders.internal/orders"),
+            &native_http.client
+        ));
+    }
+
+    #[test]
+    fn test_client_for_without_host_clients() {
+        let native_http = NativeHttp::init(&Default::default(), &Default::default()).unwrap();
+
+        assert!(std::ptr::eq(
+            client_for(&native_http, "https://payments.internal/charges"),
+            &native_http.client
+        ));
+    }
+
+    #[test]
+    fn test_init_invalid_ca_bundle() {
+        let upstream = Upstream::default().ca_certs(vec![Pem {
+            path: "ca.pem".to_string(),
+            content: "-----BEGIN CERTIFICATE-----\n???\n-----END CERTIFICATE-----\n".to_string(),
+        }]);
+        let error = NativeHttp::init(&upstream, &Default::default())
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "Failed to read upstream CA bundle: ca.pem"
+        );
+    }
+
+    #[test]
+    fn test_init_invalid_client_cert() {
+        let mut client_cert = identity(&[]);
+        client_cert.key = Pem { path: "client.key".to_string(), content: String::new() };
+        let upstream = Upstream::default().client_certs(vec![client_cert]);
+        let error = NativeHttp::init(&upstream, &Default::default())
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Failed to read upstream client certificate: {} with key: client.key",
+                tailcall_fixtures::mtls::IDENTITY
+            )
+        );
+    }
 }
@@ -0,0 +1,435 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures_util::stream::{self, StreamExt};
+use hyper::body::{Bytes, HttpBody};
+use hyper::client::connect::{Connected, Connection};
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderValue, USER_AGENT};
+use hyper::http::uri::Scheme;
+use hyper::service::Service;
+use hyper::{Body, Client, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use rustls_pki_types::{CertificateDer, Der, ServerName, TrustAnchor, UnixTime};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{
+    ring, verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms,
+};
+use tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme,
+};
+
+use super::http::ca_certs;
+use crate::core::blueprint::{ClientIdentity, Upstream};
+use crate::core::config::PrivateKey;
+use crate::core::http::Response;
+use crate::core::{BodyFrame, BodyStream};
+
+/// Client for responses that are consumed while they arrive. Unlike reqwest,
+/// hyper returns the trailers of a response, which carry the status of native
+/// gRPC calls.
+#[derive(Clone)]
+pub struct StreamClient {
+    client: Client<HttpsConnector<ProxyConnector>>,
+    user_agent: Option<HeaderValue>,
+    timeout: Duration,
+}
+
+impl StreamClient {
+    pub fn try_new(upstream: &Upstream, client_cert: Option<&ClientIdentity>) -> Result<Self> {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_keepalive(Some(Duration::from_secs(upstream.tcp_keep_alive)));
+        http.set_connect_timeout(Some(Duration::from_secs(upstream.connect_timeout)));
+
+        let proxy = upstream
+            .proxy
+            .as_ref()
+            .map(|proxy| {
+                proxy
+                    .url
+                    .parse::<Uri>()
+                    .with_context(|| format!("Failed to set proxy: {}", proxy.url))
+            })
+            .transpose()?;
+
+        let connector = HttpsConnectorBuilder::new()
+            .with_tls_config(tls_config(upstream, client_cert)?)
+            .https_or_http()
+            .enable_all_versions()
+            .wrap_connector(ProxyConnector { http, proxy });
+
+        let client = Client::builder()
+            .http2_only(upstream.http2_only)
+            .http2_keep_alive_interval(Some(Duration::from_secs(upstream.keep_alive_interval)))
+            .http2_keep_alive_timeout(Duration::from_secs(upstream.keep_alive_timeout))
+            .http2_keep_alive_while_idle(upstream.keep_alive_while_idle)
+            .pool_idle_timeout(Some(Duration::from_secs(upstream.pool_idle_timeout)))
+            .pool_max_idle_per_host(upstream.pool_max_idle_per_host)
+            .build(connector);
+
+        Ok(Self {
+            client,
+            user_agent: HeaderValue::from_str(&upstream.user_agent).ok(),
+            timeout: Duration::from_secs(upstream.timeout),
+        })
+    }
+
+    /// Sends the request and returns the body as it arrives, followed by the
+    /// trailers of the response. The upstream timeout only applies until the
+    /// response starts, streams are consumed for as long as they are open.
+    /// Error statuses are returned as well, their body is parsed by the
+    /// protocol of the call.
+    pub async fn execute(&self, request: reqwest::Request) -> Result<Response<BodyStream>> {
+        let mut hyper_request = hyper::Request::new(Body::from(
+            request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(Bytes::copy_from_slice)
+                .unwrap_or_default(),
+        ));
+        *hyper_request.method_mut() = request.method().clone();
+        *hyper_request.uri_mut() = request.url().as_str().parse()?;
+        *hyper_request.headers_mut() = request.headers().clone();
+        if let Some(user_agent) = &self.user_agent {
+            hyper_request
+                .headers_mut()
+                .entry(USER_AGENT)
+                .or_insert_with(|| user_agent.clone());
+        }
+
+        let response = tokio::time::timeout(self.timeout, self.client.request(hyper_request))
+            .await
+            .context("Upstream request timed out")??;
+
+        let (parts, body) = response.into_parts();
+        let body = stream::unfold(Some(body), |body| async move {
+            let mut body = body?;
+            match body.data().await {
+                Some(chunk) => {
+                    let frame = chunk.map(BodyFrame::Data).map_err(anyhow::Error::from);
+                    Some((frame, Some(body)))
+                }
+                None => match body.trailers().await {
+                    Ok(Some(trailers)) => Some((Ok(BodyFrame::Trailers(trailers)), None)),
+                    Ok(None) => None,
+                    Err(err) => Some((Err(err.into()), None)),
+                },
+            }
+        });
+
+        Ok(Response {
+            status: parts.status,
+            headers: parts.headers,
+            body: body.boxed(),
+        })
+    }
+}
+
+/// Sends the requests to `http` URLs through the proxy, like the
+/// `reqwest::Proxy::http` of the reqwest client of the same upstream.
+#[derive(Clone)]
+struct ProxyConnector {
+    http: HttpConnector,
+    proxy: Option<Uri>,
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = ProxyStream;
+    type Error = <HttpConnector as Service<Uri>>::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<ProxyStream, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let proxy = self
+            .proxy
+            .clone()
+            .filter(|_| dst.scheme() == Some(&Scheme::HTTP));
+        let proxied = proxy.is_some();
+        let connecting = self.http.call(proxy.unwrap_or(dst));
+
+        Box::pin(async move { Ok(ProxyStream { stream: connecting.await?, proxied }) })
+    }
+}
+
+/// Connection to the upstream or to its proxy, requests sent to the proxy
+/// use the absolute URL of the upstream.
+struct ProxyStream {
+    stream: TcpStream,
+    proxied: bool,
+}
+
+impl Connection for ProxyStream {
+    fn connected(&self) -> Connected {
+        self.stream.connected().proxy(self.proxied)
+    }
+}
+
+impl AsyncRead for ProxyStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ProxyStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+/// Trusts the webpki roots and the linked CA bundles, like the reqwest client
+/// of the same upstream.
+fn tls_config(upstream: &Upstream, client_cert: Option<&ClientIdentity>) -> Result<ClientConfig> {
+    let builder = if upstream.verify_ssl {
+        let mut roots = RootCertStore::empty();
+        roots.extend(
+            webpki_roots::TLS_SERVER_ROOTS
+                .iter()
+                .map(|root| TrustAnchor {
+                    subject: Der::from_slice(root.subject),
+                    subject_public_key_info: Der::from_slice(root.spki),
+                    name_constraints: root.name_constraints.map(Der::from_slice),
+                }),
+        );
+        roots.add_parsable_certificates(ca_certs(upstream)?);
+
+        ClientConfig::builder().with_root_certificates(roots)
+    } else {
+        ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoVerifier(
+                ring::default_provider().signature_verification_algorithms,
+            )))
+    };
+
+    let config = match client_cert {
+        Some(client_cert) => {
+            let context = || {
+                format!(
+                    "Failed to read upstream client certificate: {} with key: {}",
+                    client_cert.cert.path, client_cert.key.path
+                )
+            };
+            let certs = rustls_pemfile::certs(&mut client_cert.cert.content.as_bytes())
+                .with_context(context)?
+                .into_iter()
+                .map(CertificateDer::from)
+                .collect();
+            let key = PrivateKey::from_pem(&client_cert.key.content)
+                .with_context(context)?
+                .into_iter()
+                .next()
+                .with_context(context)?;
+
+            builder
+                .with_client_auth_cert(certs, key.into_inner())
+                .with_context(context)?
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    Ok(config)
+}
+
+/// Accepts any server certificate, used when `verifySSL` is disabled.
+#[derive(Debug)]
+struct NoVerifier(WebPkiSupportedAlgorithms);
+
+impl ServerCertVerifier for NoVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        verify_tls12_signature(message, cert, dss, &self.0)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        verify_tls13_signature(message, cert, dss, &self.0)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+
+    use futures_util::TryStreamExt;
+    use hyper::header::HeaderMap;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Server, StatusCode};
+    use reqwest::Method;
+
+    use super::*;
+
+    /// Starts an HTTP/2 server answering every request with `body` followed
+    /// by a `grpc-status: 0` trailer.
+    async fn start_server(body: &'static [u8]) -> SocketAddr {
+        let make_service = make_service_fn(move |_| async move {
+            Ok::<_, Infallible>(service_fn(move |_| async move {
+                let (mut sender, response_body) = Body::channel();
+                tokio::spawn(async move {
+                    sender.send_data(Bytes::from_static(body)).await.unwrap();
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert("grpc-status", HeaderValue::from_static("0"));
+                    sender.send_trailers(trailers).await.unwrap();
+                });
+                Ok::<_, Infallible>(hyper::Response::new(response_body))
+            }))
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into())
+            .http2_only(true)
+            .serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_trailers() {
+        let addr = start_server(b"message").await;
+        let upstream = Upstream::default().http2_only(true);
+        let client = StreamClient::try_new(&upstream, None).unwrap();
+        let request = reqwest::Request::new(
+            Method::POST,
+            format!("http://{addr}/greetings.Greeter/SayHello")
+                .parse()
+                .unwrap(),
+        );
+
+        let frames = client
+            .execute(request)
+            .await
+            .unwrap()
+            .body
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert!(
+            matches!(&frames[..], [BodyFrame::Data(data), BodyFrame::Trailers(trailers)]
+            if data == "message" && trailers["grpc-status"] == "0")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_returns_error_status() {
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|_| async {
+                let mut response = hyper::Response::new(Body::from("{\"code\":\"not_found\"}"));
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                Ok::<_, Infallible>(response)
+            }))
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let client = StreamClient::try_new(&Upstream::default(), None).unwrap();
+        let request =
+            reqwest::Request::new(Method::POST, format!("http://{addr}/").parse().unwrap());
+        let response = client.execute(request).await.unwrap();
+        let frames = response.body.try_collect::<Vec<_>>().await.unwrap();
+
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert!(
+            matches!(&frames[..], [BodyFrame::Data(data)] if data == "{\"code\":\"not_found\"}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_through_proxy() {
+        // the proxy answers with the URL it was asked for
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|request: hyper::Request<Body>| async move {
+                Ok::<_, Infallible>(hyper::Response::new(Body::from(request.uri().to_string())))
+            }))
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let upstream = Upstream {
+            proxy: Some(crate::core::blueprint::Proxy { url: format!("http://{addr}") }),
+            ..Default::default()
+        };
+        let client = StreamClient::try_new(&upstream, None).unwrap();
+        let request = reqwest::Request::new(
+            Method::GET,
+            "http://upstream.invalid/users".parse().unwrap(),
+        );
+
+        let frames = client
+            .execute(request)
+            .await
+            .unwrap()
+            .body
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert!(
+            matches!(&frames[..], [BodyFrame::Data(data)] if data == "http://upstream.invalid/users")
+        );
+    }
+
+    #[test]
+    fn test_invalid_proxy() {
+        let upstream = Upstream {
+            proxy: Some(crate::core::blueprint::Proxy { url: "http://proxy host".into() }),
+            ..Default::default()
+        };
+
+        assert!(StreamClient::try_new(&upstream, None).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 mod env;
 mod file;
 mod http;
+mod http_stream;
 
 use std::fs;
 use std::hash::Hash;
@@ -50,39 +51,40 @@ fn init_resolver_worker_io(
 }
 
 // Provides access to http in native rust environment
-fn init_http(blueprint: &Blueprint) -> Arc<dyn HttpIO> {
-    Arc::new(http::NativeHttp::init(
+fn init_http(blueprint: &Blueprint) -> anyhow::Result<Arc<dyn HttpIO>> {
+    Ok(Arc::new(http::NativeHttp::init(
         &blueprint.upstream,
         &blueprint.telemetry,
-    ))
+    )?))
 }
 
 // Provides access to http in native rust environment
-fn init_http2_only(blueprint: &Blueprint) -> Arc<dyn HttpIO> {
-    Arc::new(http::NativeHttp::init(
+fn init_http2_only(blueprint: &Blueprint) -> anyhow::Result<Arc<dyn HttpIO>> {
+    Ok(Arc::new(http::NativeHttp::init(
         &blueprint.upstream.clone().http2_only(true),
         &blueprint.telemetry,
-    ))
+    )?))
 }
 
 fn init_in_memory_cache<K: Hash + Eq, V: Clone>() -> InMemoryCache<K, V> {
     InMemoryCache::default()
 }
 
-pub fn init(blueprint: &Blueprint) -> TargetRuntime {
+pub fn init(blueprint: &Blueprint) -> anyhow::Result<TargetRuntime> {
     #[cfg(not(feature = "js"))]
     tracing::warn!("JS capabilities are disabled in this build");
 
-    TargetRuntime {
-        http: init_http(blueprint),
-        http2_only: init_http2_only(blueprint),
+    Ok(TargetRuntime {
+        http: init_http(blueprint)?,
+        http2_only: init_http2_only(blueprint)?,
         env: init_env(),
         file: init_file(),
         cache: Arc::new(init_in_memory_cache()),
+        oauth2_tokens: Default::default(),
         extensions: Arc::new(vec![]),
         cmd_worker: init_http_worker_io(blueprint.server.script.clone()),
         worker: init_resolver_worker_io(blueprint.server.script.clone()),
-    }
+    })
 }
 
 pub async fn confirm_and_write(
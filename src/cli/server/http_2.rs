@@ -1,25 +1,35 @@
 #![allow(clippy::too_many_arguments)]
+use std::collections::HashSet;
 use std::sync::Arc;
 
-use hyper::server::conn::AddrIncoming;
+use hyper::server::conn::{AddrIncoming, Http};
 use hyper::service::{make_service_fn, service_fn};
-use hyper::Server;
+use hyper::{Body, Request, Server};
 use hyper_rustls::TlsAcceptor;
-use rustls_pki_types::CertificateDer;
+use rustls_pki_types::{CertificateDer, UnixTime};
+use tokio::net::TcpListener;
 use tokio::sync::oneshot;
+use tokio_rustls::rustls::server::danger::ClientCertVerifier;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig as TlsConfig};
 
 use super::server_config::ServerConfig;
 use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
 use crate::core::config::PrivateKey;
-use crate::core::http::handle_request;
+use crate::core::http::{handle_request, subject_names, ClientCert};
 use crate::core::Errata;
 
 pub async fn start_http_2(
     sc: Arc<ServerConfig>,
     cert: Vec<CertificateDer<'static>>,
     key: PrivateKey,
+    client_ca: Vec<Vec<CertificateDer<'static>>>,
     server_up_sender: Option<oneshot::Sender<()>>,
 ) -> anyhow::Result<()> {
+    if !client_ca.is_empty() {
+        return start_http_2_with_client_auth(sc, cert, key, client_ca, server_up_sender).await;
+    }
+
     let addr = sc.addr();
     let incoming = AddrIncoming::bind(&addr)?;
     let acceptor = TlsAcceptor::builder()
@@ -65,3 +75,191 @@ pub async fn start_http_2(
 
     Ok(result?)
 }
+
+/// Verifies client certificates against the CA bundle of one `@link(type:
+/// ClientCA)`, so that a chain is only trusted by the providers of the bundles
+/// it was issued by.
+struct ClientCa {
+    verifier: Arc<dyn ClientCertVerifier>,
+    authorities: HashSet<String>,
+}
+
+impl ClientCa {
+    fn try_new(bundle: Vec<CertificateDer<'static>>) -> anyhow::Result<Self> {
+        let authorities = subject_names(&bundle);
+        let mut roots = RootCertStore::empty();
+        for ca in bundle {
+            roots.add(ca)?;
+        }
+        let verifier = WebPkiClientVerifier::builder(Arc::new(roots)).build()?;
+
+        Ok(Self { verifier, authorities })
+    }
+}
+
+/// Names of the authorities of the CA bundles the chain verifies against.
+fn verified_issuers(client_cas: &[ClientCa], chain: &[CertificateDer<'_>]) -> Vec<String> {
+    let Some((leaf, intermediates)) = chain.split_first() else {
+        return vec![];
+    };
+    let now = UnixTime::now();
+
+    client_cas
+        .iter()
+        .filter(|client_ca| {
+            client_ca
+                .verifier
+                .verify_client_cert(leaf, intermediates, now)
+                .is_ok()
+        })
+        .flat_map(|client_ca| client_ca.authorities.iter().cloned())
+        .collect()
+}
+
+/// Serves HTTP2 while requesting a certificate from every client. Presented
+/// certificates are verified against every bundle of `client_ca` during the
+/// handshake, and against each bundle separately to find the authorities the
+/// identity attached to the requests of the connection is issued by. Clients
+/// without a certificate are still accepted so that only `@protected` fields
+/// require one.
+async fn start_http_2_with_client_auth(
+    sc: Arc<ServerConfig>,
+    cert: Vec<CertificateDer<'static>>,
+    key: PrivateKey,
+    client_ca: Vec<Vec<CertificateDer<'static>>>,
+    server_up_sender: Option<oneshot::Sender<()>>,
+) -> anyhow::Result<()> {
+    let mut roots = RootCertStore::empty();
+    for ca in client_ca.iter().flatten() {
+        roots.add(ca.clone())?;
+    }
+
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+        .allow_unauthenticated()
+        .build()?;
+    let client_cas = Arc::new(
+        client_ca
+            .into_iter()
+            .map(ClientCa::try_new)
+            .collect::<anyhow::Result<Vec<_>>>()?,
+    );
+
+    let mut tls_config = TlsConfig::builder()
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(cert, key.into_inner())?;
+    tls_config.alpn_protocols = vec![b"h2".to_vec()];
+
+    let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(tls_config));
+    let listener = TcpListener::bind(sc.addr()).await?;
+
+    super::log_launch(sc.as_ref());
+
+    if let Some(sender) = server_up_sender {
+        sender
+            .send(())
+            .or(Err(anyhow::anyhow!("Failed to send message")))?;
+    }
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                tracing::warn!("Failed to accept connection: {}", err);
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let state = Arc::clone(&sc);
+        let client_cas = client_cas.clone();
+
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    tracing::debug!("TLS handshake failed: {}", err);
+                    return;
+                }
+            };
+
+            let client_cert = stream.get_ref().1.peer_certificates().and_then(|chain| {
+                ClientCert::from_chain(chain, verified_issuers(&client_cas, chain))
+            });
+            let enable_batch_requests = state.blueprint.server.enable_batch_requests;
+
+            let service = service_fn(move |mut req: Request<Body>| {
+                if let Some(client_cert) = client_cert.clone() {
+                    req.extensions_mut().insert(client_cert);
+                }
+
+                let app_ctx = state.app_ctx.clone();
+                async move {
+                    if enable_batch_requests {
+                        handle_request::<GraphQLBatchRequest>(req, app_ctx).await
+                    } else {
+                        handle_request::<GraphQLRequest>(req, app_ctx).await
+                    }
+                }
+            });
+
+            if let Err(err) = Http::new()
+                .http2_only(true)
+                .serve_connection(stream, service)
+                .await
+            {
+                tracing::debug!("Failed to serve connection: {}", err);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rustls_pki_types::CertificateDer;
+    use tailcall_fixtures::mtls;
+
+    use super::{verified_issuers, ClientCa};
+    use crate::core::http::ClientCert;
+
+    fn certs(path: &str) -> Vec<CertificateDer<'static>> {
+        let content = std::fs::read(path).unwrap();
+        rustls_pemfile::certs(&mut content.as_slice())
+            .unwrap()
+            .into_iter()
+            .map(CertificateDer::from)
+            .collect()
+    }
+
+    fn client_cas() -> Vec<ClientCa> {
+        vec![
+            ClientCa::try_new(certs(mtls::CA_A)).unwrap(),
+            ClientCa::try_new(certs(mtls::CA_B)).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_verified_issuers() {
+        let chain = certs(mtls::CLIENT_A);
+        let issuers = verified_issuers(&client_cas(), &chain);
+
+        assert_eq!(issuers, vec!["CN=CA A".to_string()]);
+    }
+
+    #[test]
+    fn test_verified_issuers_ignores_spoofed_certs() {
+        let mut chain = certs(mtls::CLIENT_A);
+        chain.extend(certs(mtls::SPOOFED_B));
+
+        let issuers = verified_issuers(&client_cas(), &chain);
+        let client_cert = ClientCert::from_chain(&chain, issuers).unwrap();
+
+        assert!(client_cert.is_issued_by(&HashSet::from(["CN=CA A".to_string()])));
+        assert!(!client_cert.is_issued_by(&HashSet::from(["CN=CA B".to_string()])));
+    }
+
+    #[test]
+    fn test_verified_issuers_without_chain() {
+        assert!(verified_issuers(&client_cas(), &[]).is_empty());
+    }
+}
@@ -10,6 +10,7 @@ use super::server_config::ServerConfig;
 use crate::cli::telemetry::init_opentelemetry;
 use crate::core::blueprint::{Blueprint, Http};
 use crate::core::config::ConfigModule;
+use crate::core::proto_reader::DescriptorRefresh;
 use crate::core::Errata;
 
 pub struct Server {
@@ -37,10 +38,15 @@ impl Server {
         let server_config = Arc::new(ServerConfig::new(blueprint.clone(), endpoints).await?);
 
         init_opentelemetry(blueprint.telemetry.clone(), &server_config.app_ctx.runtime)?;
+        DescriptorRefresh::new(self.config_module.clone(), &server_config.app_ctx).start();
+        if let Some(grpc_health) = server_config.app_ctx.grpc_health.clone() {
+            let runtime = server_config.app_ctx.runtime.clone();
+            grpc_health.start(runtime, blueprint.server.vars.clone());
+        }
 
         match blueprint.server.http.clone() {
-            Http::HTTP2 { cert, key } => {
-                start_http_2(server_config, cert, key, self.server_up_sender).await
+            Http::HTTP2 { cert, key, client_ca } => {
+                start_http_2(server_config, cert, key, client_ca, self.server_up_sender).await
             }
             Http::HTTP1 => start_http_1(server_config, self.server_up_sender).await,
         }
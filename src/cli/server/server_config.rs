@@ -20,7 +20,7 @@ impl ServerConfig {
         blueprint: Blueprint,
         endpoints: EndpointSet<Unchecked>,
     ) -> anyhow::Result<Self> {
-        let mut rt = init(&blueprint);
+        let mut rt = init(&blueprint)?;
 
         let mut extensions = vec![];
 
@@ -37,7 +37,7 @@ impl ServerConfig {
         rt.add_extensions(extensions);
 
         let endpoints = endpoints.into_checked(&blueprint, rt.clone()).await?;
-        let app_context = Arc::new(AppContext::new(blueprint.clone(), rt, endpoints));
+        let app_context = Arc::new(AppContext::new(blueprint.clone(), rt, endpoints)?);
 
         Ok(Self { app_ctx: app_context, blueprint })
     }
@@ -48,7 +48,7 @@ impl ServerConfig {
 
     pub fn http_version(&self) -> String {
         match self.blueprint.server.http {
-            Http::HTTP2 { cert: _, key: _ } => "HTTP/2".to_string(),
+            Http::HTTP2 { .. } => "HTTP/2".to_string(),
             _ => "HTTP/1.1".to_string(),
         }
     }
@@ -1,6 +1,6 @@
 use anyhow::Result;
 
-use super::helpers::{display_schema, log_endpoint_set};
+use super::helpers::{display_openapi, display_proto, display_schema, log_endpoint_set};
 use crate::cli::fmt::Fmt;
 use crate::core::blueprint::Blueprint;
 use crate::core::config::reader::ConfigReader;
@@ -11,11 +11,20 @@ pub(super) struct CheckParams {
     pub(super) file_paths: Vec<String>,
     pub(super) n_plus_one_queries: bool,
     pub(super) schema: bool,
+    pub(super) proto: bool,
+    pub(super) openapi: bool,
     pub(super) runtime: TargetRuntime,
 }
 
 pub(super) async fn check_command(params: CheckParams, config_reader: &ConfigReader) -> Result<()> {
-    let CheckParams { file_paths, n_plus_one_queries, schema, runtime } = params;
+    let CheckParams {
+        file_paths,
+        n_plus_one_queries,
+        schema,
+        proto,
+        openapi,
+        runtime,
+    } = params;
 
     let config_module = (config_reader.read_all(&file_paths)).await?;
     log_endpoint_set(&config_module.extensions().endpoint_set);
@@ -26,7 +35,7 @@ pub(super) async fn check_command(params: CheckParams, config_reader: &ConfigRea
             tracing::info!("Config {} ... ok", file_paths.join(", "));
             Fmt::log_n_plus_one(n_plus_one_queries, config_module.config());
             // Check the endpoints' schema
-            let _ = config_module
+            let endpoints = config_module
                 .extensions()
                 .endpoint_set
                 .clone()
@@ -35,6 +44,12 @@ pub(super) async fn check_command(params: CheckParams, config_reader: &ConfigRea
             if schema {
                 display_schema(&blueprint);
             }
+            if proto {
+                display_proto(&blueprint);
+            }
+            if openapi {
+                display_openapi(&blueprint, &endpoints)?;
+            }
 
             Ok(())
         }
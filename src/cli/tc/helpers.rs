@@ -2,9 +2,10 @@ use lazy_static::lazy_static;
 
 use crate::cli::fmt::Fmt;
 use crate::core::blueprint::Blueprint;
+use crate::core::grpc::service::proto::ProtoFile;
 use crate::core::http::API_URL_PREFIX;
 use crate::core::print_schema;
-use crate::core::rest::{EndpointSet, Unchecked};
+use crate::core::rest::{Checked, EndpointSet, OpenApi, Unchecked};
 
 pub const TAILCALL_RC: &str = ".tailcallrc.graphql";
 pub const GRAPHQL_RC: &str = ".graphqlrc.yml";
@@ -40,3 +41,20 @@ pub(super) fn display_schema(blueprint: &Blueprint) {
     let sdl = blueprint.to_schema();
     Fmt::display(format!("{}\n", print_schema::print_schema(sdl)));
 }
+
+pub(super) fn display_proto(blueprint: &Blueprint) {
+    Fmt::display(Fmt::heading("gRPC Service:\n"));
+    let options = blueprint.server.grpc_service.clone().unwrap_or_default();
+    let proto = ProtoFile::new(blueprint, &options);
+    Fmt::display(proto.to_proto_string());
+}
+
+pub(super) fn display_openapi(
+    blueprint: &Blueprint,
+    endpoints: &EndpointSet<Checked>,
+) -> anyhow::Result<()> {
+    Fmt::display(Fmt::heading("OpenAPI:\n"));
+    let openapi = OpenApi::new(blueprint, endpoints);
+    Fmt::display(serde_json::to_string_pretty(&openapi)?);
+    Ok(())
+}
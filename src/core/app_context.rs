@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use anyhow::Context;
 use async_graphql::dynamic::{self, DynamicRequest};
 use async_graphql_value::ConstValue;
 use dashmap::DashMap;
@@ -11,11 +12,13 @@ use crate::core::data_loader::{DataLoader, DedupeResult};
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc;
 use crate::core::grpc::data_loader::GrpcDataLoader;
+use crate::core::grpc::health::GrpcHealth;
+use crate::core::grpc::service::GrpcServer;
 use crate::core::http::{DataLoaderRequest, HttpDataLoader};
 use crate::core::ir::model::{DataLoaderId, IoId, IO, IR};
 use crate::core::ir::Error;
-use crate::core::jit::{OPHash, OperationPlan};
-use crate::core::rest::{Checked, EndpointSet};
+use crate::core::jit::{OPHash, OperationPlan, ProtectedSchema};
+use crate::core::rest::{Checked, EndpointSet, OpenApi};
 use crate::core::runtime::TargetRuntime;
 
 pub struct AppContext {
@@ -26,10 +29,14 @@ pub struct AppContext {
     pub gql_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, GraphqlDataLoader>>>,
     pub grpc_data_loaders: Arc<Vec<DataLoader<grpc::DataLoaderRequest, GrpcDataLoader>>>,
     pub endpoints: EndpointSet<Checked>,
+    pub openapi: OpenApi,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
     pub dedupe_operation_handler: DedupeResult<OperationId, AnyResponse<Vec<u8>>, Error>,
     pub operation_plans: DashMap<OPHash, OperationPlan<async_graphql_value::Value>>,
     pub const_execution_cache: DashMap<OPHash, AnyResponse<Vec<u8>>>,
+    pub protected_schema: ProtectedSchema,
+    pub grpc_server: Option<Arc<GrpcServer>>,
+    pub grpc_health: Option<Arc<GrpcHealth>>,
 }
 
 impl AppContext {
@@ -37,7 +44,7 @@ impl AppContext {
         mut blueprint: Blueprint,
         runtime: TargetRuntime,
         endpoints: EndpointSet<Checked>,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let mut http_data_loaders = vec![];
         let mut gql_data_loaders = vec![];
         let mut grpc_data_loaders = vec![];
@@ -59,6 +66,7 @@ impl AppContext {
                                         group_by.clone(),
                                         is_list,
                                     )
+                                    .with_oauth2(req_template.oauth2.clone())
                                     .to_data_loader(upstream_batch.clone().unwrap_or_default());
 
                                     let result = Some(IR::IO(IO::Http {
@@ -79,6 +87,7 @@ impl AppContext {
                                     let dedupe = *dedupe;
                                     let graphql_data_loader =
                                         GraphqlDataLoader::new(runtime.clone(), *batch)
+                                            .with_oauth2(req_template.oauth2.clone())
                                             .into_data_loader(
                                                 upstream_batch.clone().unwrap_or_default(),
                                             );
@@ -102,6 +111,7 @@ impl AppContext {
                                         runtime: runtime.clone(),
                                         operation: req_template.operation.clone(),
                                         group_by: group_by.clone(),
+                                        oauth2: req_template.oauth2.clone(),
                                     };
                                     let data_loader = data_loader.into_data_loader(
                                         upstream_batch.clone().unwrap_or_default(),
@@ -133,7 +143,30 @@ impl AppContext {
         let schema = blueprint
             .to_schema_with(SchemaModifiers::default().extensions(runtime.extensions.clone()));
 
-        AppContext {
+        let protected_schema = ProtectedSchema::new(
+            &blueprint,
+            blueprint.server.get_enable_introspection_filter(),
+        );
+
+        let openapi = OpenApi::new(&blueprint, &endpoints);
+
+        let grpc_server = blueprint
+            .server
+            .grpc_service
+            .as_ref()
+            .map(|options| GrpcServer::try_new(&blueprint, options))
+            .transpose()
+            .context("Failed to create the gRPC service")?
+            .map(Arc::new);
+
+        let grpc_health = Some(&blueprint.upstream.grpc_health_checks)
+            .filter(|checks| !checks.is_empty())
+            .map(|checks| GrpcHealth::try_new(checks))
+            .transpose()
+            .context("Failed to create the health checks")?
+            .map(Arc::new);
+
+        Ok(AppContext {
             schema,
             runtime,
             blueprint,
@@ -141,12 +174,16 @@ impl AppContext {
             gql_data_loaders: Arc::new(gql_data_loaders),
             grpc_data_loaders: Arc::new(grpc_data_loaders),
             endpoints,
+            openapi,
 
             dedupe_handler: Arc::new(DedupeResult::new(false)),
             dedupe_operation_handler: DedupeResult::new(false),
             operation_plans: DashMap::new(),
             const_execution_cache: DashMap::default(),
-        }
+            protected_schema,
+            grpc_server,
+            grpc_health,
+        })
     }
 
     pub async fn execute(&self, request: impl Into<DynamicRequest>) -> async_graphql::Response {
@@ -1,17 +1,26 @@
 use std::any::Any;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
-use anyhow::Result;
-use async_graphql::parser::types::{ExecutableDocument, OperationType};
-use async_graphql::{BatchResponse, Executor, Value};
+use anyhow::{anyhow, bail, Result};
+use async_graphql::parser::types::{DocumentOperations, ExecutableDocument, OperationType};
+use async_graphql::{BatchResponse, Executor, ServerError, Value};
+use futures_util::stream::{self, BoxStream, StreamExt};
 use http::header::{HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
 use http::{Response, StatusCode};
 use hyper::Body;
+use multer::{Constraints, Multipart, SizeLimit};
 use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tailcall_hasher::TailcallHasher;
 
+use super::config::{RestError, RestErrorFormat, Uploads};
+use super::grpc::status::StatusMapping;
+use super::http::multipart::Upload;
+use super::ir::{FORBIDDEN, UNAUTHENTICATED};
 use super::jit::{BatchResponse as JITBatchResponse, JITExecutor};
+use super::rest::ErrorMapping;
 
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
 pub struct OperationId(u64);
@@ -25,8 +34,17 @@ pub trait GraphQLRequestLike: Hash + Send {
 
     async fn execute_with_jit(self, executor: JITExecutor) -> GraphQLArcResponse;
 
+    /// Executes a subscription, every event of the stream is a response of
+    /// its own.
+    fn execute_stream<E>(self, executor: &E) -> BoxStream<'static, async_graphql::Response>
+    where
+        E: Executor;
+
     fn parse_query(&mut self) -> Option<&ExecutableDocument>;
 
+    /// Whether the executed operation is a subscription.
+    fn is_subscription(&mut self) -> bool;
+
     fn is_query(&mut self) -> bool {
         self.parse_query()
             .map(|a| {
@@ -90,9 +108,21 @@ impl GraphQLRequestLike for GraphQLBatchRequest {
         GraphQLResponse(executor.execute_batch(self.0).await)
     }
 
+    fn execute_stream<E>(self, _executor: &E) -> BoxStream<'static, async_graphql::Response>
+    where
+        E: Executor,
+    {
+        let error = ServerError::new("Subscriptions can't be sent in a batch", None);
+        stream::once(async move { async_graphql::Response::from_errors(vec![error]) }).boxed()
+    }
+
     fn parse_query(&mut self) -> Option<&ExecutableDocument> {
         None
     }
+
+    fn is_subscription(&mut self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -129,9 +159,29 @@ impl GraphQLRequestLike for GraphQLRequest {
         GraphQLResponse(executor.execute(self.0).await.into())
     }
 
+    fn execute_stream<E>(self, executor: &E) -> BoxStream<'static, async_graphql::Response>
+    where
+        E: Executor,
+    {
+        executor.execute_stream(self.0, None)
+    }
+
     fn parse_query(&mut self) -> Option<&ExecutableDocument> {
         self.0.parsed_query().ok()
     }
+
+    fn is_subscription(&mut self) -> bool {
+        let operation_name = self.0.operation_name.clone();
+        self.parse_query()
+            .and_then(|document| match (&document.operations, operation_name) {
+                (DocumentOperations::Single(operation), _) => Some(operation.node.ty),
+                (DocumentOperations::Multiple(operations), Some(name)) => operations
+                    .get(name.as_str())
+                    .map(|operation| operation.node.ty),
+                (DocumentOperations::Multiple(_), None) => None,
+            })
+            .is_some_and(|ty| ty == OperationType::Subscription)
+    }
 }
 
 // TODO: drop this type since we can use jit::response?
@@ -184,9 +234,78 @@ impl GraphQLQuery {
     }
 }
 
+/// Reads a request of the GraphQL multipart request spec
+/// (https://github.com/jaydenseric/graphql-multipart-request-spec), every file
+/// is set as an `Upload` value at its paths in the `map` field. The body is
+/// streamed and fails as soon as a file exceeds the limits.
+pub async fn read_multipart<T: DeserializeOwned>(
+    content_type: &str,
+    body: Body,
+    uploads: &Uploads,
+) -> Result<T> {
+    let boundary = multer::parse_boundary(content_type)?;
+    let size_limit = SizeLimit::new()
+        .whole_stream(uploads.get_max_request_size())
+        .per_field(uploads.get_max_file_size())
+        .for_field("operations", uploads.get_max_request_size())
+        .for_field("map", uploads.get_max_request_size());
+    let constraints = Constraints::new().size_limit(size_limit);
+    let mut multipart = Multipart::with_constraints(body, boundary, constraints);
+
+    let mut operations: Option<serde_json::Value> = None;
+    let mut map: Option<HashMap<String, Vec<String>>> = None;
+    let mut files = 0;
+
+    while let Some(field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "operations" => operations = Some(serde_json::from_slice(&field.bytes().await?)?),
+            "map" => map = Some(serde_json::from_slice(&field.bytes().await?)?),
+            _ => {
+                files += 1;
+                if files > uploads.get_max_files() {
+                    bail!(
+                        "Too many files, at most {} are accepted",
+                        uploads.get_max_files()
+                    );
+                }
+
+                // the spec requires `operations` and `map` to precede the files
+                let (Some(operations), Some(map)) = (operations.as_mut(), map.as_ref()) else {
+                    bail!("Fields `operations` and `map` must precede the files");
+                };
+                let paths = map
+                    .get(&name)
+                    .ok_or_else(|| anyhow!("File `{name}` is missing from `map`"))?;
+
+                let upload = Upload {
+                    filename: field.file_name().unwrap_or_default().to_string(),
+                    content_type: field.content_type().map(ToString::to_string),
+                    content: field.bytes().await?.to_vec(),
+                }
+                .to_value();
+
+                for path in paths {
+                    let pointer = format!("/{}", path.replace('.', "/"));
+                    *operations
+                        .pointer_mut(&pointer)
+                        .ok_or_else(|| anyhow!("Invalid path `{path}` of file `{name}`"))? =
+                        upload.clone();
+                }
+            }
+        }
+    }
+
+    let operations = operations.ok_or_else(|| anyhow!("Missing field `operations`"))?;
+    Ok(serde_json::from_value(operations)?)
+}
+
 static APPLICATION_JSON: Lazy<HeaderValue> =
     Lazy::new(|| HeaderValue::from_static("application/json"));
 
+static APPLICATION_PROBLEM_JSON: Lazy<HeaderValue> =
+    Lazy::new(|| HeaderValue::from_static("application/problem+json"));
+
 impl GraphQLResponse {
     fn build_response(&self, status: StatusCode, body: Body) -> Result<Response<Body>> {
         let mut response = Response::builder()
@@ -224,14 +343,30 @@ impl GraphQLResponse {
     /// Transforms a plain `GraphQLResponse` into a `Response<Body>`.
     /// Differs as `to_response` by flattening the response's data
     /// `{"data": {"user": {"name": "John"}}}` becomes `{"name": "John"}`.
-    pub fn into_rest_response(self) -> Result<Response<hyper::Body>> {
+    /// Errors raised by gRPC upstreams set the status code through
+    /// `grpc_status_mapping`, other errors through `rest_errors`. A `null`
+    /// root field is reported as not found only if `not_found_on_null` is set.
+    pub fn into_rest_response(
+        self,
+        grpc_status_mapping: &StatusMapping,
+        rest_errors: &ErrorMapping,
+        not_found_on_null: bool,
+    ) -> Result<Response<hyper::Body>> {
         if !self.0.is_ok() {
-            return self.build_response(StatusCode::INTERNAL_SERVER_ERROR, self.default_body()?);
+            let status = self
+                .grpc_code()
+                .map(|code| grpc_status_mapping.http_status(code))
+                .unwrap_or_else(|| rest_errors.http_status(self.rest_error()));
+            return self.build_error_response(status, rest_errors);
         }
 
         match self.0 {
             BatchResponse::Single(ref res) => {
                 let item = Self::flatten_response(&res.data);
+                if not_found_on_null && item == &Value::Null {
+                    let status = rest_errors.http_status(RestError::NotFound);
+                    return self.build_error_response(status, rest_errors);
+                }
                 let data = serde_json::to_string(item)?;
 
                 self.build_response(StatusCode::OK, Body::from(data))
@@ -248,6 +383,70 @@ impl GraphQLResponse {
         }
     }
 
+    fn build_error_response(
+        &self,
+        status: StatusCode,
+        rest_errors: &ErrorMapping,
+    ) -> Result<Response<Body>> {
+        match rest_errors.format() {
+            RestErrorFormat::Graphql => self.build_response(status, self.default_body()?),
+            RestErrorFormat::ProblemJson => {
+                let errors = serde_json::to_value(self.errors())?;
+                let problem = rest_errors.problem(status, &errors);
+                let mut response =
+                    self.build_response(status, Body::from(serde_json::to_string(&problem)?))?;
+                response
+                    .headers_mut()
+                    .insert(CONTENT_TYPE, APPLICATION_PROBLEM_JSON.clone());
+
+                Ok(response)
+            }
+        }
+    }
+
+    fn errors(&self) -> Vec<&ServerError> {
+        match &self.0 {
+            BatchResponse::Single(res) => res.errors.iter().collect(),
+            BatchResponse::Batch(list) => list.iter().flat_map(|res| &res.errors).collect(),
+        }
+    }
+
+    /// Status code of the first error that was raised by a gRPC upstream.
+    fn grpc_code(&self) -> Option<i32> {
+        self.errors().into_iter().find_map(|error| {
+            match error.extensions.as_ref()?.get("grpcCode")? {
+                Value::Number(code) => code.as_i64().map(|code| code as i32),
+                _ => None,
+            }
+        })
+    }
+
+    /// Kind of the failure, detected through the `code` extension of the
+    /// errors. Errors without a path were raised before the execution, e.g.
+    /// while coercing the variables of the request.
+    fn rest_error(&self) -> RestError {
+        let errors = self.errors();
+        let has_code = |code: &str| {
+            errors.iter().any(|error| {
+                error
+                    .extensions
+                    .as_ref()
+                    .and_then(|extensions| extensions.get("code"))
+                    .is_some_and(|value| matches!(value, Value::String(value) if value == code))
+            })
+        };
+
+        if has_code(UNAUTHENTICATED) {
+            RestError::Unauthenticated
+        } else if has_code(FORBIDDEN) {
+            RestError::Forbidden
+        } else if errors.iter().all(|error| error.path.is_empty()) {
+            RestError::BadRequest
+        } else {
+            RestError::Internal
+        }
+    }
+
     /// Sets the `cache_control` for a given `GraphQLResponse`.
     ///
     /// The function modifies the `GraphQLResponse` to set the `cache_control`
@@ -412,7 +611,7 @@ impl GraphQLArcResponse {
 
 #[cfg(test)]
 mod tests {
-    use async_graphql::{Name, Response, ServerError, Value};
+    use async_graphql::{Name, PathSegment, Response, ServerError, Value};
     use http::StatusCode;
     use indexmap::IndexMap;
     use serde_json::json;
@@ -427,7 +626,9 @@ mod tests {
         let data = IndexMap::from([(Name::new("user"), Value::Object(user))]);
 
         let response = GraphQLResponse(BatchResponse::Single(Response::new(Value::Object(data))));
-        let rest_response = response.into_rest_response().unwrap();
+        let rest_response = response
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
 
         assert_eq!(rest_response.status(), StatusCode::OK);
         assert_eq!(rest_response.headers()["content-type"], "application/json");
@@ -454,7 +655,9 @@ mod tests {
             .collect();
 
         let response = GraphQLResponse(BatchResponse::Batch(list));
-        let rest_response = response.into_rest_response().unwrap();
+        let rest_response = response
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
 
         assert_eq!(rest_response.status(), StatusCode::OK);
         assert_eq!(rest_response.headers()["content-type"], "application/json");
@@ -480,10 +683,16 @@ mod tests {
         let mut response: Response = Default::default();
         response.errors = errors
             .iter()
-            .map(|error| ServerError::new(error.to_string(), None))
+            .map(|error| {
+                let mut error = ServerError::new(error.to_string(), None);
+                error.path = vec![PathSegment::Field("user".to_string())];
+                error
+            })
             .collect();
         let response = GraphQLResponse(BatchResponse::Single(response));
-        let rest_response = response.into_rest_response().unwrap();
+        let rest_response = response
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
 
         assert_eq!(rest_response.status(), StatusCode::INTERNAL_SERVER_ERROR);
         assert_eq!(rest_response.headers()["content-type"], "application/json");
@@ -497,6 +706,7 @@ mod tests {
                 "errors": errors.iter().map(|error| {
                     json!({
                         "message": error,
+                        "path": ["user"],
                     })
                 }).collect::<Vec<_>>()
             })
@@ -506,6 +716,123 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_to_rest_response_with_request_error() {
+        let error = ServerError::new("Invalid value for argument \"id\"", None);
+        let response = GraphQLResponse(BatchResponse::Single(Response::from_errors(vec![error])));
+        let rest_response = response
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
+
+        assert_eq!(rest_response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_to_rest_response_not_found() {
+        let data = IndexMap::from([(Name::new("user"), Value::Null)]);
+        let response = || {
+            GraphQLResponse(BatchResponse::Single(Response::new(Value::Object(
+                data.clone(),
+            ))))
+        };
+
+        let rest_response = response()
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
+        assert_eq!(rest_response.status(), StatusCode::NOT_FOUND);
+
+        let mapping = ErrorMapping::new(
+            RestErrorFormat::Graphql,
+            [(RestError::NotFound, StatusCode::NO_CONTENT)],
+        );
+        let rest_response = response()
+            .into_rest_response(&StatusMapping::default(), &mapping, true)
+            .unwrap();
+        assert_eq!(rest_response.status(), StatusCode::NO_CONTENT);
+
+        let rest_response = response()
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), false)
+            .unwrap();
+        assert_eq!(rest_response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_to_rest_response_forbidden() {
+        let mut error = ServerError::new("Not allowed", None);
+        error.path = vec![PathSegment::Field("user".to_string())];
+        let mut extensions = async_graphql::ErrorExtensionValues::default();
+        extensions.set("code", "FORBIDDEN");
+        error.extensions = Some(extensions);
+        let response = GraphQLResponse(BatchResponse::Single(Response::from_errors(vec![error])));
+        let rest_response = response
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
+
+        assert_eq!(rest_response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_to_rest_response_problem_json() {
+        let mut error =
+            ServerError::new("Authentication Failure: Missing Authorization Header", None);
+        error.path = vec![PathSegment::Field("user".to_string())];
+        let mut extensions = async_graphql::ErrorExtensionValues::default();
+        extensions.set("code", "UNAUTHENTICATED");
+        error.extensions = Some(extensions);
+        let response = GraphQLResponse(BatchResponse::Single(Response::from_errors(vec![error])));
+        let mapping = ErrorMapping::new(RestErrorFormat::ProblemJson, []);
+        let rest_response = response
+            .into_rest_response(&StatusMapping::default(), &mapping, true)
+            .unwrap();
+
+        assert_eq!(rest_response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            rest_response.headers()["content-type"],
+            "application/problem+json"
+        );
+        let body = hyper::body::to_bytes(rest_response.into_body())
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            json!({
+                "type": "about:blank",
+                "title": "Unauthorized",
+                "status": 401,
+                "detail": "Authentication Failure: Missing Authorization Header",
+                "errors": [{
+                    "message": "Authentication Failure: Missing Authorization Header",
+                    "path": ["user"],
+                    "extensions": { "code": "UNAUTHENTICATED" }
+                }]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_to_rest_response_with_grpc_error() {
+        let response = || {
+            let mut error = ServerError::new("gRPC Error", None);
+            let mut extensions = async_graphql::ErrorExtensionValues::default();
+            extensions.set("grpcCode", tonic::Code::NotFound as i32);
+            error.extensions = Some(extensions);
+            let mut response: Response = Default::default();
+            response.errors = vec![error];
+            GraphQLResponse(BatchResponse::Single(response))
+        };
+
+        let rest_response = response()
+            .into_rest_response(&StatusMapping::default(), &ErrorMapping::default(), true)
+            .unwrap();
+        assert_eq!(rest_response.status(), StatusCode::NOT_FOUND);
+
+        let mapping = StatusMapping::new([(tonic::Code::NotFound, StatusCode::GONE)]);
+        let rest_response = response()
+            .into_rest_response(&mapping, &ErrorMapping::default(), true)
+            .unwrap();
+        assert_eq!(rest_response.status(), StatusCode::GONE);
+    }
+
     #[test]
     fn to_value() {
         assert_eq!(CacheControl { public: true, max_age: 0 }.value(), None);
@@ -535,4 +862,55 @@ mod tests {
             Some("no-cache, private".to_string())
         );
     }
+
+    const UPLOAD_BODY: &str = "--X\r\nContent-Disposition: form-data; name=\"operations\"\r\n\r\n\
+        {\"query\": \"mutation($file: Upload!) { upload(file: $file) }\", \"variables\": { \"file\": null }}\r\n\
+        --X\r\nContent-Disposition: form-data; name=\"map\"\r\n\r\n{\"0\": [\"variables.file\"]}\r\n\
+        --X\r\nContent-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\r\nhello\r\n\
+        --X--\r\n";
+
+    #[tokio::test]
+    async fn test_read_multipart() {
+        let request = read_multipart::<serde_json::Value>(
+            "multipart/form-data; boundary=X",
+            Body::from(UPLOAD_BODY),
+            &Uploads::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            request["variables"]["file"],
+            json!({ "filename": "a.txt", "contentType": "text/plain", "content": "aGVsbG8=" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_multipart_limits() {
+        let too_large = Uploads { max_file_size: Some(4), ..Default::default() };
+        let too_many = Uploads { max_files: Some(0), ..Default::default() };
+        let too_long = Uploads { max_request_size: Some(64), ..Default::default() };
+
+        let mut errors = vec![];
+        for uploads in [too_large, too_many, too_long] {
+            let error = read_multipart::<serde_json::Value>(
+                "multipart/form-data; boundary=X",
+                Body::from(UPLOAD_BODY),
+                &uploads,
+            )
+            .await
+            .unwrap_err();
+            errors.push(error.to_string());
+        }
+
+        assert_eq!(
+            errors,
+            vec![
+                "field \"0\" exceeded the size limit: 4 bytes",
+                "Too many files, at most 0 are accepted",
+                "stream size exceeded limit: 64 bytes"
+            ]
+        );
+    }
 }
@@ -1,5 +1,6 @@
 pub mod basic;
 pub mod error;
 pub mod jwt;
+pub mod mtls;
 mod verification;
 pub mod verify;
@@ -0,0 +1,78 @@
+use super::error::Error;
+use super::verification::Verification;
+use super::verify::Verify;
+use crate::core::blueprint;
+use crate::core::http::RequestContext;
+
+pub struct MtlsVerifier {
+    options: blueprint::Mtls,
+}
+
+impl MtlsVerifier {
+    pub fn new(options: blueprint::Mtls) -> Self {
+        Self { options }
+    }
+}
+
+#[async_trait::async_trait]
+impl Verify for MtlsVerifier {
+    /// Verify that the client presented a certificate issued by one of the
+    /// authorities of the provider. The chain itself is verified during the
+    /// TLS handshake.
+    async fn verify(&self, req_ctx: &RequestContext) -> Verification {
+        let Some(client_cert) = &req_ctx.client_cert else {
+            return Verification::fail(Error::Missing);
+        };
+
+        if client_cert.is_issued_by(&self.options.issuers) {
+            Verification::succeed()
+        } else {
+            Verification::fail(Error::Invalid)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::core::http::ClientCert;
+
+    fn setup_provider() -> MtlsVerifier {
+        MtlsVerifier::new(blueprint::Mtls {
+            issuers: HashSet::from(["CN=Internal CA".to_string()]),
+        })
+    }
+
+    fn create_mtls_request(issuer: &str) -> RequestContext {
+        RequestContext::default().client_cert(Some(ClientCert {
+            subject: "CN=orders".to_string(),
+            san: vec![],
+            issuers: vec![issuer.to_string()],
+        }))
+    }
+
+    #[tokio::test]
+    async fn verify_missing_certificate() {
+        let provider = setup_provider();
+        let validation = provider.verify(&RequestContext::default()).await;
+        assert_eq!(validation, Verification::fail(Error::Missing));
+    }
+
+    #[tokio::test]
+    async fn verify_unknown_issuer() {
+        let provider = setup_provider();
+        let validation = provider.verify(&create_mtls_request("CN=Other CA")).await;
+        assert_eq!(validation, Verification::fail(Error::Invalid));
+    }
+
+    #[tokio::test]
+    async fn verify_known_issuer() {
+        let provider = setup_provider();
+        let validation = provider
+            .verify(&create_mtls_request("CN=Internal CA"))
+            .await;
+        assert_eq!(validation, Verification::succeed());
+    }
+}
@@ -2,6 +2,7 @@ use futures_util::join;
 
 use super::basic::BasicVerifier;
 use super::jwt::jwt_verify::JwtVerifier;
+use super::mtls::MtlsVerifier;
 use super::verification::Verification;
 use crate::core::blueprint;
 use crate::core::http::RequestContext;
@@ -14,6 +15,7 @@ pub(crate) trait Verify {
 pub enum Verifier {
     Basic(BasicVerifier),
     Jwt(JwtVerifier),
+    Mtls(MtlsVerifier),
 }
 
 pub enum AuthVerifier {
@@ -27,6 +29,7 @@ impl From<blueprint::Provider> for Verifier {
         match provider {
             blueprint::Provider::Basic(options) => Verifier::Basic(BasicVerifier::new(options)),
             blueprint::Provider::Jwt(options) => Verifier::Jwt(JwtVerifier::new(options)),
+            blueprint::Provider::Mtls(options) => Verifier::Mtls(MtlsVerifier::new(options)),
         }
     }
 }
@@ -51,6 +54,7 @@ impl Verify for Verifier {
         match self {
             Verifier::Basic(basic) => basic.verify(req_ctx).await,
             Verifier::Jwt(jwt) => jwt.verify(req_ctx).await,
+            Verifier::Mtls(mtls) => mtls.verify(req_ctx).await,
         }
     }
 }
@@ -2,8 +2,10 @@ use std::collections::HashSet;
 use std::fmt::Debug;
 
 use jsonwebtoken::jwk::JwkSet;
+use rustls_pki_types::CertificateDer;
 
 use crate::core::config::{ConfigModule, Content};
+use crate::core::http::subject_names;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Basic {
@@ -18,10 +20,18 @@ pub struct Jwt {
     pub jwks: JwkSet,
 }
 
+/// Requires the client to present a certificate issued by one of the
+/// authorities of the linked CA bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mtls {
+    pub issuers: HashSet<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Provider {
     Basic(Basic),
     Jwt(Jwt),
+    Mtls(Mtls),
 }
 
 impl From<Content<String>> for Content<Provider> {
@@ -47,6 +57,15 @@ impl From<Content<JwkSet>> for Content<Provider> {
     }
 }
 
+impl From<Content<Vec<CertificateDer<'static>>>> for Content<Provider> {
+    fn from(content: Content<Vec<CertificateDer<'static>>>) -> Self {
+        Content {
+            id: content.id,
+            content: Provider::Mtls(Mtls { issuers: subject_names(&content.content) }),
+        }
+    }
+}
+
 impl Provider {
     /// Used to collect all auth providers from the config module
     pub fn from_config(config_module: &ConfigModule) -> Vec<Content<Provider>> {
@@ -62,6 +81,13 @@ impl Provider {
                     .iter()
                     .map(|jwks| jwks.clone().into()),
             )
+            .chain(
+                config_module
+                    .extensions()
+                    .client_ca
+                    .iter()
+                    .map(|client_ca| client_ca.clone().into()),
+            )
             .collect()
     }
 }
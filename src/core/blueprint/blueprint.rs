@@ -6,7 +6,7 @@ use async_graphql::extensions::ApolloTracing;
 use async_graphql::ValidationMode;
 use derive_setters::Setters;
 
-use super::directive::Directive;
+use super::directive::{is_inaccessible, Directive};
 use super::telemetry::Telemetry;
 use super::{GlobalTimeout, Index};
 use crate::core::blueprint::{Server, Upstream};
@@ -60,6 +60,18 @@ impl Definition {
             Definition::Union(def) => &def.directives,
         }
     }
+
+    /// gets the description of the definition
+    pub fn description(&self) -> Option<&String> {
+        match self {
+            Definition::Interface(def) => def.description.as_ref(),
+            Definition::Object(def) => def.description.as_ref(),
+            Definition::InputObject(def) => def.description.as_ref(),
+            Definition::Scalar(def) => def.description.as_ref(),
+            Definition::Enum(def) => def.description.as_ref(),
+            Definition::Union(def) => def.description.as_ref(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -108,6 +120,7 @@ pub struct EnumValueDefinition {
 pub struct SchemaDefinition {
     pub query: String,
     pub mutation: Option<String>,
+    pub subscription: Option<String>,
     pub directives: Vec<Directive>,
 }
 
@@ -182,6 +195,10 @@ impl Blueprint {
         self.schema.mutation.clone()
     }
 
+    pub fn subscription(&self) -> Option<String> {
+        self.schema.subscription.clone()
+    }
+
     fn drop_resolvers(mut self) -> Self {
         for def in self.definitions.iter_mut() {
             if let Definition::Object(def) = def {
@@ -194,6 +211,37 @@ impl Blueprint {
         self
     }
 
+    /// Removes the `@inaccessible` types and fields, along with the fields and
+    /// members referring to the removed types.
+    fn drop_inaccessible(mut self) -> Self {
+        let types = self
+            .definitions
+            .iter()
+            .filter(|def| is_inaccessible(def.directives()))
+            .map(|def| def.name().to_string())
+            .collect::<BTreeSet<_>>();
+
+        self.definitions.retain(|def| !types.contains(def.name()));
+        for def in self.definitions.iter_mut() {
+            let (fields, implements) = match def {
+                Definition::Object(def) => (&mut def.fields, &mut def.implements),
+                Definition::Interface(def) => (&mut def.fields, &mut def.implements),
+                Definition::Union(def) => {
+                    def.types.retain(|type_| !types.contains(type_));
+                    continue;
+                }
+                _ => continue,
+            };
+
+            fields.retain(|field| {
+                !is_inaccessible(&field.directives) && !types.contains(field.of_type.name())
+            });
+            implements.retain(|interface| !types.contains(interface));
+        }
+
+        self
+    }
+
     ///
     /// This function is used to generate a schema from a blueprint.
     pub fn to_schema(&self) -> Schema {
@@ -208,7 +256,8 @@ impl Blueprint {
             self.clone().drop_resolvers()
         } else {
             self.clone()
-        };
+        }
+        .drop_inaccessible();
 
         let server = &blueprint.server;
         let mut schema = SchemaBuilder::from(&blueprint);
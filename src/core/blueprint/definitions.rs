@@ -551,6 +551,7 @@ pub fn to_field_definition(
         .and(update_enum_alias())
         .and(update_union_resolver())
         .and(update_interface_resolver())
+        .and(update_federation_directives())
         .try_fold(
             &(config_module, field, type_of, name),
             FieldDefinition::default(),
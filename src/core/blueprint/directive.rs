@@ -14,6 +14,13 @@ pub struct Directive {
     pub arguments: HashMap<String, Value>,
 }
 
+/// Fields and types marked `@inaccessible` aren't part of the API schema.
+pub(super) fn is_inaccessible(directives: &[Directive]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.name == "inaccessible")
+}
+
 pub fn to_directive(const_directive: ConstDirective) -> Valid<Directive, BlueprintError> {
     match const_directive
         .arguments
@@ -10,6 +10,21 @@ pub enum BlueprintError {
     #[error("Apollo federation resolvers can't be a part of entity resolver")]
     ApolloFederationResolversNoPartOfEntityResolver,
 
+    #[error("@external fields are resolved by other subgraphs and can't have a resolver")]
+    ExternalFieldWithResolver,
+
+    #[error("@override can't be used on an @external field")]
+    OverrideOnExternalField,
+
+    #[error("@provides is only allowed on fields returning an object")]
+    ProvidesOnNonObjectField,
+
+    #[error("Field {0} isn't @external")]
+    FieldIsNotExternal(String),
+
+    #[error("Invalid field set: `{0}`")]
+    InvalidFieldSet(String),
+
     #[error("Query type is not an object inside the blueprint")]
     QueryTypeNotObject,
 
@@ -100,6 +115,15 @@ pub enum BlueprintError {
     #[error("Link src cannot be empty")]
     LinkSrcCannotBeEmpty,
 
+    #[error("refreshInterval is only supported on links of type Grpc")]
+    RefreshIntervalRequiresGrpcLink,
+
+    #[error("refreshInterval must be greater than 0")]
+    InvalidRefreshInterval,
+
+    #[error("interval of the health check must be greater than 0")]
+    InvalidHealthCheckInterval,
+
     #[error("Duplicated id: {0}")]
     Duplicated(String),
 
@@ -148,12 +172,66 @@ pub enum BlueprintError {
     #[error("Mutation type is not defined")]
     MutationTypeNotDefined,
 
+    #[error("Subscription type is not defined")]
+    SubscriptionTypeNotDefined,
+
+    #[error("Subscription fields can only be resolved by server-streaming @grpc methods")]
+    SubscriptionRequiresGrpcStream,
+
+    #[error("batchKey, select and onResponseBody are not supported on subscription fields")]
+    SubscriptionGrpcOptionNotSupported,
+
     #[error("Certificate is required for HTTP2")]
     CertificateIsRequiredForHTTP2,
 
     #[error("Key is required for HTTP2")]
     KeyIsRequiredForHTTP2,
 
+    #[error("Client certificate verification requires HTTP2")]
+    ClientCARequiresHTTP2,
+
+    #[error("unknown client certificate field '{0}'")]
+    UnknownClientCertField(String),
+
+    #[error("no UpstreamCert link found with id '{0}'")]
+    UpstreamCertNotFound(String),
+
+    #[error("no UpstreamKey link found with id '{0}'")]
+    UpstreamKeyNotFound(String),
+
+    #[error("Only one client certificate without hosts is allowed")]
+    OnlyOneDefaultClientCertAllowed,
+
+    #[error("Host '{0}' is used by more than one client certificate")]
+    DuplicateClientCertHost(String),
+
+    #[error("Invalid OAuth2 token URL: {0}")]
+    InvalidOAuth2TokenUrl(String),
+
+    #[error("Signing can not be used with batchKey, batched requests are combined after signing")]
+    SigningWithBatchingNotSupported,
+
+    #[error("Signing can not be used with oauth2, the access token replaces the signature")]
+    SigningWithOAuth2NotSupported,
+
+    #[error("Invalid signing header name: {0}")]
+    InvalidSigningHeader(String),
+
+    #[error("gRPC service requires HTTP2")]
+    GrpcServiceRequiresHTTP2,
+
+    #[error("Message {0} of the gRPC service conflicts with another message of the same name")]
+    GrpcServiceMessageConflict(String),
+
+    #[error("Invalid proto package: {0}")]
+    InvalidProtoPackage(String),
+
+    #[error("Invalid HTTP status code {0} in grpcStatusMapping")]
+    InvalidGrpcStatusMapping(u16),
+
+    #[error("Invalid HTTP status code {0} in restErrors")]
+    InvalidRestStatusMapping(u16),
+
     #[error("Experimental headers must start with 'x-' or 'X-'. Got: '{0}'")]
     ExperimentalHeaderInvalidFormat(String),
 
@@ -10,6 +10,7 @@ use crate::core::blueprint::compress::compress;
 use crate::core::blueprint::*;
 use crate::core::config::transformer::Required;
 use crate::core::config::{Arg, Batch, Config, ConfigModule};
+use crate::core::grpc::service::proto::validate_message_names;
 use crate::core::ir::model::{IO, IR};
 use crate::core::json::JsonSchema;
 use crate::core::try_fold::TryFold;
@@ -43,6 +44,16 @@ pub fn config_blueprint<'a>() -> TryFold<'a, ConfigModule, Blueprint, BlueprintE
         |blueprint| blueprint.telemetry,
     );
 
+    let grpc_service = TryFoldConfig::<Blueprint>::new(|_, blueprint| {
+        if blueprint.server.grpc_service.is_some() {
+            validate_message_names(&blueprint)
+                .trace("grpcService")
+                .map_to(blueprint)
+        } else {
+            Valid::succeed(blueprint)
+        }
+    });
+
     server
         .and(schema)
         .and(definitions)
@@ -52,6 +63,7 @@ pub fn config_blueprint<'a>() -> TryFold<'a, ConfigModule, Blueprint, BlueprintE
         // set the federation config only after setting other properties to be able
         // to use blueprint inside the handler and to avoid recursion overflow
         .and(update_federation().trace("federation"))
+        .and(grpc_service)
         .update(apply_batching)
         .update(compress)
 }
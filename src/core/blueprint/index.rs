@@ -1,7 +1,8 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use indexmap::IndexMap;
 
+use super::directive::is_inaccessible;
 use super::InputObjectTypeDefinition;
 use crate::core::blueprint::{
     Blueprint, Definition, FieldDefinition, InputFieldDefinition, SchemaDefinition,
@@ -16,6 +17,8 @@ use crate::core::scalar;
 pub struct Index {
     map: IndexMap<String, (Definition, IndexMap<String, QueryField>)>,
     schema: SchemaDefinition,
+    /// `@inaccessible` fields by type, they're left out of `map`
+    inaccessible: HashMap<String, HashSet<String>>,
 }
 
 #[derive(Debug)]
@@ -75,6 +78,12 @@ impl Index {
             .and_then(|(_, fields_map)| fields_map.get(field_name))
     }
 
+    pub fn is_inaccessible(&self, type_name: &str, field_name: &str) -> bool {
+        self.inaccessible
+            .get(type_name)
+            .is_some_and(|fields| fields.contains(field_name))
+    }
+
     pub fn get_query(&self) -> &String {
         &self.schema.query
     }
@@ -106,14 +115,36 @@ impl Index {
 impl From<&Blueprint> for Index {
     fn from(blueprint: &Blueprint) -> Self {
         let mut map = IndexMap::new();
+        let mut inaccessible: HashMap<String, HashSet<String>> = HashMap::new();
 
         for definition in blueprint.definitions.iter() {
+            let fields = match definition {
+                Definition::Object(def) => def.fields.as_slice(),
+                Definition::Interface(def) => def.fields.as_slice(),
+                _ => &[],
+            };
+            let is_type_inaccessible = is_inaccessible(definition.directives());
+            let inaccessible_fields = fields
+                .iter()
+                .filter(|field| is_type_inaccessible || is_inaccessible(&field.directives))
+                .map(|field| field.name.clone())
+                .collect::<HashSet<_>>();
+            if !inaccessible_fields.is_empty() {
+                inaccessible.insert(definition.name().to_string(), inaccessible_fields);
+            }
+            if is_type_inaccessible {
+                continue;
+            }
+
             match definition {
                 Definition::Object(object_def) => {
                     let type_name = object_def.name.clone();
                     let mut fields_map = IndexMap::new();
 
                     for field in &object_def.fields {
+                        if is_inaccessible(&field.directives) {
+                            continue;
+                        }
                         let args_map = IndexMap::from_iter(
                             field
                                 .args
@@ -137,6 +168,9 @@ impl From<&Blueprint> for Index {
                     let mut fields_map = IndexMap::new();
 
                     for field in interface_def.fields.clone() {
+                        if is_inaccessible(&field.directives) {
+                            continue;
+                        }
                         let args_map = IndexMap::from_iter(
                             field
                                 .args
@@ -192,7 +226,7 @@ impl From<&Blueprint> for Index {
             }
         }
 
-        Self { map, schema: blueprint.schema.to_owned() }
+        Self { map, schema: blueprint.schema.to_owned(), inaccessible }
     }
 }
 
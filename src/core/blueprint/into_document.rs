@@ -139,7 +139,11 @@ impl From<&Blueprint> for ServiceDocument {
                 .mutation
                 .as_ref()
                 .map(|mutation| pos(Name::new(mutation))),
-            subscription: None,
+            subscription: blueprint
+                .schema
+                .subscription
+                .as_ref()
+                .map(|subscription| pos(Name::new(subscription))),
         })));
 
         for def in &blueprint.definitions {
@@ -1,13 +1,15 @@
 use std::sync::Arc;
 
-use async_graphql::dynamic::{self, FieldFuture, FieldValue, SchemaBuilder, TypeRef};
+use async_graphql::dynamic::{
+    self, FieldFuture, FieldValue, SchemaBuilder, SubscriptionFieldFuture, TypeRef,
+};
 use async_graphql_value::ConstValue;
-use futures_util::TryFutureExt;
+use futures_util::{StreamExt, TryFutureExt};
 use tracing::Instrument;
 
-use crate::core::blueprint::{Blueprint, Definition};
+use crate::core::blueprint::{Blueprint, Definition, ObjectTypeDefinition};
 use crate::core::http::RequestContext;
-use crate::core::ir::{EvalContext, ResolverContext, TypedValue};
+use crate::core::ir::{Error, EvalContext, ResolverContext, TypedValue};
 use crate::core::jit::graphql_error::ErrorExtensions;
 use crate::core::scalar::Scalar;
 
@@ -94,8 +96,9 @@ fn to_type(def: &Definition) -> dynamic::Type {
                                         let ctx: ResolverContext = ctx.into();
                                         let ctx = &mut EvalContext::new(req_ctx, &ctx);
 
-                                        let value =
-                                            expr.eval(ctx).await.map_err(|err| err.extend())?;
+                                        let value = expr.eval(ctx).await.map_err(|err| {
+                                            err.extend().into_async_graphql_error()
+                                        })?;
 
                                         if let ConstValue::Null = value {
                                             Ok(FieldValue::NONE)
@@ -192,16 +195,71 @@ fn to_type(def: &Definition) -> dynamic::Type {
     }
 }
 
+/// Builds the subscription root, every field streams the values of its
+/// resolver.
+fn to_subscription(def: &ObjectTypeDefinition) -> dynamic::Subscription {
+    let mut subscription = dynamic::Subscription::new(def.name.clone());
+    for field in def.fields.iter() {
+        let resolver = field.resolver.clone();
+        let mut dyn_schema_field = dynamic::SubscriptionField::new(
+            field.name.clone(),
+            TypeRef::from(&field.of_type),
+            move |ctx| {
+                let resolver = resolver.clone();
+                SubscriptionFieldFuture::new(async move {
+                    let req_ctx = ctx.ctx.data::<Arc<RequestContext>>()?;
+                    let ctx: ResolverContext = ctx.into();
+                    let ctx = &mut EvalContext::new(req_ctx, &ctx);
+
+                    let stream = match resolver {
+                        Some(expr) => expr.eval_stream(ctx).await,
+                        None => Err(Error::IO("Subscription field has no resolver".to_owned())),
+                    }
+                    .map_err(|err| err.extend().into_async_graphql_error())?;
+
+                    Ok(stream.map(|value| match value {
+                        Ok(ConstValue::Null) => Ok(FieldValue::NULL),
+                        Ok(value) => Ok(to_field_value(value)),
+                        Err(err) => Err(err.extend().into_async_graphql_error()),
+                    }))
+                })
+            },
+        );
+        if let Some(description) = &field.description {
+            dyn_schema_field = dyn_schema_field.description(description);
+        }
+        for arg in field.args.iter() {
+            dyn_schema_field = dyn_schema_field.argument(set_default_value(
+                dynamic::InputValue::new(arg.name.clone(), TypeRef::from(&arg.of_type)),
+                arg.default_value.clone(),
+            ));
+        }
+        subscription = subscription.field(dyn_schema_field);
+    }
+    if let Some(description) = &def.description {
+        subscription = subscription.description(description);
+    }
+
+    subscription
+}
+
 impl From<&Blueprint> for SchemaBuilder {
     fn from(blueprint: &Blueprint) -> Self {
         let query = blueprint.query();
         let mutation = blueprint.mutation();
-        let mut schema = dynamic::Schema::build(query.as_str(), mutation.as_deref(), None);
+        let subscription = blueprint.subscription();
+        let mut schema =
+            dynamic::Schema::build(query.as_str(), mutation.as_deref(), subscription.as_deref());
 
         schema = inject_custom_scalars(schema, blueprint);
 
         for def in blueprint.definitions.iter() {
-            schema = schema.register(to_type(def));
+            schema = match def {
+                Definition::Object(def) if subscription.as_ref() == Some(&def.name) => {
+                    schema.register(to_subscription(def))
+                }
+                def => schema.register(to_type(def)),
+            };
         }
 
         schema
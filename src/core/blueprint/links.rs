@@ -19,6 +19,13 @@ impl TryFrom<Vec<Link>> for Links {
                         Valid::succeed(link)
                     }
                 })
+                .and_then(|link| match link.refresh_interval {
+                    Some(_) if link.type_of != LinkType::Grpc => {
+                        Valid::fail(BlueprintError::RefreshIntervalRequiresGrpcLink)
+                    }
+                    Some(0) => Valid::fail(BlueprintError::InvalidRefreshInterval),
+                    _ => Valid::succeed(link),
+                })
                 .and_then(|link| {
                     if let Some(id) = &link.id {
                         if links.iter().filter(|l| l.id.as_ref() == Some(id)).count() > 1 {
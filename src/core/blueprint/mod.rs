@@ -13,9 +13,11 @@ mod into_document;
 mod into_schema;
 mod links;
 mod mustache;
+mod oauth2;
 mod operators;
 mod schema;
 mod server;
+mod signing;
 pub mod telemetry;
 mod template_validation;
 mod timeout;
@@ -31,9 +33,11 @@ pub use error::*;
 pub use from_config::*;
 pub use index::*;
 pub use links::*;
+pub use oauth2::*;
 pub use operators::*;
 pub use schema::*;
 pub use server::*;
+pub use signing::*;
 pub use timeout::GlobalTimeout;
 pub use upstream::*;
 
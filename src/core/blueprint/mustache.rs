@@ -89,6 +89,13 @@ impl<'a> MustachePartsValidator<'a> {
                     return Valid::fail(BlueprintError::VarNotSetInServerConfig(tail.to_string()));
                 }
             }
+            "client" => {
+                // the certificate itself is only known at runtime, but the
+                // exposed fields are fixed
+                if !matches!(tail, "subject" | "san") {
+                    return Valid::fail(BlueprintError::UnknownClientCertField(tail.to_string()));
+                }
+            }
             "headers" | "env" => {
                 // "headers" and "env" refers to values known at runtime, which
                 // we can't validate here
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use tailcall_valid::{Valid, Validator};
+
+use super::BlueprintError;
+use crate::core::config::{self, ConfigModule};
+use crate::core::http::OAuth2;
+
+/// Resolves the OAuth2 settings of an operator, falling back to the settings
+/// of `@upstream` when the operator doesn't define its own.
+pub fn to_oauth2(
+    config_module: &ConfigModule,
+    oauth2: Option<&config::OAuth2>,
+) -> Valid<Option<Arc<OAuth2>>, BlueprintError> {
+    let Some(oauth2) = oauth2.or(config_module.upstream.oauth2.as_ref()) else {
+        return Valid::succeed(None);
+    };
+
+    match OAuth2::try_from(oauth2) {
+        Ok(oauth2) => Valid::succeed(Some(Arc::new(oauth2))),
+        Err(e) => {
+            Valid::fail(BlueprintError::InvalidOAuth2TokenUrl(e.to_string())).trace("tokenUrl")
+        }
+    }
+    .trace("oauth2")
+}
@@ -5,11 +5,16 @@ use async_graphql::parser::types::ServiceDocument;
 use tailcall_valid::{Valid, Validator};
 
 use super::{compile_resolver, CompileResolver};
-use crate::core::blueprint::{Blueprint, BlueprintError, Definition, TryFoldConfig};
+use crate::core::blueprint::{
+    Blueprint, BlueprintError, Definition, FieldDefinition, TryFoldConfig,
+};
 use crate::core::config::{
-    ApolloFederation, ConfigModule, EntityResolver, Field, GraphQLOperationType, Resolver,
+    self, ApolloFederation, ConfigModule, Directive, EntityFetch, EntityResolver, Field, FieldSet,
+    GraphQLOperationType, KeyValue, Resolver,
 };
-use crate::core::ir::model::IR;
+use crate::core::graphql::{Entity, RequestTemplate};
+use crate::core::ir::model::{IO, IR};
+use crate::core::try_fold::TryFold;
 use crate::core::Type;
 
 pub struct CompileEntityResolver<'a> {
@@ -35,7 +40,7 @@ pub fn compile_entity_resolver(inputs: CompileEntityResolver<'_>) -> Valid<IR, B
                     ApolloFederation::EntityResolver(entity_resolver) => {
                         compile_entity_resolver(CompileEntityResolver { entity_resolver, ..inputs })
                     }
-                    ApolloFederation::Service => {
+                    ApolloFederation::Service | ApolloFederation::EntityFetch(_) => {
                         Valid::fail(BlueprintError::ApolloFederationResolversNoPartOfEntityResolver)
                     }
                 },
@@ -54,6 +59,19 @@ pub fn compile_entity_resolver(inputs: CompileEntityResolver<'_>) -> Valid<IR, B
             };
 
             ir.map(|ir| {
+                // the representation carries the @external fields that other fields
+                // @requires, they are made available as `.value` for them
+                let has_external = config_module.types.get(type_name).is_some_and(|ty| {
+                    ty.fields
+                        .values()
+                        .any(|field| has_directive(&field.directives, "external"))
+                });
+                let ir = if has_external {
+                    IR::Merge(vec![ir, IR::ContextPath(vec![])])
+                } else {
+                    ir
+                };
+
                 resolver_by_type.insert(type_name.to_owned(), ir);
             })
         },
@@ -61,6 +79,156 @@ pub fn compile_entity_resolver(inputs: CompileEntityResolver<'_>) -> Valid<IR, B
     .map_to(IR::Entity(resolver_by_type))
 }
 
+/// Compiles the `_entities` query of the supergraph gateway, the fetches of the
+/// fields it requires are run first and piped into it.
+pub fn compile_entity_fetch(fetch: &EntityFetch) -> Valid<IR, BlueprintError> {
+    let representations = KeyValue {
+        key: "representations".to_string(),
+        value: format!("[{}]", fetch.representation),
+    };
+
+    let req_template = match RequestTemplate::new(
+        fetch.url.clone(),
+        &GraphQLOperationType::Query,
+        "_entities",
+        Some(&vec![representations]),
+        Default::default(),
+        Default::default(),
+    ) {
+        Ok(req_template) => Valid::succeed(req_template),
+        Err(err) => Valid::fail(BlueprintError::Error(err)),
+    };
+
+    req_template
+        .map(|req_template| {
+            let entity = Entity {
+                type_name: Some(fetch.type_name.clone()),
+                selection: (&fetch.selection).into(),
+                sources: fetch.sources.clone(),
+                group: None,
+            };
+            // the fetches of the fields grouped into one query share the request
+            IR::IO(IO::GraphQL {
+                req_template: req_template.entity(Some(entity)),
+                field_name: "_entities".to_string(),
+                batch: true,
+                dl_id: None,
+                dedupe: true,
+            })
+        })
+        .and_then(|io| {
+            if fetch.requires.is_empty() {
+                return Valid::succeed(io);
+            }
+
+            Valid::from_iter(fetch.requires.iter(), compile_entity_fetch)
+                .map(|requires| IR::Merge(requires).pipe(io))
+        })
+}
+
+fn has_directive(directives: &[Directive], name: &str) -> bool {
+    directives.iter().any(|directive| directive.name == name)
+}
+
+/// Validates the fields of a field set used by `@requires` or `@provides`, the
+/// top level fields have to be `@external`.
+fn validate_field_set(
+    config_module: &ConfigModule,
+    directive: Option<&Directive>,
+    type_of: &config::Type,
+) -> Valid<(), BlueprintError> {
+    let Some(fields) = directive
+        .and_then(|directive| directive.arguments.get("fields"))
+        .and_then(|fields| fields.as_str())
+    else {
+        return Valid::succeed(());
+    };
+
+    Valid::from_option(
+        FieldSet::parse(fields),
+        BlueprintError::InvalidFieldSet(fields.to_string()),
+    )
+    .and_then(|field_set| {
+        Valid::from_iter(field_set.0.iter(), |(name, _)| {
+            match type_of.fields.get(name) {
+                Some(field) if !has_directive(&field.directives, "external") => {
+                    Valid::fail(BlueprintError::FieldIsNotExternal(name.clone()))
+                }
+                // missing fields are reported along with the nested ones
+                _ => Valid::succeed(()),
+            }
+        })
+        .and(validate_selection(config_module, &field_set, type_of))
+    })
+    .unit()
+}
+
+fn validate_selection(
+    config_module: &ConfigModule,
+    field_set: &FieldSet,
+    type_of: &config::Type,
+) -> Valid<(), BlueprintError> {
+    Valid::from_iter(field_set.0.iter(), |(name, nested)| {
+        Valid::from_option(
+            type_of.fields.get(name),
+            BlueprintError::FieldNotFoundInType(name.clone()),
+        )
+        .and_then(
+            |field| match config_module.types.get(field.type_of.name()) {
+                Some(type_of) => validate_selection(config_module, nested, type_of),
+                None => Valid::succeed(()),
+            },
+        )
+        .trace(name)
+    })
+    .unit()
+}
+
+/// Validates the federation directives of the fields of a subgraph,
+/// `@external` fields are resolved by other subgraphs, the fields of
+/// `@requires` and `@provides` have to be `@external` and `@override` can't
+/// take over an `@external` field.
+pub fn update_federation_directives<'a>() -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &str), FieldDefinition, BlueprintError>::new(
+        |(config_module, field, type_of, _), b_field| {
+            if !config_module.server.get_enable_federation() {
+                return Valid::succeed(b_field);
+            }
+
+            let find = |name: &str| field.directives.iter().find(|d| d.name == name);
+            let is_external = find("external").is_some();
+
+            let external = if is_external && field.has_resolver() {
+                Valid::fail(BlueprintError::ExternalFieldWithResolver).trace("@external")
+            } else if is_external && find("override").is_some() {
+                Valid::fail(BlueprintError::OverrideOnExternalField).trace("@override")
+            } else {
+                Valid::succeed(())
+            };
+
+            let requires =
+                validate_field_set(config_module, find("requires"), type_of).trace("@requires");
+
+            let provides = match (
+                find("provides"),
+                config_module.types.get(field.type_of.name()),
+            ) {
+                (None, _) => Valid::succeed(()),
+                (Some(_), None) => Valid::fail(BlueprintError::ProvidesOnNonObjectField),
+                (provides, Some(type_of)) => validate_field_set(config_module, provides, type_of),
+            }
+            .trace("@provides");
+
+            external.and(requires).and(provides).map_to(b_field)
+        },
+    )
+}
+
 pub fn compile_service(mut sdl: String) -> Valid<IR, BlueprintError> {
     writeln!(sdl).ok();
 
@@ -124,6 +292,8 @@ pub fn update_federation<'a>() -> TryFoldConfig<'a, Blueprint> {
                             })
                         }
                         ApolloFederation::Service => compile_service(std::mem::take(&mut sdl)),
+                        // compiled along with the other resolvers
+                        ApolloFederation::EntityFetch(_) => return Valid::succeed(b_field),
                     }
                     .map(|resolver| b_field.resolver(Some(resolver)))
                 })
@@ -137,3 +307,92 @@ pub fn update_federation<'a>() -> TryFoldConfig<'a, Blueprint> {
         .map(|definitions| blueprint.definitions(definitions))
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+
+    fn compile(types: &str) -> Vec<String> {
+        let sdl = format!(
+            r#"
+            schema @server(enableFederation: true) {{ query: Query }}
+            type Query {{
+              product(upc: String!): Product @http(url: "http://localhost/products/{{{{.args.upc}}}}")
+            }}
+            {types}
+            "#
+        );
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+
+        match Blueprint::try_from(&ConfigModule::from(config)) {
+            Ok(_) => vec![],
+            Err(err) => err
+                .as_vec()
+                .iter()
+                .map(|cause| format!("{:?}: {}", cause.trace, cause.message))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_federation_directives() {
+        let errors = compile(
+            r#"
+            type Product @http(url: "http://localhost/products/{{.value.upc}}") {
+              upc: String!
+              weight: Int @external
+              shippingEstimate: Int @requires(fields: "weight") @expr(body: "{{.value.weight}}")
+              reviews: [Review] @provides(fields: "author { name }") @http(url: "http://localhost/reviews")
+            }
+            type Review {
+              id: Int!
+              author: User @external
+            }
+            type User {
+              name: String
+            }
+            "#,
+        );
+
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_federation_directives_errors() {
+        let errors = compile(
+            r#"
+            type Product @http(url: "http://localhost/products/{{.value.upc}}") {
+              upc: String!
+              weight: Int @external @expr(body: 1)
+              price: Int @external @override(from: "products")
+              shippingEstimate: Int @requires(fields: "upc size") @expr(body: 1)
+              reviews: [Review] @provides(fields: "author { age }") @http(url: "http://localhost/reviews")
+              name: String @provides(fields: "name")
+            }
+            type Review {
+              id: Int!
+              author: User
+            }
+            type User {
+              name: String
+            }
+            "#,
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                r#"["Product", "name", "@provides"]: @provides is only allowed on fields returning an object"#,
+                r#"["Product", "price", "@override"]: @override can't be used on an @external field"#,
+                r#"["Product", "reviews", "@provides"]: Field author isn't @external"#,
+                r#"["Product", "reviews", "@provides", "author", "age"]: Cannot find field age in the type"#,
+                r#"["Product", "shippingEstimate", "@requires"]: Field upc isn't @external"#,
+                r#"["Product", "shippingEstimate", "@requires", "size"]: Cannot find field size in the type"#,
+                r#"["Product", "weight", "@external"]: @external fields are resolved by other subgraphs and can't have a resolver"#,
+            ]
+        );
+    }
+}
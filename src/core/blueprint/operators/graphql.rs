@@ -2,9 +2,10 @@ use std::collections::{HashMap, HashSet};
 
 use tailcall_valid::{Valid, Validator};
 
-use crate::core::blueprint::BlueprintError;
+use crate::core::blueprint::{to_oauth2, BlueprintError};
+use crate::core::config::stitching::Lookup;
 use crate::core::config::{Config, ConfigModule, GraphQL, GraphQLOperationType};
-use crate::core::graphql::RequestTemplate;
+use crate::core::graphql::{Entity, RequestTemplate};
 use crate::core::helpers;
 use crate::core::ir::model::{IO, IR};
 use crate::core::ir::RelatedFields;
@@ -81,10 +82,47 @@ pub fn compile_graphql(
                 Err(err) => Valid::fail(BlueprintError::Error(err)),
             }
         })
-        .map(|req_template| {
+        .zip(to_oauth2(config, None))
+        .map(|(req_template, oauth2)| {
+            let req_template = req_template.oauth2(oauth2);
             let field_name = graphql.name.clone();
             let batch = graphql.batch;
             let dedupe = graphql.dedupe;
             IR::IO(IO::GraphQL { req_template, field_name, batch, dl_id: None, dedupe })
         })
 }
+
+/// Compiles the lookup of a field of a stitched type, the field is selected
+/// from the type returned by the root field of the service owning it.
+pub fn compile_lookup(config: &ConfigModule, lookup: &Lookup) -> Valid<IR, BlueprintError> {
+    let req_template = match RequestTemplate::new(
+        lookup.url.clone(),
+        &GraphQLOperationType::Query,
+        &lookup.name,
+        Some(&lookup.args),
+        Default::default(),
+        Default::default(),
+    ) {
+        Ok(req_template) => Valid::succeed(req_template),
+        Err(err) => Valid::fail(BlueprintError::Error(err)),
+    };
+
+    req_template
+        .zip(to_oauth2(config, None))
+        .map(|(req_template, oauth2)| {
+            let entity = Entity {
+                type_name: None,
+                selection: (&lookup.selection).into(),
+                sources: lookup.sources.clone(),
+                group: None,
+            };
+            // the lookups of the fields grouped into one query share the request
+            IR::IO(IO::GraphQL {
+                req_template: req_template.entity(Some(entity)).oauth2(oauth2),
+                field_name: lookup.name.clone(),
+                batch: true,
+                dl_id: None,
+                dedupe: true,
+            })
+        })
+}
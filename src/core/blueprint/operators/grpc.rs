@@ -1,11 +1,12 @@
 use std::fmt::Display;
+use std::time::Duration;
 
 use prost_reflect::prost_types::FileDescriptorSet;
 use prost_reflect::FieldDescriptor;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::apply_select;
-use crate::core::blueprint::BlueprintError;
+use crate::core::blueprint::{to_oauth2, BlueprintError};
 use crate::core::config::group_by::GroupBy;
 use crate::core::config::{Config, ConfigModule, Field, GraphQLOperationType, Grpc};
 use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
@@ -64,9 +65,10 @@ fn validate_schema(
     field_schema: FieldSchema,
     operation: &ProtobufOperation,
     name: &str,
+    subscription: bool,
 ) -> Valid<(), BlueprintError> {
-    let input_type = &operation.input_type;
-    let output_type = &operation.output_type;
+    let input_type = &operation.input_type();
+    let output_type = &operation.output_type();
 
     let input_type = match JsonSchema::try_from(input_type) {
         Ok(input_schema) => Valid::succeed(input_schema),
@@ -81,6 +83,13 @@ fn validate_schema(
     input_type
         .zip(output_type)
         .and_then(|(_input_schema, sub_type)| {
+            // server-streaming methods are resolved as the list of all messages,
+            // except on subscriptions that return them one by one
+            let sub_type = if operation.is_server_streaming() && !subscription {
+                JsonSchema::Arr(Box::new(sub_type))
+            } else {
+                sub_type
+            };
             // TODO: add validation for input schema - should compare result grpc.body to
             // schema
             let super_type = field_schema.field;
@@ -99,8 +108,8 @@ fn validate_group_by(
     operation: &ProtobufOperation,
     group_by: Vec<String>,
 ) -> Valid<(), BlueprintError> {
-    let input_type = &operation.input_type;
-    let output_type = &operation.output_type;
+    let input_type = &operation.input_type();
+    let output_type = &operation.output_type();
     let mut field_descriptor: Result<FieldDescriptor, ValidationError<BlueprintError>> = None
         .ok_or(ValidationError::new(BlueprintError::FieldNotFound(
             group_by[0].clone(),
@@ -145,6 +154,9 @@ pub struct CompileGrpc<'a> {
     pub field: &'a Field,
     pub grpc: &'a Grpc,
     pub validate_with_schema: bool,
+    /// Whether the field is a field of the subscription root, which streams
+    /// the messages of the method.
+    pub subscription: bool,
 }
 pub struct GrpcMethod {
     pub package: String,
@@ -185,9 +197,18 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
     let field = inputs.field;
     let grpc = inputs.grpc;
     let validate_with_schema = inputs.validate_with_schema;
+    let subscription = inputs.subscription;
     let dedupe = grpc.dedupe.unwrap_or_default();
 
-    Valid::from(GrpcMethod::try_from(grpc.method.as_str()))
+    let health_check = match &grpc.health_check {
+        Some(health_check) if health_check.interval == Some(0) => {
+            Valid::fail(BlueprintError::InvalidHealthCheckInterval).trace("healthCheck")
+        }
+        _ => Valid::succeed(()),
+    };
+
+    health_check
+        .and_then(|_| Valid::from(GrpcMethod::try_from(grpc.method.as_str())))
         .and_then(|method| {
             let file_descriptor_set = config_module.extensions().get_file_descriptor_set();
 
@@ -205,11 +226,34 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
                 Err(e) => Valid::from_validation_err(BlueprintError::from_validation_string(e)),
             }
         })
+        .map(|(operation, url, headers, body)| {
+            let compression = grpc
+                .compression
+                .or(config_module.upstream.grpc_compression)
+                .unwrap_or_default();
+            let operation = operation
+                .with_compression(compression)
+                .with_protocol(grpc.protocol.unwrap_or_default());
+            (operation, url, headers, body)
+        })
         .and_then(|(operation, url, headers, body)| {
+            if subscription {
+                if !operation.is_server_streaming() {
+                    return Valid::fail(BlueprintError::SubscriptionRequiresGrpcStream);
+                }
+                if !grpc.batch_key.is_empty()
+                    || grpc.select.is_some()
+                    || grpc.on_response_body.is_some()
+                {
+                    return Valid::fail(BlueprintError::SubscriptionGrpcOptionNotSupported);
+                }
+            }
+
             let validation = if validate_with_schema {
                 let field_schema = json_schema_from_field(config_module, field);
                 if grpc.batch_key.is_empty() {
-                    validate_schema(field_schema, &operation, field.type_of.name()).unit()
+                    validate_schema(field_schema, &operation, field.type_of.name(), subscription)
+                        .unit()
                 } else {
                     validate_group_by(&field_schema, &operation, grpc.batch_key.clone()).unit()
                 }
@@ -218,13 +262,16 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
             };
             validation.map(|_| (url, headers, operation, body))
         })
-        .map(|(url, headers, operation, body)| {
+        .zip(to_oauth2(config_module, None))
+        .map(|((url, headers, operation, body), oauth2)| {
             let req_template = RequestTemplate {
                 url,
                 headers,
                 operation,
                 body,
                 operation_type: operation_type.clone(),
+                oauth2,
+                timeout: grpc.timeout.map(Duration::from_millis),
             };
             let on_response = grpc.on_response_body.clone();
             let hook = WorkerHooks::try_new(None, on_response).ok();
@@ -85,6 +85,11 @@ pub fn compile_http(
                 Valid::succeed(request_template)
             }
         })
+        .zip(to_oauth2(config_module, http.oauth2.as_ref()))
+        .and_then(|(req_template, oauth2)| {
+            to_signing(http.signing.as_ref(), &http.batch_key, oauth2.is_some())
+                .map(|signing| req_template.oauth2(oauth2).signing(signing))
+        })
         .map(|req_template| {
             // marge http and upstream on_request
             let on_request = http
@@ -1,6 +1,9 @@
 use tailcall_valid::{Valid, Validator};
 
-use super::{compile_call, compile_expr, compile_graphql, compile_grpc, compile_http, compile_js};
+use super::{
+    compile_call, compile_entity_fetch, compile_expr, compile_graphql, compile_grpc, compile_http,
+    compile_js, compile_lookup,
+};
 use crate::core::blueprint::{BlueprintError, FieldDefinition};
 use crate::core::config::{self, ConfigModule, Field, GraphQLOperationType, Resolver};
 use crate::core::directive::DirectiveCodec;
@@ -19,6 +22,11 @@ pub fn compile_resolver(
     resolver: &Resolver,
 ) -> Valid<Option<IR>, BlueprintError> {
     let CompileResolver { config_module, field, operation_type, object_name } = inputs;
+    let subscription = config_module.schema.subscription.as_deref() == Some(*object_name);
+
+    if subscription && !matches!(resolver, Resolver::Grpc(_)) {
+        return Valid::fail(BlueprintError::SubscriptionRequiresGrpcStream);
+    }
 
     match resolver {
         Resolver::Http(http) => {
@@ -30,6 +38,7 @@ pub fn compile_resolver(
             field,
             grpc,
             validate_with_schema: true,
+            subscription,
         })
         .trace(config::Grpc::trace_name().as_str()),
         Resolver::Graphql(graphql) => {
@@ -46,6 +55,10 @@ pub fn compile_resolver(
             compile_expr(super::CompileExpr { config_module, field, expr, validate: true })
                 .trace(config::Expr::trace_name().as_str())
         }
+        Resolver::Lookup(lookup) => compile_lookup(config_module, lookup),
+        Resolver::ApolloFederation(config::ApolloFederation::EntityFetch(fetch)) => {
+            compile_entity_fetch(fetch)
+        }
         Resolver::ApolloFederation(_) => {
             // ignore the Federation resolvers since they have special meaning
             // and should be executed only after the other config processing
@@ -5,12 +5,15 @@ use std::time::Duration;
 
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::StatusCode;
 use rustls_pki_types::CertificateDer;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::BlueprintError;
 use crate::core::blueprint::Cors;
-use crate::core::config::{self, ConfigModule, HttpVersion, PrivateKey, Routes};
+use crate::core::config::{self, ConfigModule, HttpVersion, PrivateKey, Routes, Uploads};
+use crate::core::grpc::status::StatusMapping;
+use crate::core::rest::ErrorMapping;
 
 #[derive(Clone, Debug, Setters)]
 pub struct Server {
@@ -18,6 +21,7 @@ pub struct Server {
     pub enable_cache_control_header: bool,
     pub enable_set_cookie_header: bool,
     pub enable_introspection: bool,
+    pub enable_introspection_filter: bool,
     pub enable_query_validation: bool,
     pub enable_response_validation: bool,
     pub enable_batch_requests: bool,
@@ -34,6 +38,27 @@ pub struct Server {
     pub cors: Option<Cors>,
     pub experimental_headers: HashSet<HeaderName>,
     pub routes: Routes,
+    pub grpc_status_mapping: StatusMapping,
+    pub rest_errors: ErrorMapping,
+    pub uploads: Uploads,
+    pub grpc_service: Option<GrpcService>,
+}
+
+/// Options of the gRPC service generated from the schema.
+#[derive(Clone, Debug)]
+pub struct GrpcService {
+    pub package: String,
+    pub max_depth: usize,
+}
+
+impl Default for GrpcService {
+    fn default() -> Self {
+        let options = config::GrpcService::default();
+        Self {
+            package: options.get_package(),
+            max_depth: options.get_max_depth(),
+        }
+    }
 }
 
 /// Mimic of mini_v8::Script that's wasm compatible
@@ -49,6 +74,10 @@ pub enum Http {
     HTTP2 {
         cert: Vec<CertificateDer<'static>>,
         key: PrivateKey,
+        /// CA bundles used to verify client certificates, one per
+        /// `@link(type: ClientCA)`. Client authentication is disabled when
+        /// empty.
+        client_ca: Vec<Vec<CertificateDer<'static>>>,
     },
 }
 
@@ -71,6 +100,10 @@ impl Server {
         self.enable_introspection
     }
 
+    pub fn get_enable_introspection_filter(&self) -> bool {
+        self.enable_introspection_filter
+    }
+
     pub fn get_enable_query_validation(&self) -> bool {
         self.enable_query_validation
     }
@@ -101,7 +134,17 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     .ok_or_else(|| ValidationError::new(BlueprintError::KeyIsRequiredForHTTP2))?
                     .clone();
 
-                Valid::succeed(Http::HTTP2 { cert, key })
+                let client_ca = config_module
+                    .extensions()
+                    .client_ca
+                    .iter()
+                    .map(|content| content.content.clone())
+                    .collect();
+
+                Valid::succeed(Http::HTTP2 { cert, key, client_ca })
+            }
+            _ if !config_module.extensions().client_ca.is_empty() => {
+                Valid::fail(BlueprintError::ClientCARequiresHTTP2)
             }
             _ => Valid::succeed(Http::HTTP1),
         };
@@ -121,12 +164,28 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     .as_ref()
                     .and_then(|headers| headers.get_cors()),
             ))
+            .fuse(validate_grpc_status_mapping(
+                config_server.get_grpc_status_mapping(),
+            ))
+            .fuse(validate_rest_errors(config_server.get_rest_errors()))
+            .fuse(validate_grpc_service(&config_server))
             .map(
-                |(hostname, http, response_headers, script, experimental_headers, cors)| Server {
+                |(
+                    hostname,
+                    http,
+                    response_headers,
+                    script,
+                    experimental_headers,
+                    cors,
+                    grpc_status_mapping,
+                    rest_errors,
+                    grpc_service,
+                )| Server {
                     enable_apollo_tracing: (config_server).enable_apollo_tracing(),
                     enable_cache_control_header: (config_server).enable_cache_control(),
                     enable_set_cookie_header: (config_server).enable_set_cookies(),
                     enable_introspection: (config_server).enable_introspection(),
+                    enable_introspection_filter: (config_server).enable_introspection_filter(),
                     enable_query_validation: (config_server).enable_query_validation(),
                     enable_response_validation: (config_server).enable_http_validation(),
                     enable_batch_requests: (config_server).enable_batch_requests(),
@@ -143,6 +202,10 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     script,
                     cors,
                     routes: config_server.get_routes(),
+                    grpc_status_mapping,
+                    rest_errors,
+                    uploads: config_server.get_uploads(),
+                    grpc_service,
                 },
             )
             .to_result()
@@ -191,6 +254,64 @@ fn validate_hostname(hostname: String) -> Valid<IpAddr, BlueprintError> {
     }
 }
 
+fn validate_grpc_status_mapping(
+    mapping: Vec<(tonic::Code, u16)>,
+) -> Valid<StatusMapping, BlueprintError> {
+    Valid::from_iter(mapping, |(code, status)| {
+        match StatusCode::from_u16(status) {
+            Ok(status) => Valid::succeed((code, status)),
+            Err(_) => Valid::fail(BlueprintError::InvalidGrpcStatusMapping(status)),
+        }
+    })
+    .map(StatusMapping::new)
+    .trace("grpcStatusMapping")
+    .trace("@server")
+    .trace("schema")
+}
+
+fn validate_rest_errors(rest_errors: config::RestErrors) -> Valid<ErrorMapping, BlueprintError> {
+    let format = rest_errors.format.unwrap_or_default();
+    Valid::from_iter(
+        rest_errors.status_mapping,
+        |mapping| match StatusCode::from_u16(mapping.http_status) {
+            Ok(status) => Valid::succeed((mapping.error, status)),
+            Err(_) => Valid::fail(BlueprintError::InvalidRestStatusMapping(
+                mapping.http_status,
+            )),
+        },
+    )
+    .map(|overrides| ErrorMapping::new(format, overrides))
+    .trace("restErrors")
+    .trace("@server")
+    .trace("schema")
+}
+
+fn validate_grpc_service(server: &config::Server) -> Valid<Option<GrpcService>, BlueprintError> {
+    let Some(grpc_service) = &server.grpc_service else {
+        return Valid::succeed(None);
+    };
+
+    let package = grpc_service.get_package();
+    let is_identifier = |part: &str| {
+        part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    if server.clone().get_version() != HttpVersion::HTTP2 {
+        Valid::fail(BlueprintError::GrpcServiceRequiresHTTP2)
+    } else if !package.split('.').all(is_identifier) {
+        Valid::fail(BlueprintError::InvalidProtoPackage(package))
+    } else {
+        Valid::succeed(Some(GrpcService {
+            package,
+            max_depth: grpc_service.get_max_depth(),
+        }))
+    }
+    .trace("grpcService")
+    .trace("@server")
+    .trace("schema")
+}
+
 fn handle_response_headers(
     resp_headers: Vec<(String, String)>,
 ) -> Valid<HeaderMap, BlueprintError> {
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use http::header::HeaderName;
+use tailcall_valid::{Valid, Validator};
+
+use super::BlueprintError;
+use crate::core::http::{AwsSigV4, Hmac, Signing};
+use crate::core::{config, Mustache};
+
+/// Resolves the signing settings of an `@http` operator, `oauth2` is set when
+/// the operator attaches OAuth2 tokens to its requests.
+pub fn to_signing(
+    signing: Option<&config::Signing>,
+    batch_key: &[String],
+    oauth2: bool,
+) -> Valid<Option<Arc<Signing>>, BlueprintError> {
+    let Some(signing) = signing else {
+        return Valid::succeed(None);
+    };
+
+    if !batch_key.is_empty() {
+        return Valid::fail(BlueprintError::SigningWithBatchingNotSupported).trace("signing");
+    }
+
+    if oauth2 {
+        return Valid::fail(BlueprintError::SigningWithOAuth2NotSupported).trace("signing");
+    }
+
+    match signing {
+        config::Signing::AwsSigV4(aws) => {
+            Valid::succeed(Some(Arc::new(Signing::AwsSigV4(AwsSigV4 {
+                region: aws.region.clone(),
+                service: aws.service.clone(),
+                access_key_id: Mustache::parse(&aws.access_key_id),
+                secret_access_key: Mustache::parse(&aws.secret_access_key),
+                session_token: aws.session_token.as_deref().map(Mustache::parse),
+            }))))
+        }
+        config::Signing::Hmac(hmac) => {
+            let header = hmac.get_header();
+            match HeaderName::from_bytes(header.as_bytes()) {
+                Ok(header) => Valid::succeed(Some(Arc::new(Signing::Hmac(Hmac {
+                    secret: Mustache::parse(&hmac.secret),
+                    algorithm: hmac.algorithm,
+                    header,
+                })))),
+                Err(_) => Valid::fail(BlueprintError::InvalidSigningHeader(header)).trace("header"),
+            }
+        }
+    }
+    .trace("signing")
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::*;
+
+    fn hmac(header: Option<&str>) -> config::Signing {
+        config::Signing::Hmac(config::Hmac {
+            secret: "{{.env.SECRET}}".to_string(),
+            header: header.map(str::to_string),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_signing_with_batch_key() {
+        let result = to_signing(Some(&hmac(None)), &["id".to_string()], false).to_result();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_signing_with_oauth2() {
+        let result = to_signing(Some(&hmac(None)), &[], true).to_result();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_hmac_header() {
+        let result = to_signing(Some(&hmac(Some("X Signature"))), &[], false).to_result();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_default_hmac_header() {
+        let signing = to_signing(Some(&hmac(None)), &[], false)
+            .to_result()
+            .unwrap();
+        match signing.as_deref() {
+            Some(Signing::Hmac(hmac)) => assert_eq!(hmac.header, "x-signature"),
+            _ => panic!("expected hmac signing"),
+        }
+    }
+}
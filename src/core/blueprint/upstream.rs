@@ -1,16 +1,64 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use derive_setters::Setters;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::BlueprintError;
-use crate::core::config::{self, Batch, ConfigModule};
+use crate::core::config::{self, Batch, Config, ConfigModule, Content, Extensions, Resolver};
+use crate::core::resource_reader::FileRead;
 
 #[derive(PartialEq, Eq, Clone, Debug, schemars::JsonSchema)]
 pub struct Proxy {
     pub url: String,
 }
 
+/// PEM encoded file linked for upstream TLS.
+#[derive(PartialEq, Eq, Clone, Debug, schemars::JsonSchema)]
+pub struct Pem {
+    pub path: String,
+    pub content: String,
+}
+
+impl From<&FileRead> for Pem {
+    fn from(file: &FileRead) -> Self {
+        Self { path: file.path.clone(), content: file.content.clone() }
+    }
+}
+
+/// Certificate presented to upstreams that require mutual TLS.
+#[derive(PartialEq, Eq, Clone, Debug, schemars::JsonSchema)]
+pub struct ClientIdentity {
+    /// Certificate chain presented to the upstream
+    pub cert: Pem,
+    /// Private key of the certificate
+    pub key: Pem,
+    /// Hosts the certificate is presented to, every host if empty
+    pub hosts: BTreeSet<String>,
+}
+
+impl ClientIdentity {
+    /// Certificate chain followed by its private key, as expected by the
+    /// HTTP client.
+    pub fn pem(&self) -> String {
+        format!("{}\n{}", self.cert.content.trim_end(), self.key.content)
+    }
+}
+
+/// Health checking of a gRPC upstream, merged across the fields calling it.
+#[derive(PartialEq, Eq, Clone, Debug, schemars::JsonSchema)]
+pub struct GrpcHealthCheck {
+    pub url: String,
+    pub service: String,
+    /// Interval between the checks in seconds
+    pub interval: u64,
+    pub required: bool,
+    /// Headers of the `@grpc` fields calling the upstream, rendered with the
+    /// environment and the server variables.
+    pub headers: BTreeMap<String, String>,
+    /// The OAuth2 settings of `@upstream`.
+    pub oauth2: Option<config::OAuth2>,
+}
+
 #[derive(PartialEq, Eq, Clone, Debug, Setters, schemars::JsonSchema)]
 pub struct Upstream {
     pub pool_idle_timeout: u64,
@@ -29,6 +77,9 @@ pub struct Upstream {
     pub http2_only: bool,
     pub on_request: Option<String>,
     pub verify_ssl: bool,
+    pub client_certs: Vec<ClientIdentity>,
+    pub ca_certs: Vec<Pem>,
+    pub grpc_health_checks: Vec<GrpcHealthCheck>,
 }
 
 impl Upstream {
@@ -65,7 +116,11 @@ impl TryFrom<&ConfigModule> for Upstream {
 
         get_batch(&config_upstream)
             .fuse(get_proxy(&config_upstream))
-            .map(|(batch, proxy)| Upstream {
+            .fuse(get_client_certs(
+                &config_upstream,
+                config_module.extensions(),
+            ))
+            .map(|(batch, proxy, client_certs)| Upstream {
                 pool_idle_timeout: (config_upstream).get_pool_idle_timeout(),
                 pool_max_idle_per_host: (config_upstream).get_pool_max_idle_per_host(),
                 keep_alive_interval: (config_upstream).get_keep_alive_interval(),
@@ -82,6 +137,14 @@ impl TryFrom<&ConfigModule> for Upstream {
                 http2_only: (config_upstream).get_http_2_only(),
                 on_request: (config_upstream).get_on_request(),
                 verify_ssl: (config_upstream).get_verify_ssl(),
+                client_certs,
+                ca_certs: config_module
+                    .extensions()
+                    .upstream_ca
+                    .iter()
+                    .map(Pem::from)
+                    .collect(),
+                grpc_health_checks: get_grpc_health_checks(config_module.config()),
             })
             .to_result()
     }
@@ -100,6 +163,58 @@ fn get_batch(upstream: &config::Upstream) -> Valid<Option<Batch>, BlueprintError
     )
 }
 
+/// Collects the health checks of every `@grpc` by upstream and service, the
+/// shortest interval wins and the check is required if any field requires it.
+/// Checks send the headers of every field calling the upstream.
+fn get_grpc_health_checks(config: &Config) -> Vec<GrpcHealthCheck> {
+    let mut checks = BTreeMap::<(String, String), GrpcHealthCheck>::new();
+
+    let resolvers = config.types.values().flat_map(|ty| {
+        ty.resolvers.0.iter().chain(
+            ty.fields
+                .values()
+                .flat_map(|field| field.resolvers.0.iter()),
+        )
+    });
+
+    for resolver in resolvers {
+        let Resolver::Grpc(grpc) = resolver else {
+            continue;
+        };
+        let Some(health_check) = &grpc.health_check else {
+            continue;
+        };
+
+        let url = grpc.url.trim_end_matches('/').to_string();
+        let key = (url.clone(), health_check.service.clone());
+        let interval = health_check.get_interval();
+        let required = health_check.get_required();
+
+        checks
+            .entry(key)
+            .and_modify(|check| {
+                check.interval = check.interval.min(interval);
+                check.required |= required;
+            })
+            .or_insert_with(|| GrpcHealthCheck {
+                url,
+                service: health_check.service.clone(),
+                interval,
+                required,
+                headers: BTreeMap::new(),
+                oauth2: config.upstream.oauth2.clone(),
+            })
+            .headers
+            .extend(
+                grpc.headers
+                    .iter()
+                    .map(|header| (header.key.clone(), header.value.clone())),
+            );
+    }
+
+    checks.into_values().collect()
+}
+
 fn get_proxy(upstream: &config::Upstream) -> Valid<Option<Proxy>, BlueprintError> {
     if let Some(ref proxy) = upstream.proxy {
         Valid::succeed(Some(Proxy { url: proxy.url.clone() }))
@@ -107,3 +222,135 @@ fn get_proxy(upstream: &config::Upstream) -> Valid<Option<Proxy>, BlueprintError
         Valid::succeed(None)
     }
 }
+
+fn get_client_certs(
+    upstream: &config::Upstream,
+    extensions: &Extensions,
+) -> Valid<Vec<ClientIdentity>, BlueprintError> {
+    let find = |links: &[Content<FileRead>], id: &str| {
+        links
+            .iter()
+            .find(|link| link.id.as_deref() == Some(id))
+            .map(|link| Pem::from(&link.content))
+    };
+
+    Valid::from_iter(upstream.get_client_certs(), |client_cert| {
+        let cert = find(&extensions.upstream_certs, &client_cert.cert);
+        let key = find(&extensions.upstream_keys, &client_cert.key);
+
+        match (cert, key) {
+            (None, _) => Valid::fail(BlueprintError::UpstreamCertNotFound(client_cert.cert)),
+            (_, None) => Valid::fail(BlueprintError::UpstreamKeyNotFound(client_cert.key)),
+            (Some(cert), Some(key)) => {
+                Valid::succeed(ClientIdentity { cert, key, hosts: client_cert.hosts })
+            }
+        }
+    })
+    .and_then(|client_certs| {
+        if client_certs.iter().filter(|c| c.hosts.is_empty()).count() > 1 {
+            return Valid::fail(BlueprintError::OnlyOneDefaultClientCertAllowed);
+        }
+
+        let mut hosts = BTreeSet::new();
+        let duplicates = client_certs
+            .iter()
+            .flat_map(|client_cert| client_cert.hosts.iter())
+            .filter(|host| !hosts.insert(*host))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        Valid::from_iter(duplicates, |host| {
+            Valid::<(), _>::fail(BlueprintError::DuplicateClientCertHost(host))
+        })
+        .map_to(client_certs)
+    })
+    .trace("clientCerts")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::core::config::{Config, UpstreamClientCert};
+
+    fn file(path: &str, content: &str) -> FileRead {
+        FileRead { path: path.to_string(), content: content.to_string() }
+    }
+
+    fn config_module(client_certs: Vec<UpstreamClientCert>) -> ConfigModule {
+        let mut config = Config::default();
+        config.upstream.client_certs = Some(client_certs);
+
+        let extensions = Extensions {
+            upstream_certs: vec![Content {
+                id: Some("orders-cert".to_string()),
+                content: file("orders.crt", "CERT\n"),
+            }],
+            upstream_keys: vec![Content {
+                id: Some("orders-key".to_string()),
+                content: file("orders.key", "KEY\n"),
+            }],
+            ..Default::default()
+        };
+
+        ConfigModule::from(config).set_extensions(extensions)
+    }
+
+    fn client_cert(cert: &str, hosts: &[&str]) -> UpstreamClientCert {
+        UpstreamClientCert {
+            cert: cert.to_string(),
+            key: "orders-key".to_string(),
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_client_certs() {
+        let upstream = Upstream::try_from(&config_module(vec![client_cert(
+            "orders-cert",
+            &["orders"],
+        )]))
+        .unwrap();
+
+        assert_eq!(
+            upstream.client_certs,
+            vec![ClientIdentity {
+                cert: Pem::from(&file("orders.crt", "CERT\n")),
+                key: Pem::from(&file("orders.key", "KEY\n")),
+                hosts: BTreeSet::from(["orders".to_string()]),
+            }]
+        );
+        assert_eq!(upstream.client_certs[0].pem(), "CERT\nKEY\n");
+    }
+
+    #[test]
+    fn test_client_certs_unknown_link() {
+        let result = Upstream::try_from(&config_module(vec![client_cert("missing", &[])]));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_client_certs_multiple_defaults() {
+        let result = Upstream::try_from(&config_module(vec![
+            client_cert("orders-cert", &[]),
+            client_cert("orders-cert", &[]),
+        ]));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_client_certs_duplicate_host() {
+        let result = Upstream::try_from(&config_module(vec![
+            client_cert("orders-cert", &["orders", "payments"]),
+            client_cert("orders-cert", &["orders"]),
+        ]));
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Host 'orders' is used by more than one client certificate"));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use tailcall_valid::{Valid, Validator};
+
+use super::{Config, Field, Type};
+
+/// Kind of the configs composed into one, the subgraphs of a supergraph or
+/// the stitched services.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Kind {
+    Subgraph,
+    Service,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Subgraph => "Subgraph",
+            Kind::Service => "Service",
+        }
+    }
+}
+
+/// Merges the definitions of several configs, the first definition of a field
+/// wins. Fails when the configs define a field with different types, their
+/// nullability may differ.
+pub(super) struct Merge<'a> {
+    kind: Kind,
+    config: Config,
+    /// Config defining each field of the merged types.
+    defined_in: BTreeMap<(String, String), &'a str>,
+    conflicts: Vec<(String, String, String)>,
+}
+
+impl<'a> Merge<'a> {
+    pub fn new(kind: Kind) -> Self {
+        let mut config = Config::default();
+        config.schema.query = Some("Query".to_string());
+
+        Self { kind, config, defined_in: BTreeMap::new(), conflicts: vec![] }
+    }
+
+    /// Merges the type `ty` of the config `source` into `type_name`. The
+    /// fields it's the first to define are added as returned by `field`.
+    pub fn add_type(
+        &mut self,
+        source: &'a str,
+        type_name: &str,
+        ty: &Type,
+        field: impl Fn(&str, &Field) -> Field,
+    ) {
+        let merged = self
+            .config
+            .types
+            .entry(type_name.to_string())
+            .or_insert_with(|| Type {
+                doc: ty.doc.clone(),
+                implements: ty.implements.clone(),
+                ..Default::default()
+            });
+
+        for (field_name, definition) in ty.fields.iter() {
+            let key = (type_name.to_string(), field_name.clone());
+
+            if let Some(merged_field) = merged.fields.get(field_name) {
+                if !is_compatible(&merged_field.type_of, &definition.type_of) {
+                    let kind = self.kind.name().to_lowercase();
+                    self.conflicts.push((
+                        type_name.to_string(),
+                        field_name.clone(),
+                        format!(
+                            "Field has the type `{:?}` in {kind} `{}` and `{:?}` in {kind} `{source}`",
+                            merged_field.type_of, self.defined_in[&key], definition.type_of
+                        ),
+                    ));
+                }
+                continue;
+            }
+
+            self.defined_in.insert(key, source);
+            merged
+                .fields
+                .insert(field_name.clone(), field(field_name, definition));
+        }
+    }
+
+    /// Merges the enums and the unions of a config, along with their variants
+    /// and members.
+    pub fn add_enums_and_unions(&mut self, config: &Config) {
+        for (name, enum_) in config.enums.iter() {
+            let merged = self
+                .config
+                .enums
+                .entry(name.clone())
+                .or_insert_with(|| enum_.clone());
+            merged.variants.extend(enum_.variants.iter().cloned());
+        }
+
+        for (name, union_) in config.unions.iter() {
+            let merged = self
+                .config
+                .unions
+                .entry(name.clone())
+                .or_insert_with(|| union_.clone());
+            merged.types.extend(union_.types.iter().cloned());
+        }
+    }
+
+    pub fn finish(self) -> Valid<Config, String> {
+        Valid::from_iter(self.conflicts, |(type_name, field_name, message)| {
+            Valid::<(), String>::fail(message)
+                .trace(&field_name)
+                .trace(&type_name)
+        })
+        .map_to(self.config)
+    }
+}
+
+/// Checks if two configs agree on the type of a field, ignoring its
+/// nullability.
+fn is_compatible(left: &crate::core::Type, right: &crate::core::Type) -> bool {
+    use crate::core::Type::{List, Named};
+
+    match (left, right) {
+        (Named { name: left, .. }, Named { name: right, .. }) => left == right,
+        (List { of_type: left, .. }, List { of_type: right, .. }) => is_compatible(left, right),
+        _ => false,
+    }
+}
+
+/// Checks that every config defining the type defines the fields of its keys,
+/// they're selected along with the type.
+pub(super) fn check_key_fields<'a>(
+    kind: Kind,
+    type_name: &str,
+    key_fields: BTreeSet<String>,
+    configs: impl Iterator<Item = (&'a str, &'a Config)>,
+) -> Valid<BTreeSet<String>, String> {
+    let missing = configs.flat_map(|(name, config)| {
+        let ty = config.types.get(type_name);
+        key_fields
+            .iter()
+            .filter(move |field| ty.is_some_and(|ty| !ty.fields.contains_key(*field)))
+            .map(move |field| (name, field))
+    });
+
+    Valid::from_iter(missing, |(name, field)| {
+        Valid::<(), String>::fail(format!(
+            "{} `{name}` doesn't define the key field `{field}`",
+            kind.name()
+        ))
+    })
+    .map_to(key_fields)
+}
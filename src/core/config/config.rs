@@ -682,6 +682,7 @@ pub enum Encoding {
     #[default]
     ApplicationJson,
     ApplicationXWwwFormUrlencoded,
+    MultipartFormData,
 }
 
 #[cfg(test)]
@@ -3,13 +3,16 @@ use std::ops::Deref;
 
 use jsonwebtoken::jwk::JwkSet;
 use prost_reflect::prost_types::{FileDescriptorProto, FileDescriptorSet};
-use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use rustls_pki_types::{
+    CertificateDer, PrivateKeyDer, PrivatePkcs1KeyDer, PrivatePkcs8KeyDer, PrivateSec1KeyDer,
+};
 use tailcall_valid::{Valid, Validator};
 
 use crate::core::config::Config;
 use crate::core::macros::MergeRight;
 use crate::core::merge_right::MergeRight;
 use crate::core::proto_reader::ProtoMetadata;
+use crate::core::resource_reader::FileRead;
 use crate::core::rest::{EndpointSet, Unchecked};
 use crate::core::Transform;
 
@@ -119,6 +122,28 @@ impl From<PrivateKeyDer<'static>> for PrivateKey {
 }
 
 impl PrivateKey {
+    /// Reads the RSA, EC and PKCS#8 private keys of a PEM file.
+    pub fn from_pem(content: &str) -> anyhow::Result<Vec<PrivateKey>> {
+        let keys = rustls_pemfile::read_all(&mut content.as_bytes())?;
+
+        Ok(keys
+            .into_iter()
+            .filter_map(|key| match key {
+                rustls_pemfile::Item::RSAKey(key) => {
+                    Some(PrivateKeyDer::Pkcs1(PrivatePkcs1KeyDer::from(key)))
+                }
+                rustls_pemfile::Item::ECKey(key) => {
+                    Some(PrivateKeyDer::Sec1(PrivateSec1KeyDer::from(key)))
+                }
+                rustls_pemfile::Item::PKCS8Key(key) => {
+                    Some(PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key)))
+                }
+                _ => None,
+            })
+            .map(PrivateKey::from)
+            .collect())
+    }
+
     pub fn into_inner(self) -> PrivateKeyDer<'static> {
         self.0
     }
@@ -141,6 +166,18 @@ pub struct Extensions {
     /// Contains the key used on HTTP2 with TLS
     pub keys: Vec<PrivateKey>,
 
+    /// Contains the CA bundles used to verify client certificates on HTTP2
+    pub client_ca: Vec<Content<Vec<CertificateDer<'static>>>>,
+
+    /// Contains the PEM encoded certificates presented to upstreams
+    pub upstream_certs: Vec<Content<FileRead>>,
+
+    /// Contains the PEM encoded keys of the upstream certificates
+    pub upstream_keys: Vec<Content<FileRead>>,
+
+    /// Contains the PEM encoded CA bundles trusted for upstream connections
+    pub upstream_ca: Vec<FileRead>,
+
     /// Contains the endpoints
     pub endpoint_set: EndpointSet<Unchecked>,
 
@@ -162,7 +199,7 @@ impl Extensions {
     }
 
     pub fn has_auth(&self) -> bool {
-        !self.htpasswd.is_empty() || !self.jwks.is_empty()
+        !self.htpasswd.is_empty() || !self.jwks.is_empty() || !self.client_ca.is_empty()
     }
 }
 
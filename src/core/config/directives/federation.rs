@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use async_graphql::parser::types::{DocumentOperations, Selection, SelectionSet};
 use serde::{Deserialize, Serialize};
 use tailcall_macros::MergeRight;
 
@@ -41,3 +42,124 @@ pub struct Key {
 pub struct EntityResolver {
     pub resolver_by_type: BTreeMap<String, Resolver>,
 }
+
+/// Resolver of the supergraph gateway, it fetches a field of an entity from the
+/// subgraph owning it with an `_entities` query.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityFetch {
+    pub url: String,
+    pub type_name: String,
+    /// Representation of the entity as a GraphQL literal, e.g. `{ __typename:
+    /// "User", id: {{.value.id}} }`.
+    pub representation: String,
+    pub selection: EntitySelection,
+    /// Fetches of the fields required with `@requires`, their results are
+    /// merged and available as `.args` in the representation.
+    pub requires: Vec<EntityFetch>,
+    /// Subgraphs selecting the field along with the entity, the field isn't
+    /// fetched when the entity comes from one of them.
+    pub sources: Vec<FieldSource>,
+}
+
+/// A subgraph, or a stitched service, resolving a field of an entity without a
+/// query of its own.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FieldSource {
+    pub url: String,
+    /// Type and field returning the entity with `@provides`, e.g. `("Review",
+    /// "product")`. Unset when the subgraph resolves the field itself.
+    pub provided_by: Option<(String, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntitySelection {
+    /// The field along with the names of its arguments, the value of the field
+    /// is returned.
+    Field { name: String, args: Vec<String> },
+    /// A fixed set of fields, the entity itself is returned.
+    Fields(String),
+}
+
+impl Default for EntitySelection {
+    fn default() -> Self {
+        Self::Fields(String::new())
+    }
+}
+
+/// Selection of fields used by `@key`, `@requires` and `@provides`, e.g. `id
+/// organization { id }`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldSet(pub Vec<(String, FieldSet)>);
+
+impl FieldSet {
+    pub fn parse(fields: &str) -> Option<Self> {
+        if fields.trim().is_empty() {
+            return Some(Self::default());
+        }
+
+        let operations = async_graphql::parser::parse_query(format!("{{ {fields} }}"))
+            .map(|document| document.operations);
+
+        match operations {
+            Ok(DocumentOperations::Single(operation)) => {
+                Some(Self::from_selection_set(&operation.node.selection_set.node))
+            }
+            _ => None,
+        }
+    }
+
+    fn from_selection_set(selection_set: &SelectionSet) -> Self {
+        Self(
+            selection_set
+                .items
+                .iter()
+                .filter_map(|selection| match &selection.node {
+                    Selection::Field(field) => Some((
+                        field.node.name.node.to_string(),
+                        Self::from_selection_set(&field.node.selection_set.node),
+                    )),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn filter(&self, f: impl Fn(&str) -> bool) -> Self {
+        Self(self.0.iter().filter(|(name, _)| f(name)).cloned().collect())
+    }
+
+    /// Fields of an object literal whose values are read from `source`, e.g.
+    /// `id: {{.value.id}}`.
+    pub fn literal(&self, source: &str) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(name, fields)| {
+                let path = format!("{source}.{name}");
+                if fields.0.is_empty() {
+                    format!("{name}: {{{{{path}}}}}")
+                } else {
+                    format!("{name}: {{ {} }}", fields.literal(&path).join(", "))
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for FieldSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = self
+            .0
+            .iter()
+            .map(|(name, fields)| match fields.0.is_empty() {
+                true => name.clone(),
+                false => format!("{name} {{ {fields} }}"),
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", fields.join(" "))
+    }
+}
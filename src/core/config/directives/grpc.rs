@@ -4,6 +4,7 @@ use tailcall_macros::{DirectiveDefinition, InputDefinition};
 
 use crate::core::config::KeyValue;
 use crate::core::is_default;
+use crate::core::macros::MergeRight;
 
 #[derive(
     Serialize,
@@ -47,7 +48,8 @@ pub struct Grpc {
     /// automatically set to application/grpc
     pub headers: Vec<KeyValue>,
     /// This refers to the gRPC method you're going to call. For instance
-    /// `GetAllNews`.
+    /// `GetAllNews`. Server-streaming methods resolve to the list of every
+    /// message sent by the server, so the field must be a list.
     pub method: String,
     #[serde(default, skip_serializing_if = "is_default")]
     /// Enables deduplication of IO operations to enhance performance.
@@ -74,4 +76,183 @@ pub struct Grpc {
     /// body before it's sent back to the client.
     #[serde(rename = "onResponseBody", default, skip_serializing_if = "is_default")]
     pub on_response_body: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `compression` parameter compresses the request messages with the
+    /// given algorithm and overrides `grpcCompression` of `@upstream`.
+    /// Compressed responses are decoded regardless of this setting.
+    pub compression: Option<GrpcCompression>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `protocol` used to reach the upstream. `GrpcWeb`, `Connect` and
+    /// `ConnectJson` are sent over HTTP/1.1 and work with backends behind
+    /// proxies that don't support HTTP/2 trailers. @default `Grpc`.
+    pub protocol: Option<GrpcProtocol>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `timeout` of the call in milliseconds, sent to the upstream as the
+    /// `grpc-timeout` deadline. When `globalResponseTimeout` is set, the
+    /// deadline is shortened to the time the request has left.
+    pub timeout: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `healthCheck` periodically probes the upstream with
+    /// `grpc.health.v1.Health/Check`. The results are reported by the status
+    /// route and gate the readiness route of `@server`.
+    pub health_check: Option<GrpcHealthCheck>,
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+/// Health checking of a gRPC upstream.
+pub struct GrpcHealthCheck {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Name of the service to check, the server as a whole is checked when
+    /// empty.
+    pub service: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Interval between the checks in seconds. @default `10`.
+    pub interval: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Whether the readiness route waits for the upstream to be serving.
+    /// @default `true`.
+    pub required: Option<bool>,
+}
+
+impl GrpcHealthCheck {
+    pub fn get_interval(&self) -> u64 {
+        self.interval.unwrap_or(10)
+    }
+
+    pub fn get_required(&self) -> bool {
+        self.required.unwrap_or(true)
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+/// Algorithm used to compress gRPC messages.
+pub enum GrpcCompression {
+    #[default]
+    Identity,
+    Gzip,
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+/// Wire protocol used to call a gRPC upstream.
+pub enum GrpcProtocol {
+    /// Native gRPC over HTTP/2.
+    #[default]
+    Grpc,
+    /// gRPC-Web with binary messages, trailers are sent in the body.
+    GrpcWeb,
+    /// The Connect protocol with binary messages.
+    Connect,
+    /// The Connect protocol with JSON messages.
+    ConnectJson,
+}
+
+impl GrpcCompression {
+    /// Name of the algorithm in the `grpc-encoding` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrpcCompression::Identity => "identity",
+            GrpcCompression::Gzip => "gzip",
+        }
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// Status codes defined by the gRPC protocol.
+pub enum GrpcCode {
+    #[default]
+    Ok,
+    Cancelled,
+    Unknown,
+    InvalidArgument,
+    DeadlineExceeded,
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    ResourceExhausted,
+    FailedPrecondition,
+    Aborted,
+    OutOfRange,
+    Unimplemented,
+    Internal,
+    Unavailable,
+    DataLoss,
+    Unauthenticated,
+}
+
+impl From<GrpcCode> for tonic::Code {
+    fn from(code: GrpcCode) -> Self {
+        match code {
+            GrpcCode::Ok => tonic::Code::Ok,
+            GrpcCode::Cancelled => tonic::Code::Cancelled,
+            GrpcCode::Unknown => tonic::Code::Unknown,
+            GrpcCode::InvalidArgument => tonic::Code::InvalidArgument,
+            GrpcCode::DeadlineExceeded => tonic::Code::DeadlineExceeded,
+            GrpcCode::NotFound => tonic::Code::NotFound,
+            GrpcCode::AlreadyExists => tonic::Code::AlreadyExists,
+            GrpcCode::PermissionDenied => tonic::Code::PermissionDenied,
+            GrpcCode::ResourceExhausted => tonic::Code::ResourceExhausted,
+            GrpcCode::FailedPrecondition => tonic::Code::FailedPrecondition,
+            GrpcCode::Aborted => tonic::Code::Aborted,
+            GrpcCode::OutOfRange => tonic::Code::OutOfRange,
+            GrpcCode::Unimplemented => tonic::Code::Unimplemented,
+            GrpcCode::Internal => tonic::Code::Internal,
+            GrpcCode::Unavailable => tonic::Code::Unavailable,
+            GrpcCode::DataLoss => tonic::Code::DataLoss,
+            GrpcCode::Unauthenticated => tonic::Code::Unauthenticated,
+        }
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+/// Overrides the HTTP status code returned by `@rest` endpoints when a gRPC
+/// upstream fails with `grpcCode`.
+pub struct GrpcStatusMapping {
+    pub grpc_code: GrpcCode,
+    pub http_status: u16,
 }
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tailcall_macros::{DirectiveDefinition, InputDefinition};
 
-use crate::core::config::{Encoding, KeyValue, URLQuery};
+use crate::core::config::{Encoding, KeyValue, OAuth2, Signing, URLQuery};
 use crate::core::http::Method;
 use crate::core::is_default;
 use crate::core::json::JsonSchema;
@@ -46,8 +46,9 @@ pub struct Http {
 
     #[serde(default, skip_serializing_if = "is_default")]
     /// The `encoding` parameter specifies the encoding of the request body. It
-    /// can be `ApplicationJson` or `ApplicationXWwwFormUrlEncoded`. @default
-    /// `ApplicationJson`.
+    /// can be `ApplicationJson`, `ApplicationXWwwFormUrlEncoded` or
+    /// `MultipartFormData`, with which `Upload` values of the body are sent as
+    /// files. @default `ApplicationJson`.
     pub encoding: Encoding,
 
     #[serde(rename = "batchKey", default, skip_serializing_if = "is_default")]
@@ -70,6 +71,13 @@ pub struct Http {
     /// include `GET`, `POST`, `PUT`, `DELETE` etc. @default `GET`.
     pub method: Method,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `oauth2` parameter obtains an access token with the OAuth2 client
+    /// credentials grant and sends it with every request made by the `@http`
+    /// operator. It takes precedence over the `oauth2` setting of
+    /// `@upstream`.
+    pub oauth2: Option<OAuth2>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// Schema of the output of the API call. It is automatically inferred in
     /// most cases.
@@ -83,6 +91,13 @@ pub struct Http {
     /// first parameter referencing a field in the current value using mustache
     /// syntax is automatically selected as the batching parameter.
     pub query: Vec<URLQuery>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `signing` parameter signs every request made by the `@http`
+    /// operator, either with AWS Signature Version 4 or with an HMAC of the
+    /// body. Credentials can be read from the environment or vars using
+    /// mustache templates.
+    pub signing: Option<Signing>,
     #[serde(default, skip_serializing_if = "is_default")]
     /// Enables deduplication of IO operations to enhance performance.
     ///
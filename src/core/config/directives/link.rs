@@ -42,6 +42,24 @@ pub enum LinkType {
     /// that can be exposed a REST API using the `@rest` directive.
     Operation,
 
+    /// Points to a CA bundle. Client certificates presented to the server over
+    /// HTTP2 are verified against it, and the link can be referenced by the
+    /// `@protected` directive to require a verified client certificate.
+    ClientCA,
+
+    /// Points to a PEM file with a certificate chain presented to upstreams
+    /// that require mutual TLS. The link is referenced by its id from
+    /// `@upstream(clientCerts)`.
+    UpstreamCert,
+
+    /// Points to the private key of an `UpstreamCert` link. The link is
+    /// referenced by its id from `@upstream(clientCerts)`.
+    UpstreamKey,
+
+    /// Points to a CA bundle that is trusted in addition to the webpki roots
+    /// when connecting to upstreams.
+    UpstreamCA,
+
     /// Points to a Htpasswd file. The imported Htpasswd file will be used by
     /// the server to authenticate users.
     Htpasswd,
@@ -53,6 +71,18 @@ pub enum LinkType {
     /// Points to a reflection endpoint. The imported reflection endpoint will
     /// be used by the `@grpc` directive to resolve data from gRPC services.
     Grpc,
+
+    /// Points to the endpoint of an Apollo Federation v2 subgraph, its SDL is
+    /// read from `_service { sdl }`. The linked subgraphs are composed into the
+    /// schema, the fields of entities are fetched from the subgraphs owning
+    /// them with `_entities` queries, batched with `@upstream(batch)`.
+    SubGraph,
+
+    /// Points to the endpoint of a GraphQL service, its introspected schema is
+    /// stitched into the schema. A type defined by several linked services is
+    /// merged, its fields are looked up from the services defining them with a
+    /// root field that returns the type by its keys, e.g. `userById(id: ID!)`.
+    GraphQL,
 }
 
 /// The @link directive allows you to import external resources, such as
@@ -98,4 +128,10 @@ pub struct Link {
     /// Only valid when [`Link::type_of`] is [`LinkType::Protobuf`]
     #[serde(default, skip_serializing_if = "is_default")]
     pub proto_paths: Option<Vec<String>>,
+    /// Interval in seconds at which the descriptors are fetched again from the
+    /// reflection endpoint. New descriptors are only used when they are
+    /// compatible with the schema. Only valid when [`Link::type_of`] is
+    /// [`LinkType::Grpc`]
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub refresh_interval: Option<u64>,
 }
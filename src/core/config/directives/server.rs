@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use tailcall_macros::DirectiveDefinition;
 
 use crate::core::config::headers::Headers;
-use crate::core::config::{merge_key_value_vecs, KeyValue};
+use crate::core::config::{merge_key_value_vecs, GrpcStatusMapping, KeyValue};
 use crate::core::is_default;
 use crate::core::macros::MergeRight;
 
@@ -45,6 +45,12 @@ pub struct Server {
     /// debugging. Use judiciously. @default `false`.
     pub batch_requests: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `grpcService` serves the schema as a gRPC service on the HTTP/2 server,
+    /// with one service per root type and server reflection enabled. The
+    /// generated `.proto` can be displayed with `tailcall check --proto`.
+    pub grpc_service: Option<GrpcService>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `headers` contains key-value pairs that are included as default headers
     /// in server responses, allowing for consistent header management across
@@ -56,6 +62,13 @@ pub struct Server {
     /// termination, acting as a safeguard against long-running queries.
     pub global_response_timeout: Option<i64>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `grpcStatusMapping` sets the HTTP status code that `@rest` endpoints
+    /// respond with when a gRPC upstream fails with the given status. Codes
+    /// without an entry follow the standard mapping, e.g. `NOT_FOUND` becomes
+    /// `404` and `UNAVAILABLE` becomes `503`.
+    pub grpc_status_mapping: Vec<GrpcStatusMapping>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `hostname` sets the server hostname.
     pub hostname: Option<String>,
@@ -66,8 +79,18 @@ pub struct Server {
     /// and operations. @default `true`.
     pub introspection: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `introspectionFilter` hides `@protected` fields and types from
+    /// introspection results when the caller doesn't satisfy their auth
+    /// providers, so that every client only discovers the part of the schema
+    /// it can query. @default `false`.
+    pub introspection_filter: Option<bool>,
+
     /// `enableFederation` enables functionality to Tailcall server to act
-    /// as a federation subgraph.
+    /// as a federation subgraph. The `@external` fields sent in the
+    /// representations of `_entities` are available as `.value` for the
+    /// fields that `@requires` them, and `@inaccessible` fields and types are
+    /// left out of the API schema.
     #[serde(default, skip_serializing_if = "is_default")]
     pub enable_federation: Option<bool>,
 
@@ -91,6 +114,11 @@ pub struct Server {
     /// upstream services using inferred schema. @default `false`.
     pub response_validation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `restErrors` sets the status code and the body that `@rest` endpoints
+    /// respond with when the query fails or its root field is `null`.
+    pub rest_errors: Option<RestErrors>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// A link to an external JS file that listens on every HTTP request
     /// response event.
@@ -100,6 +128,12 @@ pub struct Server {
     /// `showcase` enables the /showcase/graphql endpoint.
     pub showcase: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `uploads` limits GraphQL multipart requests, their files are read into
+    /// memory before the query is executed. Clients must send the
+    /// `Apollo-Require-Preflight` header along with the request.
+    pub uploads: Option<Uploads>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     #[merge_right(merge_right_fn = "merge_right_vars")]
     /// This configuration defines local variables for server operations. Useful
@@ -121,8 +155,10 @@ pub struct Server {
     /// It provides options to change the default paths for status and GraphQL
     /// endpoints. Default values are:
     /// - status: "/status"
-    /// - graphQL: "/graphql" If not specified, these default values will be
-    ///   used.
+    /// - graphQL: "/graphql"
+    /// - readiness: "/ready"
+    /// - openapi: "/openapi.json" If not specified, these default values will
+    ///   be used.
     pub routes: Option<Routes>,
 }
 
@@ -132,6 +168,20 @@ pub struct Routes {
     status: String,
     #[serde(rename = "graphQL", default = "default_graphql")]
     graphql: String,
+    /// Responds with `503` until the upstreams with a required `healthCheck`
+    /// are serving.
+    #[serde(
+        default = "default_readiness",
+        skip_serializing_if = "is_default_readiness"
+    )]
+    readiness: String,
+    /// Serves the OpenAPI document of the `@rest` endpoints, as YAML when the
+    /// `Accept` header asks for it.
+    #[serde(
+        default = "default_openapi",
+        skip_serializing_if = "is_default_openapi"
+    )]
+    openapi: String,
 }
 
 fn default_status() -> String {
@@ -142,22 +192,185 @@ fn default_graphql() -> String {
     "/graphql".into()
 }
 
+fn default_readiness() -> String {
+    "/ready".into()
+}
+
+fn is_default_readiness(readiness: &str) -> bool {
+    readiness == default_readiness()
+}
+
+fn default_openapi() -> String {
+    "/openapi.json".into()
+}
+
+fn is_default_openapi(openapi: &str) -> bool {
+    openapi == default_openapi()
+}
+
 impl Default for Routes {
     fn default() -> Self {
-        Self { status: "/status".into(), graphql: "/graphql".into() }
+        Self {
+            status: default_status(),
+            graphql: default_graphql(),
+            readiness: default_readiness(),
+            openapi: default_openapi(),
+        }
     }
 }
 
 impl Routes {
     pub fn with_status<T: Into<String>>(self, status: T) -> Self {
-        Self { graphql: self.graphql, status: status.into() }
+        Self { status: status.into(), ..self }
     }
 
     pub fn with_graphql<T: Into<String>>(self, graphql: T) -> Self {
-        Self { status: self.status, graphql: graphql.into() }
+        Self { graphql: graphql.into(), ..self }
+    }
+
+    pub fn with_readiness<T: Into<String>>(self, readiness: T) -> Self {
+        Self { readiness: readiness.into(), ..self }
+    }
+
+    pub fn with_openapi<T: Into<String>>(self, openapi: T) -> Self {
+        Self { openapi: openapi.into(), ..self }
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GrpcService {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `package` of the generated `.proto`. @default `tailcall`.
+    pub package: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxDepth` limits how deep nested objects are selected in responses,
+    /// as the schema may contain cycles. @default `3`.
+    pub max_depth: Option<usize>,
+}
+
+impl GrpcService {
+    pub fn get_package(&self) -> String {
+        self.package.clone().unwrap_or("tailcall".to_string())
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth.unwrap_or(3)
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Uploads {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxFileSize` is the size in bytes above which a file is rejected.
+    /// @default `10485760` (10 MiB).
+    pub max_file_size: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxFiles` is the number of files accepted in a request. @default `10`.
+    pub max_files: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxRequestSize` is the size in bytes above which a multipart request,
+    /// including its `operations` and `map` fields, is rejected. @default
+    /// `104857600` (100 MiB).
+    pub max_request_size: Option<u64>,
+}
+
+impl Uploads {
+    pub fn get_max_file_size(&self) -> u64 {
+        self.max_file_size.unwrap_or(10 * 1024 * 1024)
+    }
+
+    pub fn get_max_files(&self) -> usize {
+        self.max_files.unwrap_or(10)
+    }
+
+    pub fn get_max_request_size(&self) -> u64 {
+        self.max_request_size.unwrap_or(100 * 1024 * 1024)
     }
 }
 
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct RestErrors {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `format` of the error body, `GRAPHQL` responds with the GraphQL errors
+    /// and `PROBLEM_JSON` with an RFC 7807 problem. @default `GRAPHQL`.
+    pub format: Option<RestErrorFormat>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `statusMapping` overrides the status code of a kind of error. Defaults
+    /// are `404` for `NOT_FOUND`, `400` for `BAD_REQUEST`, `401` for
+    /// `UNAUTHENTICATED`, `403` for `FORBIDDEN` and `500` for `INTERNAL`.
+    pub status_mapping: Vec<RestStatusMapping>,
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RestErrorFormat {
+    #[default]
+    Graphql,
+    ProblemJson,
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// Kinds of failures of `@rest` endpoints.
+pub enum RestError {
+    /// The root field of a query fetched with `GET` resolved to `null`.
+    NotFound,
+    /// The parameters or the body of the request are invalid.
+    BadRequest,
+    /// The request failed authentication.
+    Unauthenticated,
+    /// The request is authenticated but isn't allowed.
+    Forbidden,
+    /// Any other error raised while executing the query.
+    #[default]
+    Internal,
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct RestStatusMapping {
+    pub error: RestError,
+    pub http_status: u16,
+}
+
 fn merge_right_vars(mut left: Vec<KeyValue>, right: Vec<KeyValue>) -> Vec<KeyValue> {
     left = merge_key_value_vecs(&left, &right);
     left
@@ -212,6 +425,9 @@ impl Server {
     pub fn enable_introspection(&self) -> bool {
         self.introspection.unwrap_or(true)
     }
+    pub fn enable_introspection_filter(&self) -> bool {
+        self.introspection_filter.unwrap_or(false)
+    }
     pub fn enable_query_validation(&self) -> bool {
         self.query_validation.unwrap_or(false)
     }
@@ -234,6 +450,21 @@ impl Server {
             .collect()
     }
 
+    pub fn get_grpc_status_mapping(&self) -> Vec<(tonic::Code, u16)> {
+        self.grpc_status_mapping
+            .iter()
+            .map(|mapping| (mapping.grpc_code.into(), mapping.http_status))
+            .collect()
+    }
+
+    pub fn get_rest_errors(&self) -> RestErrors {
+        self.rest_errors.clone().unwrap_or_default()
+    }
+
+    pub fn get_uploads(&self) -> Uploads {
+        self.uploads.clone().unwrap_or_default()
+    }
+
     pub fn get_response_headers(&self) -> Vec<(String, String)> {
         self.headers
             .as_ref()
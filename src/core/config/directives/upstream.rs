@@ -4,6 +4,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use tailcall_macros::{DirectiveDefinition, InputDefinition};
 
+use crate::core::config::{GrpcCompression, OAuth2};
 use crate::core::macros::MergeRight;
 use crate::core::{default_verify_ssl, is_default, verify_ssl_is_default};
 
@@ -34,6 +35,22 @@ pub struct Proxy {
     pub url: String,
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, schemars::JsonSchema, MergeRight)]
+#[serde(rename_all = "camelCase")]
+/// A client certificate presented to upstreams that require mutual TLS.
+pub struct UpstreamClientCert {
+    /// Id of the `UpstreamCert` link that contains the certificate chain.
+    pub cert: String,
+
+    /// Id of the `UpstreamKey` link that contains the private key.
+    pub key: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Hosts the certificate is presented to. If empty, the certificate is
+    /// presented to every upstream that has no certificate of its own.
+    pub hosts: BTreeSet<String>,
+}
+
 #[derive(
     Serialize,
     Deserialize,
@@ -73,11 +90,23 @@ pub struct Upstream {
     /// the batch).
     pub batch: Option<Batch>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `clientCerts` lists the certificates presented to upstreams that
+    /// require mutual TLS, optionally scoped to specific hosts. Additional CA
+    /// bundles can be trusted for upstream connections with `UpstreamCA`
+    /// links.
+    pub client_certs: Option<Vec<UpstreamClientCert>>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The time in seconds that the connection will wait for a response before
     /// timing out.
     pub connect_timeout: Option<u64>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `grpcCompression` setting compresses the messages of every `@grpc`
+    /// request with the given algorithm. @default `Identity`.
+    pub grpc_compression: Option<GrpcCompression>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// Providing httpCache size enables Tailcall's HTTP caching, adhering to the [HTTP Caching RFC](https://tools.ietf.org/html/rfc7234), to enhance performance by minimizing redundant data fetches. Defaults to `0` if unspecified.
     pub http_cache: Option<u64>,
@@ -105,6 +134,13 @@ pub struct Upstream {
     /// sent while the connection is idle.
     pub keep_alive_while_idle: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `oauth2` setting obtains an access token with the OAuth2 client
+    /// credentials grant and sends it as a bearer token with every HTTP and
+    /// gRPC request made to upstreams. Tokens are cached and refreshed
+    /// shortly before they expire.
+    pub oauth2: Option<OAuth2>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The maximum number of idle connections that will be maintained per host.
     pub pool_max_idle_per_host: Option<usize>,
@@ -194,6 +230,9 @@ impl Upstream {
             .as_ref()
             .map_or(DEFAULT_MAX_SIZE, |b| b.max_size.unwrap_or(DEFAULT_MAX_SIZE))
     }
+    pub fn get_client_certs(&self) -> Vec<UpstreamClientCert> {
+        self.client_certs.clone().unwrap_or_default()
+    }
     pub fn get_http_2_only(&self) -> bool {
         self.http2_only.unwrap_or(false)
     }
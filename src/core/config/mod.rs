@@ -5,11 +5,14 @@ pub use directive::Directive;
 pub use directives::*;
 pub use key_values::*;
 pub use npo::QueryPath;
+pub use oauth2::*;
 pub use reader_context::*;
 pub use resolver::*;
+pub use signing::*;
 pub use source::*;
 pub use url_query::*;
 mod apollo;
+mod composition;
 mod config;
 mod config_module;
 pub mod cors;
@@ -21,9 +24,13 @@ mod headers;
 mod into_document;
 mod key_values;
 mod npo;
+mod oauth2;
 pub mod reader;
 pub mod reader_context;
 mod resolver;
+mod signing;
 mod source;
+pub mod stitching;
+pub mod supergraph;
 pub mod transformer;
 mod url_query;
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::is_default;
+use crate::core::macros::MergeRight;
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+/// The OAuth2 input type configures the client credentials grant used to
+/// obtain access tokens for an upstream. Tokens are cached until shortly
+/// before they expire and are sent as a bearer token in the `Authorization`
+/// header.
+pub struct OAuth2 {
+    /// URL of the token endpoint of the authorization server.
+    pub token_url: String,
+    /// The client identifier. Supports mustache templates to read it from the
+    /// environment eg: `{{.env.CLIENT_ID}}`.
+    pub client_id: String,
+    /// The client secret. Supports mustache templates to read it from the
+    /// environment eg: `{{.env.CLIENT_SECRET}}`.
+    pub client_secret: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The scopes requested for the access token.
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The audience of the access token, required by some authorization
+    /// servers.
+    pub audience: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The time in seconds before expiry at which the token is refreshed.
+    /// @default `60`.
+    pub refresh_before: Option<u64>,
+}
+
+impl OAuth2 {
+    pub fn get_refresh_before(&self) -> u64 {
+        self.refresh_before.unwrap_or(60)
+    }
+}
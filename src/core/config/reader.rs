@@ -1,15 +1,18 @@
 use std::path::Path;
+use std::str::FromStr;
 
 use futures_util::future::join_all;
+use http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
 use rustls_pemfile;
-use rustls_pki_types::{
-    CertificateDer, PrivateKeyDer, PrivatePkcs1KeyDer, PrivatePkcs8KeyDer, PrivateSec1KeyDer,
-};
+use rustls_pki_types::CertificateDer;
 use tailcall_valid::{Valid, ValidationError, Validator};
 use url::Url;
 
-use super::{ConfigModule, Content, Link, LinkType, PrivateKey};
+use super::stitching::{self, Service};
+use super::supergraph::{self, Subgraph};
+use super::{ConfigModule, Content, KeyValue, Link, LinkType, PrivateKey};
 use crate::core::config::{Config, ConfigReaderContext, Source};
+use crate::core::generator::{from_introspection, INTROSPECTION_QUERY};
 use crate::core::proto_reader::ProtoReader;
 use crate::core::resource_reader::{Cached, Resource, ResourceReader};
 use crate::core::rest::EndpointSet;
@@ -61,6 +64,8 @@ impl ConfigReader {
 
         let mut extensions = config_module.extensions().clone();
         let mut config_module = Valid::succeed(config_module);
+        let mut subgraphs = vec![];
+        let mut services = vec![];
 
         for link in links.iter() {
             let path = Self::resolve_path(&link.src, parent_dir);
@@ -103,6 +108,38 @@ impl ConfigReader {
                     let content = source.content;
                     extensions.keys = self.load_private_key(content).await?
                 }
+                LinkType::ClientCA => {
+                    let source = self.resource_reader.read_file(path).await?;
+                    let content = source.content;
+
+                    extensions.client_ca.push(Content {
+                        id: link.id.clone(),
+                        content: self.load_cert(content).await?,
+                    });
+                }
+                LinkType::UpstreamCert => {
+                    let source = self.resource_reader.read_file(path).await?;
+
+                    // parse eagerly so that invalid files are reported on startup
+                    self.load_cert(source.content.clone()).await?;
+                    extensions
+                        .upstream_certs
+                        .push(Content { id: link.id.clone(), content: source });
+                }
+                LinkType::UpstreamKey => {
+                    let source = self.resource_reader.read_file(path).await?;
+
+                    self.load_private_key(source.content.clone()).await?;
+                    extensions
+                        .upstream_keys
+                        .push(Content { id: link.id.clone(), content: source });
+                }
+                LinkType::UpstreamCA => {
+                    let source = self.resource_reader.read_file(path).await?;
+
+                    self.load_cert(source.content.clone()).await?;
+                    extensions.upstream_ca.push(source);
+                }
                 LinkType::Operation => {
                     let source = self.resource_reader.read_file(path).await?;
                     let content = source.content;
@@ -138,14 +175,75 @@ impl ConfigReader {
                         extensions.add_proto(m);
                     }
                 }
+                LinkType::SubGraph => {
+                    let sdl = self.read_subgraph_sdl(link).await?;
+
+                    subgraphs.push(Subgraph {
+                        name: link.id.clone().unwrap_or_else(|| link.src.clone()),
+                        url: link.src.clone(),
+                        sdl,
+                    });
+                }
+                LinkType::GraphQL => {
+                    let response = self.query_link(link, INTROSPECTION_QUERY).await?;
+                    let config = from_introspection(&response, &link.src, "Query", "Mutation")?;
+
+                    services.push(Service {
+                        name: link.id.clone().unwrap_or_else(|| link.src.clone()),
+                        url: link.src.clone(),
+                        config,
+                    });
+                }
             }
         }
 
+        if !subgraphs.is_empty() {
+            config_module = config_module.and_then(|config_module| {
+                supergraph::compose(&subgraphs)
+                    .and_then(|config| config_module.unify(ConfigModule::from(config)))
+                    .trace("supergraph")
+            });
+        }
+
+        if !services.is_empty() {
+            config_module = config_module.and_then(|config_module| {
+                stitching::compose(&services)
+                    .and_then(|config| config_module.unify(ConfigModule::from(config)))
+                    .trace("stitching")
+            });
+        }
+
         Ok(config_module
             .map(|config_module| config_module.set_extensions(extensions))
             .to_result()?)
     }
 
+    /// Sends a GraphQL query to the endpoint of a link along with its headers
+    async fn query_link(&self, link: &Link, query: &str) -> anyhow::Result<serde_json::Value> {
+        let mut request = reqwest::Request::new(reqwest::Method::POST, Url::parse(&link.src)?);
+        let headers = request.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for KeyValue { key, value } in link.headers.iter().flatten() {
+            headers.insert(HeaderName::from_str(key)?, HeaderValue::from_str(value)?);
+        }
+        let body = serde_json::json!({ "query": query }).to_string();
+        request.body_mut().replace(body.into());
+
+        let response = self.runtime.http.execute(request).await?;
+
+        Ok(serde_json::from_slice(&response.body)?)
+    }
+
+    /// Reads the SDL of a subgraph from its `_service` field
+    async fn read_subgraph_sdl(&self, link: &Link) -> anyhow::Result<String> {
+        let response = self.query_link(link, "{ _service { sdl } }").await?;
+
+        response["data"]["_service"]["sdl"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Subgraph `{}` didn't return its SDL", link.src))
+    }
+
     /// Reads the certificate from a given file
     async fn load_cert(&self, content: String) -> anyhow::Result<Vec<CertificateDer<'static>>> {
         let certificates = rustls_pemfile::certs(&mut content.as_bytes())?;
@@ -155,24 +253,7 @@ impl ConfigReader {
 
     /// Reads a private key from a given file
     async fn load_private_key(&self, content: String) -> anyhow::Result<Vec<PrivateKey>> {
-        let keys = rustls_pemfile::read_all(&mut content.as_bytes())?;
-
-        Ok(keys
-            .into_iter()
-            .filter_map(|key| match key {
-                rustls_pemfile::Item::RSAKey(key) => {
-                    Some(PrivateKeyDer::Pkcs1(PrivatePkcs1KeyDer::from(key)))
-                }
-                rustls_pemfile::Item::ECKey(key) => {
-                    Some(PrivateKeyDer::Sec1(PrivateSec1KeyDer::from(key)))
-                }
-                rustls_pemfile::Item::PKCS8Key(key) => {
-                    Some(PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key)))
-                }
-                _ => None,
-            })
-            .map(PrivateKey::from)
-            .collect())
+        PrivateKey::from_pem(&content)
     }
 
     /// Reads a single file and returns the config
@@ -274,6 +355,7 @@ mod reader_tests {
     use std::path::{Path, PathBuf};
 
     use pretty_assertions::assert_eq;
+    use tailcall_valid::Validator;
 
     use crate::core::config::reader::ConfigReader;
     use crate::core::config::{Config, Type};
@@ -325,6 +407,125 @@ mod reader_tests {
         bar_mock.assert();
     }
 
+    #[tokio::test]
+    async fn test_subgraph_links() {
+        let runtime = crate::core::runtime::test::init(None);
+        let server = start_mock_server();
+
+        let subgraphs = [
+            (
+                "products",
+                r#"type Query { products: [Product] } type Product @key(fields: "upc") { upc: String! name: String }"#,
+            ),
+            (
+                "reviews",
+                r#"type Product @key(fields: "upc") { upc: String! reviews: [String] }"#,
+            ),
+        ];
+        let mocks = subgraphs.map(|(name, sdl)| {
+            server.mock(|when, then| {
+                when.method(httpmock::Method::POST)
+                    .path(format!("/{name}"))
+                    .body_contains("_service");
+                then.status(200)
+                    .json_body(serde_json::json!({ "data": { "_service": { "sdl": sdl } } }));
+            })
+        });
+
+        let port = server.port();
+        let sdl = format!(
+            r#"schema
+                @link(id: "products", type: SubGraph, src: "http://localhost:{port}/products")
+                @link(id: "reviews", type: SubGraph, src: "http://localhost:{port}/reviews") {{
+                query: Query
+            }}"#
+        );
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+        let config_module = ConfigReader::init(runtime)
+            .resolve(config, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            config_module.types["Product"]
+                .fields
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["name", "reviews", "upc"]
+        );
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_graphql_links() {
+        let runtime = crate::core::runtime::test::init(None);
+        let server = start_mock_server();
+
+        let id =
+            serde_json::json!({ "kind": "NON_NULL", "ofType": { "kind": "SCALAR", "name": "ID" } });
+        let string = serde_json::json!({ "kind": "SCALAR", "name": "String" });
+        let user = serde_json::json!({ "kind": "OBJECT", "name": "User" });
+        let schema = |lookup: &str, field: &str| {
+            serde_json::json!({ "data": { "__schema": {
+                "queryType": { "name": "Query" },
+                "types": [
+                    { "kind": "OBJECT", "name": "Query", "fields": [
+                        { "name": lookup, "args": [{ "name": "id", "type": id }], "type": user }
+                    ] },
+                    { "kind": "OBJECT", "name": "User", "fields": [
+                        { "name": "id", "args": [], "type": id },
+                        { "name": field, "args": [], "type": string }
+                    ] }
+                ]
+            } } })
+        };
+        let services = [
+            ("users", schema("user", "name")),
+            ("accounts", schema("account", "email")),
+        ];
+        let mocks = services.map(|(name, schema)| {
+            server.mock(|when, then| {
+                when.method(httpmock::Method::POST)
+                    .path(format!("/{name}"))
+                    .body_contains("__schema");
+                then.status(200).json_body(schema);
+            })
+        });
+
+        let port = server.port();
+        let sdl = format!(
+            r#"schema
+                @link(id: "users", type: GraphQL, src: "http://localhost:{port}/users")
+                @link(id: "accounts", type: GraphQL, src: "http://localhost:{port}/accounts") {{
+                query: Query
+            }}"#
+        );
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+        let config_module = ConfigReader::init(runtime)
+            .resolve(config, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            config_module.types["Query"]
+                .fields
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["account", "user"]
+        );
+        let user = &config_module.types["User"];
+        assert!(!user.fields["id"].has_resolver());
+        assert!(matches!(
+            user.fields["email"].resolvers.0.as_slice(),
+            [crate::core::config::Resolver::Lookup(lookup)] if lookup.name == "account"
+        ));
+        for mock in mocks {
+            mock.assert();
+        }
+    }
+
     #[tokio::test]
     async fn test_local_files() {
         let runtime = crate::core::runtime::test::init(None);
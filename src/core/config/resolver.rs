@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 use tailcall_macros::{CustomResolver, MergeRight};
 use tailcall_valid::{Valid, Validator};
 
-use super::{Call, EntityResolver, Expr, GraphQL, Grpc, Http, JS};
+use super::stitching::Lookup;
+use super::{Call, EntityFetch, EntityResolver, Expr, GraphQL, Grpc, Http, JS};
 use crate::core::directive::DirectiveCodec;
 use crate::core::merge_right::MergeRight;
 
@@ -14,6 +15,7 @@ use crate::core::merge_right::MergeRight;
 pub enum ApolloFederation {
     EntityResolver(EntityResolver),
     Service,
+    EntityFetch(EntityFetch),
 }
 
 #[derive(
@@ -30,7 +32,7 @@ pub enum ApolloFederation {
 #[serde(rename_all = "camelCase")]
 pub enum Resolver {
     Http(Http),
-    Grpc(Grpc),
+    Grpc(Box<Grpc>),
     Graphql(GraphQL),
     Call(Call),
     Js(JS),
@@ -38,6 +40,9 @@ pub enum Resolver {
     #[serde(skip)]
     #[resolver(skip_directive)]
     ApolloFederation(ApolloFederation),
+    #[serde(skip)]
+    #[resolver(skip_directive)]
+    Lookup(Lookup),
 }
 
 impl Resolver {
@@ -52,6 +57,7 @@ impl Resolver {
                     .values()
                     .any(Resolver::is_batched)
             }
+            Resolver::ApolloFederation(ApolloFederation::EntityFetch(_)) => true,
             _ => false,
         }
     }
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::is_default;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+/// The Signing input type signs requests to upstreams that authenticate the
+/// request itself instead of a token. The signature is computed once the
+/// request is built, so it covers the final URL, headers and body.
+pub enum Signing {
+    /// Signs the request with AWS Signature Version 4.
+    AwsSigV4(AwsSigV4),
+    /// Signs the body of the request with an HMAC and sends the signature in a
+    /// header.
+    Hmac(Hmac),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsSigV4 {
+    /// The AWS region of the service eg: `us-east-1`.
+    pub region: String,
+    /// The name of the service eg: `execute-api` or `s3`.
+    pub service: String,
+    /// The access key id. Supports mustache templates to read it from the
+    /// environment or vars eg: `{{.env.AWS_ACCESS_KEY_ID}}`.
+    pub access_key_id: String,
+    /// The secret access key. Supports mustache templates to read it from the
+    /// environment or vars eg: `{{.env.AWS_SECRET_ACCESS_KEY}}`.
+    pub secret_access_key: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The session token of temporary credentials. Supports mustache
+    /// templates eg: `{{.env.AWS_SESSION_TOKEN}}`.
+    pub session_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Hmac {
+    /// The shared secret. Supports mustache templates to read it from the
+    /// environment or vars eg: `{{.vars.HMAC_SECRET}}`.
+    pub secret: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The hash function of the HMAC. @default `Sha256`.
+    pub algorithm: HmacAlgorithm,
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The header the hex encoded signature is sent in. @default
+    /// `X-Signature`.
+    pub header: Option<String>,
+}
+
+impl Hmac {
+    pub fn get_header(&self) -> String {
+        self.header.clone().unwrap_or("X-Signature".to_string())
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, schemars::JsonSchema,
+)]
+pub enum HmacAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
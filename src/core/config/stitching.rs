@@ -0,0 +1,327 @@
+use std::collections::BTreeSet;
+
+use tailcall_valid::{Valid, Validator};
+
+use super::composition::{check_key_fields, Kind, Merge};
+use super::{Config, EntitySelection, Field, FieldSource, KeyValue, Resolver};
+
+/// A GraphQL service linked with `@link(type: GraphQL)`, along with the config
+/// generated from its introspection.
+#[derive(Clone, Debug)]
+pub struct Service {
+    pub name: String,
+    pub url: String,
+    pub config: Config,
+}
+
+/// Resolver of a field of a stitched type, it looks the type up from the
+/// service owning the field, e.g. `userById(id: {{.value.id}}) { email }`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lookup {
+    pub url: String,
+    /// Root field of the service returning the type by its key.
+    pub name: String,
+    pub args: Vec<KeyValue>,
+    pub selection: EntitySelection,
+    /// Services defining the field, it isn't looked up when the type comes
+    /// from one of them.
+    pub sources: Vec<FieldSource>,
+}
+
+/// Stitches the schemas of the services together. Root fields are resolved by
+/// the first service defining them. A type defined by several services is
+/// merged when one of them has a root field returning it whose arguments are
+/// fields of the type, e.g. `userById(id: ID!): User`. The fields of a merged
+/// type that aren't part of its keys are looked up from the first service
+/// defining them that can look the type up, unless the type comes from a
+/// service defining them. The lookups of a type from the same service are
+/// sent as one query. Fails when the services define a field with different
+/// types, their nullability may differ.
+pub fn compose(services: &[Service]) -> Valid<Config, String> {
+    merge_definitions(services).and_then(|config| stitch(services, config))
+}
+
+/// Resolves the fields of the merged types with lookups.
+fn stitch(services: &[Service], mut config: Config) -> Valid<Config, String> {
+    let merged = config
+        .types
+        .keys()
+        .filter(|name| lookups(services, name).next().is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    Valid::from_iter(merged, |type_name| {
+        key_fields(services, &type_name)
+            .map(|key_fields| {
+                config.types[&type_name]
+                    .fields
+                    .iter()
+                    .filter(|(name, _)| !key_fields.contains(*name))
+                    .filter_map(|(name, field)| {
+                        lookup(services, &type_name, name, field)
+                            .map(|lookup| (name.clone(), lookup))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map(|lookups| (type_name.clone(), lookups))
+            .trace(&type_name)
+    })
+    .map(|merged| {
+        for (type_name, lookups) in merged {
+            let ty = config.types.get_mut(&type_name).unwrap();
+            for (field_name, lookup) in lookups {
+                let field = ty.fields.get_mut(&field_name).unwrap();
+                field.resolvers = Resolver::Lookup(lookup).into();
+            }
+        }
+
+        config
+    })
+}
+
+/// Merges the definitions of the services, the first definition of a field
+/// wins.
+fn merge_definitions(services: &[Service]) -> Valid<Config, String> {
+    let mut merge = Merge::new(Kind::Service);
+
+    for service in services {
+        for (name, ty) in service.config.types.iter() {
+            merge.add_type(&service.name, name, ty, |_, field| field.clone());
+        }
+
+        merge.add_enums_and_unions(&service.config);
+    }
+
+    merge.finish().map(|mut config| {
+        if services
+            .iter()
+            .any(|service| service.config.schema.mutation.is_some())
+        {
+            config.schema.mutation = Some("Mutation".to_string());
+        }
+
+        config
+    })
+}
+
+/// Root fields of the services returning the type by its key, along with the
+/// service and the names of the key fields.
+fn lookups<'a>(
+    services: &'a [Service],
+    type_name: &'a str,
+) -> impl Iterator<Item = (&'a Service, &'a str, Vec<&'a str>)> {
+    services.iter().filter_map(move |service| {
+        let ty = service.config.types.get(type_name)?;
+        let query = service.config.schema.query.as_ref()?;
+        let (name, field) = service
+            .config
+            .types
+            .get(query)?
+            .fields
+            .iter()
+            .find(|(_, field)| {
+                field.type_of.name() == type_name
+                    && !field.type_of.is_list()
+                    && !field.args.is_empty()
+                    && field.args.keys().all(|arg| ty.fields.contains_key(arg))
+            })?;
+
+        Some((
+            service,
+            name.as_str(),
+            field.args.keys().map(String::as_str).collect(),
+        ))
+    })
+}
+
+/// Fields of the keys of a merged type, they're selected along with the type
+/// so every service defining the type has to define them.
+fn key_fields(services: &[Service], type_name: &str) -> Valid<BTreeSet<String>, String> {
+    let key_fields = lookups(services, type_name)
+        .flat_map(|(_, _, key)| key)
+        .map(str::to_string)
+        .collect::<BTreeSet<_>>();
+
+    let services = services
+        .iter()
+        .map(|service| (service.name.as_str(), &service.config));
+    check_key_fields(Kind::Service, type_name, key_fields, services)
+}
+
+/// Lookup of the field from the first service defining it that can look the
+/// type up, the other fields are resolved from the parent value.
+fn lookup(services: &[Service], type_name: &str, name: &str, field: &Field) -> Option<Lookup> {
+    lookups(services, type_name)
+        .find(|(service, _, _)| service.config.types[type_name].fields.contains_key(name))
+        .map(|(service, lookup, key)| Lookup {
+            url: service.url.clone(),
+            name: lookup.to_string(),
+            args: key
+                .iter()
+                .map(|key| KeyValue {
+                    key: key.to_string(),
+                    value: format!("{{{{.value.{key}}}}}"),
+                })
+                .collect(),
+            selection: EntitySelection::Field {
+                name: name.to_string(),
+                args: field.args.keys().cloned().collect(),
+            },
+            sources: services
+                .iter()
+                .filter(|service| {
+                    service
+                        .config
+                        .types
+                        .get(type_name)
+                        .is_some_and(|ty| ty.fields.contains_key(name))
+                })
+                .map(|service| FieldSource { url: service.url.clone(), provided_by: None })
+                .collect(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::config::GraphQL;
+
+    fn service(name: &str, sdl: &str) -> Service {
+        let url = format!("http://{name}/graphql");
+        let mut config = Config::from_sdl(sdl).to_result().unwrap();
+        config.schema.query = Some("Query".to_string());
+        for (field_name, field) in config.types.get_mut("Query").unwrap().fields.iter_mut() {
+            let graphql = GraphQL {
+                url: url.clone(),
+                name: field_name.clone(),
+                ..Default::default()
+            };
+            field.resolvers = Resolver::Graphql(graphql).into();
+        }
+
+        Service { name: name.to_string(), url, config }
+    }
+
+    fn services() -> Vec<Service> {
+        vec![
+            service(
+                "users",
+                r#"
+                type Query { user(id: ID!): User users: [User] }
+                type User { id: ID! name: String }
+                type PageInfo { hasNextPage: Boolean }
+                "#,
+            ),
+            service(
+                "posts",
+                r#"
+                type Query { author(id: ID!): User posts: [Post] }
+                type User { id: ID! name: String posts(first: Int): [Post] }
+                type Post { title: String author: User }
+                type PageInfo { hasNextPage: Boolean endCursor: String }
+                "#,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_compose() {
+        let config = compose(&services()).to_result().unwrap();
+
+        assert_eq!(
+            config.types["Query"].fields.keys().collect::<Vec<_>>(),
+            vec!["author", "posts", "user", "users"]
+        );
+
+        let user = &config.types["User"];
+        assert!(!user.fields["id"].has_resolver());
+        assert_eq!(
+            user.fields["name"].resolvers.0,
+            vec![Resolver::Lookup(Lookup {
+                url: "http://users/graphql".to_string(),
+                name: "user".to_string(),
+                args: vec![KeyValue { key: "id".to_string(), value: "{{.value.id}}".to_string() }],
+                selection: EntitySelection::Field { name: "name".to_string(), args: vec![] },
+                sources: vec![
+                    FieldSource { url: "http://users/graphql".to_string(), provided_by: None },
+                    FieldSource { url: "http://posts/graphql".to_string(), provided_by: None },
+                ],
+            })]
+        );
+        assert_eq!(
+            user.fields["posts"].resolvers.0,
+            vec![Resolver::Lookup(Lookup {
+                url: "http://posts/graphql".to_string(),
+                name: "author".to_string(),
+                args: vec![KeyValue { key: "id".to_string(), value: "{{.value.id}}".to_string() }],
+                selection: EntitySelection::Field {
+                    name: "posts".to_string(),
+                    args: vec!["first".to_string()],
+                },
+                sources: vec![FieldSource {
+                    url: "http://posts/graphql".to_string(),
+                    provided_by: None,
+                }],
+            })]
+        );
+
+        // types without lookups are merged as they are
+        let page_info = &config.types["PageInfo"];
+        assert_eq!(
+            page_info.fields.keys().collect::<Vec<_>>(),
+            vec!["endCursor", "hasNextPage"]
+        );
+        assert!(!page_info.fields["endCursor"].has_resolver());
+    }
+
+    #[test]
+    fn test_compose_errors() {
+        let mut services = services();
+        services.push(service(
+            "profiles",
+            r#"type Query { profile(handle: String!): User } type User { handle: String! bio: String }"#,
+        ));
+        let errors = compose(&services)
+            .to_result()
+            .unwrap_err()
+            .as_vec()
+            .iter()
+            .map(|cause| format!("{:?}: {}", cause.trace, cause.message))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                r#"["User"]: Service `users` doesn't define the key field `handle`"#,
+                r#"["User"]: Service `posts` doesn't define the key field `handle`"#,
+                r#"["User"]: Service `profiles` doesn't define the key field `id`"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compose_type_conflict() {
+        let mut services = services();
+        services.push(service(
+            "profiles",
+            r#"type Query { profile(id: ID!): User } type User { id: ID name: [String] }"#,
+        ));
+        let errors = compose(&services)
+            .to_result()
+            .unwrap_err()
+            .as_vec()
+            .iter()
+            .map(|cause| format!("{:?}: {}", cause.trace, cause.message))
+            .collect::<Vec<_>>();
+
+        // the nullability of `id` may differ
+        assert_eq!(
+            errors,
+            vec![
+                r#"["User", "name"]: Field has the type `String` in service `users` and `[String]` in service `profiles`"#
+            ]
+        );
+    }
+}
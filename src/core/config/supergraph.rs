@@ -0,0 +1,694 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use async_graphql::parser::types::TypeSystemDefinition;
+use serde_json::Value;
+use tailcall_valid::{Valid, Validator};
+
+use super::composition::{check_key_fields, Kind, Merge};
+use super::from_document::from_document;
+use super::{
+    ApolloFederation, Config, Directive, EntityFetch, EntitySelection, Field, FieldSet,
+    FieldSource, GraphQL, KeyValue, Resolver,
+};
+
+/// A subgraph of Apollo Federation v2 linked with `@link(type: SubGraph)`.
+#[derive(Clone, Debug)]
+pub struct Subgraph {
+    pub name: String,
+    pub url: String,
+    pub sdl: String,
+}
+
+/// Composes the subgraphs into the config of the supergraph gateway. Root
+/// fields are resolved by the subgraph defining them, the fields of an entity
+/// that aren't part of its `@key` are fetched from the subgraph owning them
+/// with an `_entities` query, after the fields they `@requires`. They're
+/// selected along with the entity instead when it comes from a subgraph
+/// resolving them, or from a field that `@provides` them.
+pub fn compose(subgraphs: &[Subgraph]) -> Valid<Config, String> {
+    Valid::from_iter(subgraphs.iter(), |subgraph| {
+        Parsed::parse(subgraph).trace(&subgraph.name)
+    })
+    .and_then(|subgraphs| Composition::new(subgraphs).compose())
+}
+
+fn field_set(fields: &str) -> Valid<FieldSet, String> {
+    Valid::from_option(
+        FieldSet::parse(fields),
+        format!("Invalid field set: `{fields}`"),
+    )
+}
+
+fn directive<'a>(directives: &'a [Directive], name: &str) -> Option<&'a Directive> {
+    directives.iter().find(|directive| directive.name == name)
+}
+
+fn argument<'a>(directive: &'a Directive, name: &str) -> Option<&'a str> {
+    directive.arguments.get(name).and_then(Value::as_str)
+}
+
+/// Types of the federation spec that are part of the subgraph SDLs.
+fn is_federation_type(name: &str) -> bool {
+    name.starts_with('_') || name.starts_with("link__") || name.starts_with("federation__")
+}
+
+struct Parsed<'a> {
+    subgraph: &'a Subgraph,
+    config: Config,
+    /// Names of the root types of the subgraph mapped to the supergraph ones.
+    roots: BTreeMap<String, &'static str>,
+    /// Resolvable keys of the entities.
+    keys: BTreeMap<String, FieldSet>,
+}
+
+impl<'a> Parsed<'a> {
+    fn parse(subgraph: &'a Subgraph) -> Valid<Self, String> {
+        let mut document = match async_graphql::parser::parse_schema(&subgraph.sdl) {
+            Ok(document) => document,
+            Err(err) => return Valid::fail(err.to_string()),
+        };
+
+        let mut roots = BTreeMap::from([
+            ("Query".to_string(), "Query"),
+            ("Mutation".to_string(), "Mutation"),
+        ]);
+        for definition in document.definitions.iter() {
+            if let TypeSystemDefinition::Schema(schema) = definition {
+                if let Some(query) = &schema.node.query {
+                    roots.insert(query.node.to_string(), "Query");
+                }
+                if let Some(mutation) = &schema.node.mutation {
+                    roots.insert(mutation.node.to_string(), "Mutation");
+                }
+            }
+        }
+
+        // `extend schema @link(...)` and the types of the spec aren't part of
+        // the supergraph
+        document.definitions.retain(|definition| match definition {
+            TypeSystemDefinition::Type(ty) => !is_federation_type(ty.node.name.node.as_str()),
+            _ => false,
+        });
+
+        from_document(document).and_then(|mut config| {
+            for (name, _) in roots.iter() {
+                if let Some(root) = config.types.get_mut(name) {
+                    root.fields.retain(|name, _| !is_federation_type(name));
+                }
+            }
+
+            Valid::from_iter(config.types.iter(), |(name, ty)| {
+                let key = ty
+                    .directives
+                    .iter()
+                    .filter(|directive| directive.name == "key")
+                    .find(|directive| {
+                        directive.arguments.get("resolvable") != Some(&Value::Bool(false))
+                    });
+
+                match key.and_then(|key| argument(key, "fields")) {
+                    Some(fields) => field_set(fields)
+                        .map(|fields| Some((name.clone(), fields)))
+                        .trace(name),
+                    None => Valid::succeed(None),
+                }
+            })
+            .map(|keys| Self {
+                subgraph,
+                roots,
+                keys: keys.into_iter().flatten().collect(),
+                config,
+            })
+        })
+    }
+
+    fn root(&self, name: &str) -> Option<&'static str> {
+        self.roots.get(name).copied()
+    }
+
+    fn is_entity(&self, name: &str) -> bool {
+        self.config
+            .types
+            .get(name)
+            .is_some_and(|ty| directive(&ty.directives, "key").is_some())
+    }
+
+    /// Fields of the subgraph that are resolved by it.
+    fn owns(&self, type_name: &str, field_name: &str) -> Option<&Field> {
+        let field = self.config.types.get(type_name)?.fields.get(field_name)?;
+        directive(&field.directives, "external")
+            .is_none()
+            .then_some(field)
+    }
+
+    /// Collects the fields of `type_name` listed in a `@provides` field set,
+    /// they're provided through the field `via`.
+    fn provided(
+        &self,
+        fields: &FieldSet,
+        type_name: &str,
+        via: (String, String),
+        provided: &mut Vec<(String, String, FieldSource)>,
+    ) {
+        for (name, nested) in fields.0.iter() {
+            provided.push((
+                type_name.to_string(),
+                name.clone(),
+                FieldSource {
+                    url: self.subgraph.url.clone(),
+                    provided_by: Some(via.clone()),
+                },
+            ));
+
+            let field = self
+                .config
+                .types
+                .get(type_name)
+                .and_then(|ty| ty.fields.get(name));
+            if let Some(field) = field {
+                let via = (type_name.to_string(), name.clone());
+                self.provided(nested, field.type_of.name(), via, provided);
+            }
+        }
+    }
+
+    /// Representation of an entity built from its key in the parent value.
+    fn representation(&self, type_name: &str, required: &FieldSet) -> String {
+        let mut fields = vec![format!(r#"__typename: "{type_name}""#)];
+        fields.extend(self.keys[type_name].literal(".value"));
+        fields.extend(required.literal(".args"));
+
+        format!("{{ {} }}", fields.join(", "))
+    }
+}
+
+struct Composition<'a> {
+    subgraphs: Vec<Parsed<'a>>,
+    config: Config,
+}
+
+impl<'a> Composition<'a> {
+    fn new(subgraphs: Vec<Parsed<'a>>) -> Self {
+        Self { subgraphs, config: Config::default() }
+    }
+
+    fn compose(mut self) -> Valid<Config, String> {
+        self.merge_definitions()
+            .and_then(|_| self.provided())
+            .and_then(move |provided| self.fetch_entities(&provided))
+    }
+
+    fn fetch_entities(
+        mut self,
+        provided: &[(String, String, FieldSource)],
+    ) -> Valid<Config, String> {
+        let entities = self
+            .config
+            .types
+            .keys()
+            .filter(|name| {
+                self.subgraphs
+                    .iter()
+                    .any(|subgraph| subgraph.is_entity(name))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Valid::from_iter(entities, |type_name| {
+            self.key_fields(&type_name)
+                .and_then(|key_fields| {
+                    let fields = self.config.types[&type_name]
+                        .fields
+                        .keys()
+                        .filter(|name| !key_fields.contains(*name))
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    Valid::from_iter(fields, |field_name| {
+                        self.entity_fetch(&type_name, &field_name, provided)
+                            .map(|fetch| (field_name.clone(), fetch))
+                            .trace(&field_name)
+                    })
+                })
+                .map(|fetches| (type_name.clone(), fetches))
+                .trace(&type_name)
+        })
+        .map(|entities| {
+            for (type_name, fetches) in entities {
+                let ty = self.config.types.get_mut(&type_name).unwrap();
+                for (field_name, fetch) in fetches {
+                    let field = ty.fields.get_mut(&field_name).unwrap();
+                    field.resolvers =
+                        Resolver::ApolloFederation(ApolloFederation::EntityFetch(fetch)).into();
+                }
+            }
+
+            self.config
+        })
+    }
+
+    /// Merges the definitions of the subgraphs, the root fields are resolved
+    /// by the first subgraph defining them.
+    fn merge_definitions(&mut self) -> Valid<(), String> {
+        let mut merge = Merge::new(Kind::Subgraph);
+        let mut has_mutation = false;
+
+        for parsed in self.subgraphs.iter() {
+            for (name, ty) in parsed.config.types.iter() {
+                let root = parsed.root(name);
+                has_mutation |= root == Some("Mutation");
+
+                merge.add_type(
+                    &parsed.subgraph.name,
+                    root.unwrap_or(name),
+                    ty,
+                    |field_name, field| {
+                        let mut field = Field { directives: vec![], ..field.clone() };
+                        if root.is_some() {
+                            let graphql = GraphQL {
+                                url: parsed.subgraph.url.clone(),
+                                name: field_name.to_string(),
+                                args: Some(
+                                    field
+                                        .args
+                                        .keys()
+                                        .map(|arg| KeyValue {
+                                            key: arg.clone(),
+                                            value: format!("{{{{.args.{arg}}}}}"),
+                                        })
+                                        .collect(),
+                                )
+                                .filter(|args: &Vec<_>| !args.is_empty()),
+                                ..Default::default()
+                            };
+                            field.resolvers = Resolver::Graphql(graphql).into();
+                        }
+                        field
+                    },
+                );
+            }
+
+            merge.add_enums_and_unions(&parsed.config);
+        }
+
+        merge.finish().map(|mut config| {
+            if has_mutation {
+                config.schema.mutation = Some("Mutation".to_string());
+            }
+            self.config = config;
+        })
+    }
+
+    /// Fields of the entities listed in `@provides`, along with the subgraph
+    /// and the field providing them, e.g. `name` of `Product` is provided by
+    /// the reviews subgraph through `Review.product`.
+    fn provided(&self) -> Valid<Vec<(String, String, FieldSource)>, String> {
+        let provides = self.subgraphs.iter().flat_map(|subgraph| {
+            subgraph
+                .config
+                .types
+                .iter()
+                .flat_map(move |(type_name, ty)| {
+                    ty.fields.iter().filter_map(move |(field_name, field)| {
+                        let fields = argument(directive(&field.directives, "provides")?, "fields")?;
+                        Some((subgraph, type_name, field_name, field, fields))
+                    })
+                })
+        });
+
+        Valid::from_iter(
+            provides,
+            |(subgraph, type_name, field_name, field, fields)| {
+                field_set(fields)
+                    .map(|fields| {
+                        let mut provided = vec![];
+                        let via = (type_name.clone(), field_name.clone());
+                        subgraph.provided(&fields, field.type_of.name(), via, &mut provided);
+                        provided
+                    })
+                    .trace(field_name)
+                    .trace(type_name)
+            },
+        )
+        .map(|provided| provided.into_iter().flatten().collect())
+    }
+
+    /// Fields of the keys of an entity, they're selected along with the entity
+    /// so every subgraph defining the entity has to define them.
+    fn key_fields(&self, type_name: &str) -> Valid<BTreeSet<String>, String> {
+        let keys = self.subgraphs.iter().flat_map(|subgraph| {
+            let ty = subgraph.config.types.get(type_name);
+            ty.into_iter()
+                .flat_map(|ty| ty.directives.iter())
+                .filter(|directive| directive.name == "key")
+                .filter_map(|directive| argument(directive, "fields"))
+        });
+
+        Valid::from_iter(keys, field_set)
+            .map(|keys| {
+                keys.iter()
+                    .flat_map(|key| key.names().map(str::to_string).collect::<Vec<_>>())
+                    .collect::<BTreeSet<_>>()
+            })
+            .and_then(|key_fields| {
+                let subgraphs = self
+                    .subgraphs
+                    .iter()
+                    .map(|subgraph| (subgraph.subgraph.name.as_str(), &subgraph.config));
+                check_key_fields(Kind::Subgraph, type_name, key_fields, subgraphs)
+            })
+    }
+
+    /// The subgraph resolving a field of an entity, `@override` takes
+    /// precedence over the order of the subgraphs.
+    fn owner(&self, type_name: &str, field_name: &str) -> Valid<&Parsed<'a>, String> {
+        let owners = self
+            .subgraphs
+            .iter()
+            .filter_map(|subgraph| Some((subgraph, subgraph.owns(type_name, field_name)?)))
+            .collect::<Vec<_>>();
+
+        let owner = owners
+            .iter()
+            .find(|(_, field)| directive(&field.directives, "override").is_some())
+            .or(owners.first());
+
+        match owner {
+            Some((subgraph, _)) if subgraph.keys.contains_key(type_name) => {
+                Valid::succeed(*subgraph)
+            }
+            Some((subgraph, _)) => Valid::fail(format!(
+                "Type `{type_name}` has no resolvable @key in subgraph `{}`",
+                subgraph.subgraph.name
+            )),
+            None => Valid::fail("Field is @external in every subgraph".to_string()),
+        }
+    }
+
+    /// Subgraphs selecting a field of an entity along with the entity.
+    fn sources(
+        &self,
+        type_name: &str,
+        field_name: &str,
+        provided: &[(String, String, FieldSource)],
+    ) -> Vec<FieldSource> {
+        let owners = self
+            .subgraphs
+            .iter()
+            .filter(|subgraph| subgraph.owns(type_name, field_name).is_some())
+            .map(|subgraph| FieldSource { url: subgraph.subgraph.url.clone(), provided_by: None });
+        let providers = provided
+            .iter()
+            .filter(|(entity, field, _)| entity == type_name && field == field_name)
+            .map(|(_, _, source)| source.clone());
+
+        owners.chain(providers).collect()
+    }
+
+    fn entity_fetch(
+        &self,
+        type_name: &str,
+        field_name: &str,
+        provided: &[(String, String, FieldSource)],
+    ) -> Valid<EntityFetch, String> {
+        self.owner(type_name, field_name).and_then(|owner| {
+            let field = &owner.config.types[type_name].fields[field_name];
+            let requires = directive(&field.directives, "requires")
+                .and_then(|requires| argument(requires, "fields"))
+                .unwrap_or_default();
+
+            field_set(requires).and_then(|requires| {
+                if !requires.0.is_empty() && !field.args.is_empty() {
+                    return Valid::fail(
+                        "@requires isn't supported on fields with arguments".to_string(),
+                    );
+                }
+
+                let key = &owner.keys[type_name];
+                let required = requires.filter(|name| !key.names().any(|key| key == name));
+
+                // fields that aren't part of the key are fetched from their owners
+                Valid::from_iter(required.0.iter(), |(name, fields)| {
+                    self.owner(type_name, name)
+                        .map(|owner| (owner, (name.clone(), fields.clone())))
+                        .trace(name)
+                })
+                .map(|required_by_owner| {
+                    let mut fetches: Vec<(&Parsed, FieldSet)> = vec![];
+                    for (owner, field) in required_by_owner {
+                        let name = &owner.subgraph.name;
+                        match fetches
+                            .iter_mut()
+                            .find(|(fetch, _)| &fetch.subgraph.name == name)
+                        {
+                            Some((_, fields)) => fields.0.push(field),
+                            None => fetches.push((owner, FieldSet(vec![field]))),
+                        }
+                    }
+                    fetches
+                })
+                .map(|fetches| EntityFetch {
+                    url: owner.subgraph.url.clone(),
+                    type_name: type_name.to_string(),
+                    representation: owner.representation(type_name, &required),
+                    selection: EntitySelection::Field {
+                        name: field_name.to_string(),
+                        args: field.args.keys().cloned().collect(),
+                    },
+                    requires: fetches
+                        .into_iter()
+                        .map(|(owner, fields)| EntityFetch {
+                            url: owner.subgraph.url.clone(),
+                            type_name: type_name.to_string(),
+                            representation: owner.representation(type_name, &FieldSet::default()),
+                            selection: EntitySelection::Fields(fields.to_string()),
+                            requires: vec![],
+                            sources: vec![],
+                        })
+                        .collect(),
+                    // fields requiring others are always fetched along with them
+                    sources: if requires.0.is_empty() {
+                        self.sources(type_name, field_name, provided)
+                    } else {
+                        vec![]
+                    },
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn subgraph(name: &str, sdl: &str) -> Subgraph {
+        Subgraph {
+            name: name.to_string(),
+            url: format!("http://{name}/graphql"),
+            sdl: sdl.to_string(),
+        }
+    }
+
+    fn subgraphs() -> Vec<Subgraph> {
+        vec![
+            subgraph(
+                "products",
+                r#"
+                extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+                type Query { products(first: Int): [Product] _service: _Service! }
+                type Product @key(fields: "upc") { upc: String! name: String weight: Int }
+                scalar _Any
+                type _Service { sdl: String }
+                "#,
+            ),
+            subgraph(
+                "shipping",
+                r#"
+                type Product @key(fields: "upc") {
+                    upc: String!
+                    weight: Int @external
+                    shippingEstimate: Int @requires(fields: "weight")
+                }
+                "#,
+            ),
+            subgraph(
+                "reviews",
+                r#"
+                type Mutation { review(upc: String!, body: String): Review }
+                type Review { body: String product: Product @provides(fields: "name") }
+                type Product @key(fields: "upc") {
+                    upc: String!
+                    name: String @external
+                    reviews: [Review]
+                }
+                "#,
+            ),
+        ]
+    }
+
+    fn resolver<'a>(config: &'a Config, type_name: &str, field_name: &str) -> &'a Resolver {
+        config.types[type_name].fields[field_name]
+            .resolvers
+            .first()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compose() {
+        let config = compose(&subgraphs()).to_result().unwrap();
+
+        assert_eq!(config.schema.query.as_deref(), Some("Query"));
+        assert_eq!(config.schema.mutation.as_deref(), Some("Mutation"));
+        assert_eq!(
+            config.types.keys().collect::<Vec<_>>(),
+            vec!["Mutation", "Product", "Query", "Review"]
+        );
+
+        let Resolver::Graphql(graphql) = resolver(&config, "Query", "products") else {
+            panic!("expected a @graphQL resolver");
+        };
+        assert_eq!(graphql.url, "http://products/graphql");
+        assert_eq!(
+            graphql.args,
+            Some(vec![KeyValue {
+                key: "first".into(),
+                value: "{{.args.first}}".into()
+            }])
+        );
+
+        // key fields are selected along with the entity
+        assert!(!config.types["Product"].fields["upc"]
+            .resolvers
+            .has_resolver());
+        // as are the fields of value types
+        assert!(!config.types["Review"].fields["body"]
+            .resolvers
+            .has_resolver());
+    }
+
+    #[test]
+    fn test_entity_fetch() {
+        let config = compose(&subgraphs()).to_result().unwrap();
+
+        let Resolver::ApolloFederation(ApolloFederation::EntityFetch(fetch)) =
+            resolver(&config, "Product", "shippingEstimate")
+        else {
+            panic!("expected an entity fetch");
+        };
+
+        assert_eq!(
+            fetch,
+            &EntityFetch {
+                url: "http://shipping/graphql".into(),
+                type_name: "Product".into(),
+                representation:
+                    r#"{ __typename: "Product", upc: {{.value.upc}}, weight: {{.args.weight}} }"#
+                        .into(),
+                selection: EntitySelection::Field { name: "shippingEstimate".into(), args: vec![] },
+                requires: vec![EntityFetch {
+                    url: "http://products/graphql".into(),
+                    type_name: "Product".into(),
+                    representation: r#"{ __typename: "Product", upc: {{.value.upc}} }"#.into(),
+                    selection: EntitySelection::Fields("weight".into()),
+                    requires: vec![],
+                    sources: vec![],
+                }],
+                // fetched after the fields it requires
+                sources: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_field_sources() {
+        let config = compose(&subgraphs()).to_result().unwrap();
+
+        let Resolver::ApolloFederation(ApolloFederation::EntityFetch(fetch)) =
+            resolver(&config, "Product", "name")
+        else {
+            panic!("expected an entity fetch");
+        };
+
+        assert_eq!(fetch.url, "http://products/graphql");
+        assert_eq!(
+            fetch.sources,
+            vec![
+                FieldSource { url: "http://products/graphql".into(), provided_by: None },
+                FieldSource {
+                    url: "http://reviews/graphql".into(),
+                    provided_by: Some(("Review".into(), "product".into()))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_override() {
+        let mut subgraphs = subgraphs();
+        subgraphs.push(subgraph(
+            "inventory",
+            r#"type Product @key(fields: "upc") { upc: String! weight: Int @override(from: "products") }"#,
+        ));
+        let config = compose(&subgraphs).to_result().unwrap();
+
+        let Resolver::ApolloFederation(ApolloFederation::EntityFetch(fetch)) =
+            resolver(&config, "Product", "weight")
+        else {
+            panic!("expected an entity fetch");
+        };
+        assert_eq!(fetch.url, "http://inventory/graphql");
+    }
+
+    #[test]
+    fn test_compose_type_conflict() {
+        let mut subgraphs = subgraphs();
+        subgraphs.push(subgraph(
+            "inventory",
+            r#"type Product @key(fields: "upc") { upc: String weight: [Int] @shareable }"#,
+        ));
+        let errors = compose(&subgraphs)
+            .to_result()
+            .unwrap_err()
+            .as_vec()
+            .iter()
+            .map(|cause| format!("{:?}: {}", cause.trace, cause.message))
+            .collect::<Vec<_>>();
+
+        // the nullability of `upc` may differ
+        assert_eq!(
+            errors,
+            vec![
+                r#"["Product", "weight"]: Field has the type `Int` in subgraph `products` and `[Int]` in subgraph `inventory`"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compose_errors() {
+        let mut subgraphs = subgraphs();
+        subgraphs.push(subgraph(
+            "inventory",
+            r#"type Product @key(fields: "sku") { sku: String! stock: Int }"#,
+        ));
+        let errors = compose(&subgraphs)
+            .to_result()
+            .unwrap_err()
+            .as_vec()
+            .iter()
+            .map(|cause| format!("{:?}: {}", cause.trace, cause.message))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                r#"["Product"]: Subgraph `products` doesn't define the key field `sku`"#,
+                r#"["Product"]: Subgraph `shipping` doesn't define the key field `sku`"#,
+                r#"["Product"]: Subgraph `reviews` doesn't define the key field `sku`"#,
+                r#"["Product"]: Subgraph `inventory` doesn't define the key field `upc`"#,
+            ]
+        );
+    }
+}
@@ -522,7 +522,7 @@ mod tests {
                 ..Default::default()
             };
 
-            let resolver = Resolver::Grpc(grpc);
+            let resolver = Resolver::Grpc(Box::new(grpc));
             let keys = KeysExtractor::extract_keys(&resolver);
 
             assert_debug_snapshot!(keys);
@@ -37,8 +37,11 @@ fn lower_case_first_letter(s: &str) -> String {
 
 impl<'a, A: Deserialize<'a> + Serialize + 'a> DirectiveCodec for A {
     fn directive_name() -> String {
+        // directives behind a pointer (eg. `Box<Grpc>`) are named after the
+        // pointee
         lower_case_first_letter(
             std::any::type_name::<A>()
+                .trim_end_matches('>')
                 .split("::")
                 .last()
                 .unwrap_or_default(),
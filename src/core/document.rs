@@ -451,11 +451,6 @@ impl<'a> From<&'a ConstDirective> for Directive<'a> {
 
 impl<'a, Input: JsonLikeOwned + Display> From<&'a JitDirective<Input>> for Directive<'a> {
     fn from(value: &'a JitDirective<Input>) -> Self {
-        let to_mustache = |s: &str| -> String {
-            s.strip_prefix('$')
-                .map(|v| format!("{{{{{}}}}}", v))
-                .unwrap_or_else(|| s.to_string())
-        };
         Self {
             name: Cow::Borrowed(value.name.as_str()),
             args: value
@@ -463,8 +458,7 @@ impl<'a, Input: JsonLikeOwned + Display> From<&'a JitDirective<Input>> for Direc
                 .iter()
                 .filter_map(|(k, v)| {
                     if !v.is_null() {
-                        let v_str = to_mustache(&v.to_string());
-                        Some(Arg { name: Cow::Borrowed(k), value: Cow::Owned(v_str) })
+                        Some(Arg { name: Cow::Borrowed(k), value: Cow::Owned(v.to_string()) })
                     } else {
                         None
                     }
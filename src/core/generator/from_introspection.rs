@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context as _, Result};
+use async_graphql::parser::types::DocumentOperations;
+use serde_json::Value;
+
+use crate::core::config::{
+    self, Arg, Config, Enum, Field, GraphQL, KeyValue, Resolver, Union, Variant,
+};
+use crate::core::scalar::Scalar;
+use crate::core::Type;
+
+/// Query sent to the upstream to fetch its schema.
+pub const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      interfaces { name }
+      enumValues(includeDeprecated: true) { name description }
+      possibleTypes { name }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType { kind name ofType { kind name ofType { kind name } } }
+      }
+    }
+  }
+}
+"#;
+
+/// Builds the config that proxies an upstream GraphQL server from the result
+/// of its introspection.
+struct Context<'a> {
+    url: &'a str,
+    /// Names of the upstream root types mapped to the generated ones.
+    roots: BTreeMap<&'a str, &'a str>,
+    config: Config,
+}
+
+fn description(value: &Value) -> Option<String> {
+    value["description"]
+        .as_str()
+        .filter(|doc| !doc.is_empty())
+        .map(str::to_string)
+}
+
+/// Parses a default value, which introspection returns as a GraphQL literal.
+fn default_value(literal: &Value) -> Option<Value> {
+    let query = format!("query($v: Int = {}) {{ v }}", literal.as_str()?);
+    let document = async_graphql::parser::parse_query(query).ok()?;
+    let DocumentOperations::Single(operation) = document.operations else {
+        return None;
+    };
+    let variable = operation.node.variable_definitions.into_iter().next()?;
+
+    variable.node.default_value?.node.into_json().ok()
+}
+
+impl<'a> Context<'a> {
+    fn type_name(&self, name: &'a str) -> String {
+        self.roots.get(name).copied().unwrap_or(name).to_string()
+    }
+
+    fn type_of(&self, type_ref: &'a Value) -> Result<Type> {
+        match type_ref["kind"].as_str() {
+            Some("NON_NULL") => Ok(self.type_of(&type_ref["ofType"])?.into_required()),
+            Some("LIST") => Ok(self.type_of(&type_ref["ofType"])?.into_list()),
+            _ => {
+                let name = type_ref["name"]
+                    .as_str()
+                    .context("Type reference without name")?;
+                Ok(Type::from(self.type_name(name)))
+            }
+        }
+    }
+
+    fn args(&self, values: &'a Value) -> Result<Vec<(String, Arg)>> {
+        values
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|value| {
+                let name = value["name"].as_str().context("Argument without name")?;
+                let arg = Arg {
+                    type_of: self.type_of(&value["type"])?,
+                    doc: description(value),
+                    default_value: default_value(&value["defaultValue"]),
+                    ..Default::default()
+                };
+                Ok((name.to_string(), arg))
+            })
+            .collect()
+    }
+
+    fn object(&self, type_def: &'a Value) -> Result<config::Type> {
+        let mut ty = config::Type { doc: description(type_def), ..Default::default() };
+        let is_root = type_def["name"]
+            .as_str()
+            .is_some_and(|name| self.roots.contains_key(name));
+
+        for field in type_def["fields"].as_array().into_iter().flatten() {
+            let name = field["name"].as_str().context("Field without name")?;
+            let args = self.args(&field["args"])?;
+            let mut config_field = Field {
+                type_of: self.type_of(&field["type"])?,
+                doc: description(field),
+                ..Default::default()
+            };
+
+            // root fields request the upstream, nested ones are resolved from
+            // the selection forwarded along with them
+            if is_root {
+                let graphql = GraphQL {
+                    url: self.url.to_string(),
+                    name: name.to_string(),
+                    args: Some(
+                        args.iter()
+                            .map(|(arg, _)| KeyValue {
+                                key: arg.clone(),
+                                value: format!("{{{{.args.{arg}}}}}"),
+                            })
+                            .collect(),
+                    )
+                    .filter(|args: &Vec<_>| !args.is_empty()),
+                    ..Default::default()
+                };
+                config_field.resolvers = Resolver::Graphql(graphql).into();
+            }
+
+            config_field.args = args.into_iter().collect();
+            ty.fields.insert(name.to_string(), config_field);
+        }
+
+        for field in type_def["inputFields"].as_array().into_iter().flatten() {
+            let name = field["name"].as_str().context("Input field without name")?;
+            let config_field = Field {
+                type_of: self.type_of(&field["type"])?,
+                doc: description(field),
+                default_value: default_value(&field["defaultValue"]),
+                ..Default::default()
+            };
+            ty.fields.insert(name.to_string(), config_field);
+        }
+
+        ty.implements = type_def["interfaces"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|interface| interface["name"].as_str())
+            .map(|name| self.type_name(name))
+            .collect();
+
+        Ok(ty)
+    }
+
+    fn type_def(&mut self, type_def: &'a Value) -> Result<()> {
+        let name = type_def["name"].as_str().context("Type without name")?;
+        if name.starts_with("__") {
+            return Ok(());
+        }
+
+        match type_def["kind"].as_str() {
+            Some("OBJECT" | "INTERFACE" | "INPUT_OBJECT") => {
+                let ty = self.object(type_def)?;
+                self.config.types.insert(self.type_name(name), ty);
+            }
+            Some("ENUM") => {
+                let variants = type_def["enumValues"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|value| value["name"].as_str())
+                    .map(|name| Variant { name: name.to_string(), alias: None })
+                    .collect();
+                let doc = description(type_def);
+                self.config
+                    .enums
+                    .insert(name.to_string(), Enum { variants, doc });
+            }
+            Some("UNION") => {
+                let types = type_def["possibleTypes"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|ty| ty["name"].as_str())
+                    .map(|name| self.type_name(name))
+                    .collect();
+                let doc = description(type_def);
+                self.config
+                    .unions
+                    .insert(name.to_string(), Union { types, doc });
+            }
+            // types without fields are printed as scalars
+            Some("SCALAR") if !Scalar::is_predefined(name) => {
+                let ty = config::Type { doc: description(type_def), ..Default::default() };
+                self.config.types.insert(name.to_string(), ty);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates the config for the schema introspected from the GraphQL server
+/// at `url`, every root field requests the upstream with `@graphQL`.
+pub fn from_introspection(
+    response: &Value,
+    url: &str,
+    query: &str,
+    mutation: &str,
+) -> Result<Config> {
+    if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+        let messages = errors
+            .iter()
+            .filter_map(|error| error["message"].as_str())
+            .collect::<Vec<_>>();
+        bail!("Introspection of {url} failed: {}", messages.join(", "));
+    }
+
+    let schema = &response["data"]["__schema"];
+    let Some(types) = schema["types"].as_array() else {
+        bail!("Introspection of {url} returned no schema");
+    };
+
+    let mut config = Config::default();
+    let mut roots = BTreeMap::new();
+    if let Some(name) = schema["queryType"]["name"].as_str() {
+        roots.insert(name, query);
+        config.schema.query = Some(query.to_string());
+    }
+    if let Some(name) = schema["mutationType"]["name"].as_str() {
+        roots.insert(name, mutation);
+        config.schema.mutation = Some(mutation.to_string());
+    }
+
+    let mut ctx = Context { url, roots, config };
+    for type_def in types {
+        ctx.type_def(type_def)?;
+    }
+
+    Ok(ctx.config)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::config::ConfigModule;
+
+    fn named(kind: &str, name: &str) -> Value {
+        json!({ "kind": kind, "name": name })
+    }
+
+    fn non_null(of_type: Value) -> Value {
+        json!({ "kind": "NON_NULL", "name": null, "ofType": of_type })
+    }
+
+    fn list(of_type: Value) -> Value {
+        json!({ "kind": "LIST", "name": null, "ofType": of_type })
+    }
+
+    fn field(name: &str, type_ref: Value, args: Value) -> Value {
+        json!({ "name": name, "description": null, "args": args, "type": type_ref })
+    }
+
+    fn response() -> Value {
+        let id = non_null(named("SCALAR", "ID"));
+        json!({
+            "data": {
+                "__schema": {
+                    "queryType": { "name": "QueryRoot" },
+                    "mutationType": { "name": "MutationRoot" },
+                    "types": [
+                        {
+                            "kind": "OBJECT",
+                            "name": "QueryRoot",
+                            "fields": [
+                                field("node", named("INTERFACE", "Node"), json!([
+                                    { "name": "id", "type": id, "defaultValue": null }
+                                ])),
+                                field("search", list(named("UNION", "SearchResult")), json!([
+                                    { "name": "term", "type": named("SCALAR", "String"), "defaultValue": null },
+                                    { "name": "first", "type": named("SCALAR", "Int"), "defaultValue": "10" }
+                                ])),
+                            ],
+                            "interfaces": []
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "MutationRoot",
+                            "fields": [
+                                field("createUser", named("OBJECT", "User"), json!([
+                                    { "name": "input", "type": non_null(named("INPUT_OBJECT", "UserInput")), "defaultValue": null }
+                                ])),
+                            ],
+                            "interfaces": []
+                        },
+                        {
+                            "kind": "INTERFACE",
+                            "name": "Node",
+                            "fields": [field("id", id.clone(), json!([]))],
+                            "interfaces": []
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "User",
+                            "description": "A registered user",
+                            "fields": [
+                                field("id", id.clone(), json!([])),
+                                field("role", named("ENUM", "Role"), json!([])),
+                                field("joinedAt", named("SCALAR", "DateTime"), json!([])),
+                                field("posts", list(non_null(named("OBJECT", "Post"))), json!([
+                                    { "name": "first", "type": named("SCALAR", "Int"), "defaultValue": "5" }
+                                ])),
+                            ],
+                            "interfaces": [{ "name": "Node" }]
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "Post",
+                            "fields": [field("title", named("SCALAR", "String"), json!([]))],
+                            "interfaces": []
+                        },
+                        {
+                            "kind": "INPUT_OBJECT",
+                            "name": "UserInput",
+                            "inputFields": [
+                                { "name": "name", "type": non_null(named("SCALAR", "String")), "defaultValue": null },
+                                { "name": "role", "type": named("ENUM", "Role"), "defaultValue": "MEMBER" }
+                            ]
+                        },
+                        {
+                            "kind": "ENUM",
+                            "name": "Role",
+                            "enumValues": [{ "name": "ADMIN" }, { "name": "MEMBER" }]
+                        },
+                        {
+                            "kind": "UNION",
+                            "name": "SearchResult",
+                            "possibleTypes": [{ "name": "User" }, { "name": "Post" }]
+                        },
+                        { "kind": "SCALAR", "name": "DateTime" },
+                        { "kind": "SCALAR", "name": "String" },
+                        { "kind": "OBJECT", "name": "__Schema", "fields": [] }
+                    ]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_from_introspection() {
+        let config =
+            from_introspection(&response(), "http://upstream/graphql", "Query", "Mutation")
+                .unwrap();
+        insta::assert_snapshot!(ConfigModule::from(config).to_sdl());
+    }
+
+    #[test]
+    fn test_root_field_args() {
+        let config =
+            from_introspection(&response(), "http://upstream/graphql", "Query", "Mutation")
+                .unwrap();
+        let field = &config.types["Query"].fields["search"];
+        let Some(Resolver::Graphql(graphql)) = field.resolvers.first() else {
+            panic!("expected a @graphQL resolver");
+        };
+
+        assert_eq!(graphql.name, "search");
+        assert_eq!(
+            graphql.args.as_ref().unwrap()[1],
+            KeyValue { key: "first".into(), value: "{{.args.first}}".into() }
+        );
+        assert_eq!(field.args["first"].default_value, Some(json!(10)));
+    }
+
+    #[test]
+    fn test_introspection_errors() {
+        let response = json!({ "errors": [{ "message": "introspection is disabled" }] });
+        let error = from_introspection(&response, "http://upstream/graphql", "Query", "Mutation")
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Introspection of http://upstream/graphql failed: introspection is disabled"
+        );
+    }
+}
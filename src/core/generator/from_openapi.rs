@@ -0,0 +1,522 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{bail, Context as _, Result};
+use convert_case::{Case, Casing};
+use serde_json::Value;
+use tailcall_valid::Validator;
+
+use super::NameGenerator;
+use crate::core::config::transformer::{AmbiguousType, TreeShake};
+use crate::core::config::{
+    self, Arg, Config, Enum, Field, Http, KeyValue, Resolver, URLQuery, Variant,
+};
+use crate::core::helpers::gql_type::is_valid_field_name;
+use crate::core::http::Method;
+use crate::core::transform::{Transform, TransformerOps};
+use crate::core::Type;
+
+const SCHEMAS_REF: &str = "#/components/schemas/";
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Builds the config from an OpenAPI 3 document, every operation becomes a
+/// field with an `@http` resolver.
+struct Context<'a> {
+    spec: &'a Value,
+    config: Config,
+    name_generator: &'a NameGenerator,
+    query: &'a str,
+    mutation: &'a str,
+    /// Types generated for the component schemas, by their name in the spec.
+    components: HashMap<String, Type>,
+}
+
+/// Turns arbitrary identifiers of the spec into valid GraphQL names.
+fn to_name(name: &str, case: Case) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_case(case);
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Primary type of the schema along with whether it allows `null`, which is
+/// expressed with `nullable` in 3.0 and with a list of types in 3.1.
+fn schema_type(schema: &Value) -> (Option<&str>, bool) {
+    let nullable = schema["nullable"].as_bool().unwrap_or_default();
+    match &schema["type"] {
+        Value::String(ty) => (Some(ty.as_str()), nullable),
+        Value::Array(types) => {
+            let mut types = types.iter().filter_map(Value::as_str);
+            let null = types.clone().any(|ty| ty == "null");
+            (types.find(|ty| *ty != "null"), nullable || null)
+        }
+        _ => (None, nullable),
+    }
+}
+
+/// Objects with properties that aren't valid field names are kept as JSON,
+/// like the ones of the JSON samples.
+fn is_object(schema: &Value) -> bool {
+    schema["properties"].as_object().is_some_and(|properties| {
+        !properties.is_empty() && properties.keys().all(|key| is_valid_field_name(key))
+    })
+}
+
+fn description(value: &Value) -> Option<String> {
+    value["description"]
+        .as_str()
+        .or(value["summary"].as_str())
+        .map(str::to_string)
+}
+
+/// Returns the JSON schema of the body, preferring JSON content.
+fn content_schema(content: &Value) -> Option<&Value> {
+    let content = content.as_object()?;
+    content
+        .iter()
+        .find(|(media_type, _)| media_type.contains("json"))
+        .or_else(|| content.iter().next())
+        .map(|(_, media)| &media["schema"])
+}
+
+impl<'a> Context<'a> {
+    /// Follows `$ref`s to parameters, request bodies and responses.
+    fn resolve(&self, value: &'a Value) -> &'a Value {
+        match value["$ref"].as_str() {
+            Some(reference) if reference.starts_with("#/") => self
+                .spec
+                .pointer(&reference[1..])
+                .map(|value| self.resolve(value))
+                .unwrap_or(&Value::Null),
+            _ => value,
+        }
+    }
+
+    /// Type of the values described by the schema, types are generated for
+    /// the objects and enums along the way.
+    fn type_of(&mut self, schema: &'a Value) -> Type {
+        if let Some(name) = schema["$ref"]
+            .as_str()
+            .and_then(|reference| reference.strip_prefix(SCHEMAS_REF))
+        {
+            return self.component(name);
+        }
+
+        if let Some(all_of) = schema["allOf"].as_array() {
+            if let [schema] = all_of.as_slice() {
+                return self.type_of(schema);
+            }
+
+            let name = self.name_generator.next();
+            let ty = self.object(schema);
+            self.config.types.insert(name.clone(), ty);
+            return Type::from(name);
+        }
+
+        let name = match schema_type(schema).0 {
+            Some("string") => match schema["format"].as_str() {
+                Some("date") => "Date",
+                Some("date-time") => "DateTime",
+                Some("email") => "Email",
+                Some("uri" | "url") => "Url",
+                Some("byte") => "Bytes",
+                _ => "String",
+            },
+            Some("integer") => match schema["format"].as_str() {
+                Some("int64") => "Int64",
+                _ => "Int",
+            },
+            Some("number") => "Float",
+            Some("boolean") => "Boolean",
+            Some("array") => return self.type_of(&schema["items"]).into_list(),
+            _ if is_object(schema) => {
+                let name = self.name_generator.next();
+                let ty = self.object(schema);
+                self.config.types.insert(name.clone(), ty);
+                return Type::from(name);
+            }
+            // Free-form objects, `oneOf` and `anyOf` accept any value
+            _ => "JSON",
+        };
+
+        Type::from(name.to_string())
+    }
+
+    /// Type of a schema under `components.schemas`, generated once.
+    fn component(&mut self, name: &str) -> Type {
+        if let Some(ty) = self.components.get(name) {
+            return ty.clone();
+        }
+
+        let Some(schema) = self.spec["components"]["schemas"].get(name) else {
+            return Type::from("JSON".to_string());
+        };
+
+        let type_name = to_name(name, Case::Pascal);
+        let is_object = is_object(schema) || schema["allOf"].is_array();
+        let variants = schema["enum"].as_array().and_then(|values| {
+            values
+                .iter()
+                .map(|value| value.as_str().filter(|value| is_valid_field_name(value)))
+                .collect::<Option<BTreeSet<_>>>()
+        });
+
+        let ty = if is_object {
+            // Register the type first, schemas can refer to themselves
+            self.components
+                .insert(name.to_string(), Type::from(type_name.clone()));
+            let mut ty = self.object(schema);
+            ty.doc = description(schema);
+            self.config.types.insert(type_name.clone(), ty);
+            Type::from(type_name)
+        } else if let Some(variants) = variants {
+            let variants = variants
+                .into_iter()
+                .map(|name| Variant { name: name.to_string(), alias: None })
+                .collect();
+            self.config.enums.insert(
+                type_name.clone(),
+                Enum { variants, doc: description(schema) },
+            );
+            Type::from(type_name)
+        } else {
+            self.type_of(schema)
+        };
+
+        self.components.insert(name.to_string(), ty.clone());
+        ty
+    }
+
+    /// Object type with the properties of the schema, `allOf` schemas are
+    /// merged into a single type.
+    fn object(&mut self, schema: &'a Value) -> config::Type {
+        let mut ty = config::Type::default();
+        let mut schemas = vec![schema];
+
+        while let Some(schema) = schemas.pop() {
+            let schema = match schema["$ref"].as_str() {
+                Some(_) => self.resolve(schema),
+                None => schema,
+            };
+            schemas.extend(schema["allOf"].as_array().into_iter().flatten());
+
+            let required = schema["required"]
+                .as_array()
+                .map(|required| {
+                    required
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            for (name, property) in schema["properties"].as_object().into_iter().flatten() {
+                let mut type_of = self.type_of(property);
+                if required.contains(&name.as_str()) && !schema_type(property).1 {
+                    type_of = type_of.into_required();
+                }
+
+                let field = Field { type_of, doc: description(property), ..Default::default() };
+                ty.fields.insert(name.clone(), field);
+            }
+        }
+
+        ty
+    }
+
+    /// Adds the field calling the operation to the query type for `GET` and
+    /// to the mutation type otherwise.
+    fn operation(
+        &mut self,
+        base_url: &str,
+        path: &str,
+        method: &str,
+        operation: &'a Value,
+        path_item: &'a Value,
+    ) -> Result<()> {
+        let field_name = operation["operationId"]
+            .as_str()
+            .map(|id| to_name(id, Case::Camel))
+            .unwrap_or_else(|| to_name(&format!("{method} {path}"), Case::Camel));
+
+        let mut field = Field { doc: description(operation), ..Default::default() };
+        let mut http = Http {
+            method: serde_json::from_value::<Method>(method.to_uppercase().into())?,
+            ..Default::default()
+        };
+        let mut url = format!("{}{path}", base_url.trim_end_matches('/'));
+
+        let parameters = path_item["parameters"]
+            .as_array()
+            .into_iter()
+            .chain(operation["parameters"].as_array())
+            .flatten()
+            .map(|parameter| self.resolve(parameter))
+            .collect::<Vec<_>>();
+
+        for parameter in parameters {
+            let (Some(name), Some(location)) =
+                (parameter["name"].as_str(), parameter["in"].as_str())
+            else {
+                continue;
+            };
+            let arg_name = to_name(name, Case::Camel);
+            let value = format!("{{{{.args.{arg_name}}}}}");
+
+            match location {
+                "path" => url = url.replace(&format!("{{{name}}}"), &value),
+                "query" => {
+                    http.query
+                        .push(URLQuery { key: name.to_string(), value, skip_empty: None })
+                }
+                "header" => http.headers.push(KeyValue { key: name.to_string(), value }),
+                _ => continue,
+            }
+
+            let mut type_of = self.type_of(&parameter["schema"]);
+            if location == "path" || parameter["required"].as_bool().unwrap_or_default() {
+                type_of = type_of.into_required();
+            }
+            let arg = Arg { type_of, doc: description(parameter), ..Default::default() };
+            field.args.insert(arg_name, arg);
+        }
+
+        let request_body = self.resolve(&operation["requestBody"]);
+        if let Some(schema) = content_schema(&request_body["content"]) {
+            let mut type_of = self.type_of(schema);
+            if request_body["required"].as_bool().unwrap_or_default() {
+                type_of = type_of.into_required();
+            }
+            http.body = Some(Value::String("{{.args.input}}".into()));
+            field
+                .args
+                .insert("input".into(), Arg { type_of, ..Default::default() });
+        }
+
+        field.type_of = match self.response(&operation["responses"]) {
+            Some(schema) => self.type_of(schema),
+            None => Type::from("Empty".to_string()),
+        };
+
+        http.url = url;
+        field.resolvers = Resolver::Http(http).into();
+
+        let type_name = if method == "get" {
+            self.config.schema.query = Some(self.query.to_string());
+            self.query
+        } else {
+            self.config.schema.mutation = Some(self.mutation.to_string());
+            self.mutation
+        };
+        self.config
+            .types
+            .entry(type_name.to_string())
+            .or_default()
+            .fields
+            .insert(field_name, field);
+
+        Ok(())
+    }
+
+    /// Schema of the successful response, or of the default one.
+    fn response(&self, responses: &'a Value) -> Option<&'a Value> {
+        let responses = responses.as_object()?;
+        let mut codes = responses
+            .keys()
+            .filter(|code| code.starts_with('2'))
+            .collect::<Vec<_>>();
+        codes.sort();
+
+        codes
+            .into_iter()
+            .chain(responses.keys().filter(|code| *code == "default"))
+            .find_map(|code| content_schema(&self.resolve(&responses[code])["content"]))
+    }
+}
+
+/// Generates the config for the operations of an OpenAPI 3 document, `url`
+/// overrides the first server of the document.
+pub fn from_openapi(
+    spec: &Value,
+    query: &str,
+    mutation: &str,
+    url: Option<&str>,
+    name_generator: &NameGenerator,
+) -> Result<Config> {
+    if !spec["openapi"].as_str().is_some_and(|v| v.starts_with('3')) {
+        bail!("Only OpenAPI 3 documents are supported");
+    }
+
+    let base_url = url
+        .or(spec["servers"][0]["url"].as_str())
+        .filter(|url| url.starts_with("http"))
+        .context("An absolute server URL is required, either in the document or as `url`")?;
+
+    let mut ctx = Context {
+        spec,
+        config: Config::default(),
+        name_generator,
+        query,
+        mutation,
+        components: HashMap::new(),
+    };
+
+    for (path, path_item) in spec["paths"].as_object().into_iter().flatten() {
+        for method in METHODS {
+            if let Some(operation) = path_item.get(method) {
+                ctx.operation(base_url, path, method, operation, path_item)?;
+            }
+        }
+    }
+
+    let config = AmbiguousType::default()
+        .pipe(TreeShake)
+        .transform(ctx.config)
+        .to_result()?;
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::config::ConfigModule;
+
+    const PETSTORE: &str = r#"
+        openapi: 3.0.0
+        info:
+          title: Petstore
+          version: 1.0.0
+        servers:
+          - url: http://petstore.swagger.io/v1
+        paths:
+          /pets:
+            get:
+              operationId: listPets
+              summary: List all pets
+              parameters:
+                - name: limit
+                  in: query
+                  schema:
+                    type: integer
+                    format: int32
+              responses:
+                '200':
+                  description: A paged array of pets
+                  content:
+                    application/json:
+                      schema:
+                        type: array
+                        items:
+                          $ref: '#/components/schemas/Pet'
+            post:
+              operationId: create-pet
+              requestBody:
+                required: true
+                content:
+                  application/json:
+                    schema:
+                      $ref: '#/components/schemas/Pet'
+              responses:
+                '201':
+                  description: The created pet
+                  content:
+                    application/json:
+                      schema:
+                        $ref: '#/components/schemas/Pet'
+          /pets/{petId}:
+            parameters:
+              - $ref: '#/components/parameters/PetId'
+            get:
+              responses:
+                '200':
+                  description: Expected response to a valid request
+                  content:
+                    application/json:
+                      schema:
+                        type: object
+                        properties:
+                          pet:
+                            $ref: '#/components/schemas/Pet'
+                          owner:
+                            type: string
+                            nullable: true
+                          metadata:
+                            type: object
+                            properties:
+                              created-at:
+                                type: string
+            delete:
+              operationId: deletePet
+              responses:
+                '204':
+                  description: Deleted
+        components:
+          parameters:
+            PetId:
+              name: petId
+              in: path
+              required: true
+              schema:
+                type: string
+          schemas:
+            Pet:
+              description: A pet of the store
+              type: object
+              required: [id, name]
+              properties:
+                id:
+                  type: integer
+                  format: int64
+                name:
+                  type: string
+                status:
+                  $ref: '#/components/schemas/Status'
+                tags:
+                  type: array
+                  items:
+                    type: string
+            Status:
+              type: string
+              enum: [available, pending, sold]
+    "#;
+
+    fn generate(spec: &str, url: Option<&str>) -> Result<Config> {
+        let spec: Value = serde_yaml_ng::from_str(spec)?;
+        from_openapi(&spec, "Query", "Mutation", url, &NameGenerator::new("T"))
+    }
+
+    #[test]
+    fn test_from_openapi() {
+        let config = generate(PETSTORE, None).unwrap();
+        insta::assert_snapshot!(ConfigModule::from(config).to_sdl());
+    }
+
+    #[test]
+    fn test_url_override() {
+        let config = generate(PETSTORE, Some("http://localhost:8080")).unwrap();
+        let field = &config.types["Query"].fields["listPets"];
+        let Some(Resolver::Http(http)) = field.resolvers.first() else {
+            panic!("expected an @http resolver");
+        };
+
+        assert_eq!(http.url, "http://localhost:8080/pets");
+    }
+
+    #[test]
+    fn test_relative_server() {
+        let spec = "openapi: 3.1.0\nservers: [{url: /v1}]\npaths: {}";
+        let error = generate(spec, None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "An absolute server URL is required, either in the document or as `url`"
+        );
+    }
+}
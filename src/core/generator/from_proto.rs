@@ -288,6 +288,8 @@ impl Context {
                     if self.map_types.contains(&type_name[1..]) {
                         // override type with single scalar
                         cfg_field.type_of = "JSON".to_string().into();
+                    } else if let Some(scalar) = well_known_scalar(type_name) {
+                        cfg_field.type_of = cfg_field.type_of.with_name(scalar.to_string());
                     } else {
                         // for non-primitive types
                         let type_of = graphql_type_from_ref(type_name)?
@@ -366,8 +368,12 @@ impl Context {
                     .into_object_type()
                     .to_string();
                 cfg_field.type_of = cfg_field.type_of.with_name(output_ty);
+                // server-streaming methods resolve to every message of the stream
+                if method.server_streaming() {
+                    cfg_field.type_of = cfg_field.type_of.into_list();
+                }
 
-                cfg_field.resolvers = Resolver::Grpc(Grpc {
+                cfg_field.resolvers = Resolver::Grpc(Box::new(Grpc {
                     url: url.to_string(),
                     body,
                     batch_key: vec![],
@@ -376,7 +382,11 @@ impl Context {
                     dedupe: None,
                     select: None,
                     on_response_body: None,
-                })
+                    compression: None,
+                    protocol: None,
+                    timeout: None,
+                    health_check: None,
+                }))
                 .into();
 
                 let method_path =
@@ -434,6 +444,29 @@ fn convert_primitive_type(proto_ty: &str) -> String {
     .to_string()
 }
 
+/// Maps the well-known types of protobuf to the scalars matching their
+/// proto3 JSON representation.
+/// see https://protobuf.dev/programming-guides/proto3/#json
+fn well_known_scalar(type_name: &str) -> Option<&'static str> {
+    let scalar = match type_name.strip_prefix(".google.protobuf.")? {
+        "Timestamp" => "DateTime",
+        // durations are encoded as strings like "1.5s"
+        "Duration" | "FieldMask" => "String",
+        "DoubleValue" | "FloatValue" => "Float",
+        "Int32Value" => "Int",
+        "Int64Value" => "Int64",
+        "UInt32Value" => "UInt32",
+        "UInt64Value" => "UInt64",
+        "BoolValue" => "Boolean",
+        "StringValue" => "String",
+        "BytesValue" => "Bytes",
+        "Struct" | "Value" | "ListValue" => "JSON",
+        _ => return None,
+    };
+
+    Some(scalar)
+}
+
 /// Determines the output type for a service method.
 fn get_output_type(output_ty: &str) -> Result<GraphQLType<Unparsed>> {
     // type, required
@@ -443,6 +476,9 @@ fn get_output_type(output_ty: &str) -> Result<GraphQLType<Unparsed>> {
             Ok(GraphQLType::new("Empty"))
         }
         _ => {
+            if let Some(scalar) = well_known_scalar(output_ty) {
+                return Ok(GraphQLType::new(scalar));
+            }
             // Setting it not null by default. There's no way to infer this from proto file
             graphql_type_from_ref(output_ty)
         }
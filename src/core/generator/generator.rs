@@ -7,6 +7,8 @@ use serde_json::Value;
 use tailcall_valid::Validator;
 use url::Url;
 
+use super::from_introspection::from_introspection;
+use super::from_openapi::from_openapi;
 use super::from_proto::from_proto;
 use super::proto::connect_rpc::ConnectRPC;
 use super::{FromJsonGenerator, NameGenerator, RequestSample, PREFIX};
@@ -49,6 +51,14 @@ pub enum Input {
         schema: String,
         source: config::Source,
     },
+    OpenApi {
+        spec: Value,
+        url: Option<String>,
+    },
+    Introspection {
+        url: String,
+        response: Value,
+    },
 }
 
 impl Default for Generator {
@@ -100,6 +110,7 @@ impl Generator {
             headers: None,
             meta: None,
             proto_paths: None,
+            refresh_interval: None,
         });
         Ok(config)
     }
@@ -145,6 +156,23 @@ impl Generator {
                     };
                     config = config.merge_right(proto_config);
                 }
+                Input::OpenApi { spec, url } => {
+                    let mutation = self.mutation.as_deref().unwrap_or("Mutation");
+                    let openapi_config = from_openapi(
+                        spec,
+                        &self.query,
+                        mutation,
+                        url.as_deref(),
+                        &type_name_generator,
+                    )?;
+                    config = config.merge_right(openapi_config);
+                }
+                Input::Introspection { url, response } => {
+                    let mutation = self.mutation.as_deref().unwrap_or("Mutation");
+                    let introspection_config =
+                        from_introspection(response, url, &self.query, mutation)?;
+                    config = config.merge_right(introspection_config);
+                }
             }
         }
 
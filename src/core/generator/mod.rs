@@ -1,10 +1,13 @@
+mod from_introspection;
 mod from_json;
+mod from_openapi;
 mod from_proto;
 mod generator;
 mod graphql_type;
 mod json;
 mod proto;
 
+pub use from_introspection::{from_introspection, INTROSPECTION_QUERY};
 pub use from_json::{FromJsonGenerator, RequestSample};
 pub use generator::{Generator, Input};
 
@@ -17,7 +17,7 @@ impl Transform for ConnectRPC {
                     .0
                     .iter()
                     .map(|resolver| match resolver {
-                        Resolver::Grpc(grpc) => Resolver::Http(Http::from(grpc.clone())),
+                        Resolver::Grpc(grpc) => Resolver::Http(Http::from(grpc.as_ref().clone())),
                         other => other.clone(),
                     })
                     .collect();
@@ -85,6 +85,10 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            compression: Default::default(),
+            protocol: Default::default(),
+            timeout: Default::default(),
+            health_check: Default::default(),
         };
 
         let http = Http::from(grpc);
@@ -105,6 +109,10 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            compression: Default::default(),
+            protocol: Default::default(),
+            timeout: Default::default(),
+            health_check: Default::default(),
         };
 
         let http = Http::from(grpc);
@@ -123,6 +131,10 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            compression: Default::default(),
+            protocol: Default::default(),
+            timeout: Default::default(),
+            health_check: Default::default(),
         };
 
         let http = Http::from(grpc);
@@ -150,6 +162,10 @@ mod tests {
             dedupe: Some(true),
             select: Some(Value::String("select_value".to_string())),
             on_response_body: Some("on_response_body_value".to_string()),
+            compression: None,
+            protocol: None,
+            timeout: None,
+            health_check: None,
         };
 
         let http = Http::from(grpc);
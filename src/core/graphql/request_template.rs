@@ -2,16 +2,18 @@
 
 use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderValue};
 use tailcall_hasher::TailcallHasher;
 use tracing::info;
 
-use crate::core::config::{GraphQLOperationType, KeyValue};
+use crate::core::config::{self, GraphQLOperationType, KeyValue};
 use crate::core::has_headers::HasHeaders;
 use crate::core::helpers::headers::MustacheHeaders;
 use crate::core::http::Method::POST;
+use crate::core::http::OAuth2;
 use crate::core::ir::model::{CacheKey, IoId};
 use crate::core::ir::{GraphQLOperationContext, RelatedFields};
 use crate::core::mustache::Mustache;
@@ -45,6 +47,112 @@ impl From<Mustache> for Selection {
     }
 }
 
+/// Selection nested into the field of an `_entities` query or of the lookup of
+/// a stitched type, see [`RequestTemplate::entity`].
+#[derive(Debug, Clone)]
+pub struct Entity {
+    /// Type of the fragment the selection is wrapped into, set for
+    /// `_entities` queries that return a list of entities.
+    pub type_name: Option<String>,
+    pub selection: EntitySelection,
+    /// Subgraphs selecting the field along with the entity, see
+    /// [`config::EntityFetch::sources`].
+    pub sources: Vec<config::FieldSource>,
+    /// Fields of the sibling queries on the same entity, they're all selected
+    /// so one deduplicated request fetches them.
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum EntitySelection {
+    /// A field of the entity, the selection of the request is nested into it.
+    Field {
+        name: String,
+        arguments: Vec<(String, Mustache)>,
+    },
+    /// A fixed set of fields, the selection of the request is ignored.
+    Fields(String),
+}
+
+impl Entity {
+    fn render<C: PathGraphql>(&self, selection_set: &str, ctx: &C) -> String {
+        let fields = match (&self.group, &self.selection) {
+            (Some(group), _) => group.to_owned(),
+            (None, EntitySelection::Field { name, arguments }) => {
+                let arguments = render_arguments(arguments, ctx);
+                if arguments.is_empty() {
+                    format!("{name} {selection_set}")
+                } else {
+                    format!("{name}({arguments}) {selection_set}")
+                }
+            }
+            (None, EntitySelection::Fields(fields)) => fields.to_owned(),
+        };
+
+        match &self.type_name {
+            Some(type_name) => format!("{{ ... on {} {{ {} }} }}", type_name, fields.trim_end()),
+            None => format!("{{ {} }}", fields.trim_end()),
+        }
+    }
+
+    /// Extracts the value from the entity of the response, only one
+    /// representation is sent by `_entities` request.
+    pub fn extract(&self, entity: async_graphql::Value) -> async_graphql::Value {
+        let entity = match (&self.type_name, entity) {
+            (None, entity) => entity,
+            (Some(_), async_graphql::Value::List(entities)) => {
+                entities.into_iter().next().unwrap_or_default()
+            }
+            (Some(_), _) => return Default::default(),
+        };
+
+        match &self.selection {
+            EntitySelection::Field { name, .. } => match entity {
+                async_graphql::Value::Object(mut entity) => {
+                    entity.swap_remove(name.as_str()).unwrap_or_default()
+                }
+                _ => Default::default(),
+            },
+            EntitySelection::Fields(_) => entity,
+        }
+    }
+}
+
+impl From<&config::EntitySelection> for EntitySelection {
+    fn from(selection: &config::EntitySelection) -> Self {
+        match selection {
+            config::EntitySelection::Field { name, args } => EntitySelection::Field {
+                name: name.clone(),
+                arguments: args
+                    .iter()
+                    .map(|arg| {
+                        (
+                            arg.clone(),
+                            Mustache::parse(&format!("{{{{.args.{arg}}}}}")),
+                        )
+                    })
+                    .collect(),
+            },
+            config::EntitySelection::Fields(fields) => EntitySelection::Fields(fields.clone()),
+        }
+    }
+}
+
+fn render_arguments<C: PathGraphql>(arguments: &[(String, Mustache)], ctx: &C) -> String {
+    arguments
+        .iter()
+        .filter_map(|(k, v)| {
+            let value = v.render_graphql(ctx);
+            if value.is_empty() {
+                None
+            } else {
+                Some(format!(r#"{}: {}"#, k, value.escape_default()))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// RequestTemplate for GraphQL requests (See RequestTemplate documentation)
 #[derive(Setters, Debug, Clone)]
 pub struct RequestTemplate {
@@ -56,6 +164,16 @@ pub struct RequestTemplate {
     pub headers: MustacheHeaders,
     pub related_fields: RelatedFields,
     pub selection: Option<Selection>,
+    /// Set for `_entities` queries of the supergraph gateway, the selection is
+    /// wrapped into a fragment on the entity.
+    pub entity: Option<Entity>,
+    /// Variables of the client's operation used by the selection along with
+    /// their definitions, e.g. `$id: ID!`. They're declared on the upstream
+    /// operation.
+    pub variables: Vec<(String, String)>,
+    /// Values of the variables as a JSON object, set for every request.
+    pub variable_values: Option<String>,
+    pub oauth2: Option<Arc<OAuth2>>,
 }
 
 impl RequestTemplate {
@@ -114,29 +232,20 @@ impl RequestTemplate {
         &self,
         ctx: &C,
     ) -> String {
-        let operation_type = &self.operation_type;
-
         let selection_set = match &self.selection {
             Some(Selection::Resolved(s)) => Cow::Borrowed(s),
             Some(Selection::UnResolved(u)) => Cow::Owned(u.to_string()),
             None => Cow::Owned(ctx.selection_set(&self.related_fields).unwrap_or_default()),
         };
+        let selection_set = match &self.entity {
+            Some(entity) => Cow::Owned(entity.render(&selection_set, ctx)),
+            None => selection_set,
+        };
 
         let mut operation = Cow::Borrowed(&self.operation_name);
 
         if let Some(args) = &self.operation_arguments {
-            let args = args
-                .iter()
-                .filter_map(|(k, v)| {
-                    let value = v.render_graphql(ctx);
-                    if value.is_empty() {
-                        None
-                    } else {
-                        Some(format!(r#"{}: {}"#, k, value.escape_default()))
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(", ");
+            let args = render_arguments(args, ctx);
 
             if !args.is_empty() {
                 let operation = operation.to_mut();
@@ -156,8 +265,30 @@ impl RequestTemplate {
             }
         }
 
-        let query =
-            format!(r#"{{ "query": "{operation_type} {{ {operation} {selection_set} }}" }}"#);
+        let mut operation_type = self.operation_type.to_string();
+
+        if !self.variables.is_empty() {
+            let definitions = self
+                .variables
+                .iter()
+                .map(|(_, definition)| definition.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            operation_type.push('(');
+            operation_type.push_str(&definitions.escape_default().to_string());
+            operation_type.push(')');
+        }
+
+        let variables = self
+            .variable_values
+            .as_ref()
+            .map(|values| format!(r#", "variables": {values}"#))
+            .unwrap_or_default();
+
+        let query = format!(
+            r#"{{ "query": "{operation_type} {{ {operation} {selection_set} }}"{variables} }}"#
+        );
         info!("Query {} ", query);
         query
     }
@@ -188,6 +319,10 @@ impl RequestTemplate {
             headers,
             related_fields,
             selection: None,
+            entity: None,
+            variables: Vec::new(),
+            variable_values: None,
+            oauth2: None,
         })
     }
 }
@@ -211,12 +346,15 @@ mod tests {
     use serde_json::json;
 
     use crate::core::config::GraphQLOperationType;
-    use crate::core::graphql::request_template::RelatedFields;
+    use crate::core::graphql::request_template::{
+        Entity, EntitySelection, RelatedFields, Selection,
+    };
     use crate::core::graphql::RequestTemplate;
     use crate::core::has_headers::HasHeaders;
     use crate::core::ir::model::CacheKey;
     use crate::core::ir::GraphQLOperationContext;
     use crate::core::json::JsonLike;
+    use crate::core::mustache::Mustache;
     use crate::core::path::PathGraphql;
 
     struct Context {
@@ -314,6 +452,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_entity_query() {
+        let tmpl = RequestTemplate::new(
+            "http://localhost:3000".to_string(),
+            &GraphQLOperationType::Query,
+            "_entities",
+            Some(
+                serde_json::from_str(
+                    r#"[{"key": "representations", "value": "[{ __typename: \"User\", id: {{.value.id}} }]"}]"#,
+                )
+                .unwrap(),
+            )
+            .as_ref(),
+            vec![],
+            RelatedFields::default(),
+        )
+        .unwrap()
+        .entity(Some(Entity {
+            type_name: Some("User".to_string()),
+            selection: EntitySelection::Field {
+                name: "posts".to_string(),
+                arguments: vec![("first".to_string(), Mustache::parse("{{.args.first}}"))],
+            },
+            sources: vec![],
+            group: None,
+        }));
+        let ctx = Context {
+            value: Value::from_json(json!({"value": {"id": 1}, "args": {"first": 2}})).unwrap(),
+            headers: Default::default(),
+        };
+
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = req.body().unwrap().as_bytes().unwrap().to_owned();
+
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            r#"{ "query": "query { _entities(representations: [{ __typename: \"User\", id: 1 }]) { ... on User { posts(first: 2) { a,b,c } } } }" }"#
+        );
+
+        let entities = Value::from_json(json!([{"posts": [{"a": 1}]}])).unwrap();
+        assert_eq!(
+            tmpl.entity.unwrap().extract(entities),
+            Value::from_json(json!([{"a": 1}])).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_query_with_variables() {
+        let mut tmpl = RequestTemplate::new(
+            "http://localhost:3000".to_string(),
+            &GraphQLOperationType::Query,
+            "user",
+            None,
+            vec![],
+            RelatedFields::default(),
+        )
+        .unwrap();
+        tmpl.selection = Some(Selection::Resolved(
+            "{ pic(size: $size) name @include(if: $withName) }".to_string(),
+        ));
+        tmpl.variables = vec![
+            ("size".to_string(), "$size: Int = 64".to_string()),
+            ("withName".to_string(), "$withName: Boolean!".to_string()),
+        ];
+        tmpl.variable_values = Some(r#"{"withName":true}"#.to_string());
+        let ctx = Context { value: Value::Null, headers: Default::default() };
+
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = req.body().unwrap().as_bytes().unwrap().to_owned();
+
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            r#"{ "query": "query($size: Int = 64, $withName: Boolean!) { user { pic(size: $size) name @include(if: $withName) } }", "variables": {"withName":true} }"#
+        );
+    }
+
     fn create_gql_request_template_and_ctx(json: serde_json::Value) -> (RequestTemplate, Context) {
         let value = Value::from_json(json).unwrap();
 
@@ -0,0 +1,115 @@
+use std::io::{Read, Write};
+
+use anyhow::{bail, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use http::HeaderMap;
+
+pub use crate::core::config::GrpcCompression;
+
+pub static GRPC_ENCODING: &str = "grpc-encoding";
+pub static GRPC_ACCEPT_ENCODING: &str = "grpc-accept-encoding";
+
+/// Encodings advertised to upstreams, responses compressed with any of them
+/// can be decoded.
+pub static ACCEPTED_ENCODINGS: &str = "gzip";
+
+/// Largest message accepted from upstreams, once decompressed. Same as the
+/// default limit of tonic.
+pub const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Reads the algorithm the messages of a response are compressed with from
+/// the given header, `grpc-encoding` for gRPC.
+pub fn response_encoding(headers: &HeaderMap, header: &str) -> Result<GrpcCompression> {
+    match headers
+        .get(header)
+        .map(|encoding| encoding.to_str())
+        .transpose()?
+    {
+        None | Some("identity") => Ok(GrpcCompression::Identity),
+        Some("gzip") => Ok(GrpcCompression::Gzip),
+        Some(encoding) => bail!("Unsupported {}: {}", header, encoding),
+    }
+}
+
+pub fn compress(compression: GrpcCompression, bytes: &[u8]) -> Result<Vec<u8>> {
+    match compression {
+        GrpcCompression::Identity => Ok(bytes.to_vec()),
+        GrpcCompression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+/// Decompresses a message, failing once it grows past [MAX_MESSAGE_SIZE]
+/// instead of inflating it entirely.
+pub fn decompress(compression: GrpcCompression, bytes: &[u8]) -> Result<Vec<u8>> {
+    match compression {
+        GrpcCompression::Identity => {
+            bail!("Received a compressed message without grpc-encoding")
+        }
+        GrpcCompression::Gzip => {
+            let mut buf = Vec::new();
+            GzDecoder::new(bytes)
+                .take(MAX_MESSAGE_SIZE as u64 + 1)
+                .read_to_end(&mut buf)?;
+            check_message_size(buf.len())?;
+            Ok(buf)
+        }
+    }
+}
+
+pub fn check_message_size(len: usize) -> Result<()> {
+    if len > MAX_MESSAGE_SIZE {
+        bail!(
+            "Received a message larger than the limit of {} bytes",
+            MAX_MESSAGE_SIZE
+        )
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue};
+
+    use super::*;
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let message = b"a message that is compressed with gzip";
+        let compressed = compress(GrpcCompression::Gzip, message).unwrap();
+        let decompressed = decompress(GrpcCompression::Gzip, &compressed).unwrap();
+
+        assert_eq!(decompressed, message);
+    }
+
+    #[test]
+    fn test_gzip_message_too_large() {
+        let message = vec![0; MAX_MESSAGE_SIZE + 1];
+        let compressed = compress(GrpcCompression::Gzip, &message).unwrap();
+
+        assert!(compressed.len() < MAX_MESSAGE_SIZE);
+        assert!(decompress(GrpcCompression::Gzip, &compressed).is_err());
+    }
+
+    #[test]
+    fn test_response_encoding() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            response_encoding(&headers, GRPC_ENCODING).unwrap(),
+            GrpcCompression::Identity
+        );
+
+        headers.insert(GRPC_ENCODING, HeaderValue::from_static("gzip"));
+        assert_eq!(
+            response_encoding(&headers, GRPC_ENCODING).unwrap(),
+            GrpcCompression::Gzip
+        );
+
+        headers.insert(GRPC_ENCODING, HeaderValue::from_static("snappy"));
+        assert!(response_encoding(&headers, GRPC_ENCODING).is_err());
+    }
+}
@@ -14,7 +14,7 @@ use crate::core::config::group_by::GroupBy;
 use crate::core::config::Batch;
 use crate::core::data_loader::{DataLoader, Loader};
 use crate::core::grpc::request::create_grpc_request;
-use crate::core::http::Response;
+use crate::core::http::{OAuth2, Response};
 use crate::core::json::JsonLike;
 use crate::core::runtime::TargetRuntime;
 
@@ -23,6 +23,7 @@ pub struct GrpcDataLoader {
     pub(crate) runtime: TargetRuntime,
     pub(crate) operation: ProtobufOperation,
     pub(crate) group_by: Option<GroupBy>,
+    pub(crate) oauth2: Option<Arc<OAuth2>>,
 }
 
 impl GrpcDataLoader {
@@ -38,7 +39,15 @@ impl GrpcDataLoader {
     ) -> anyhow::Result<HashMap<DataLoaderRequest, Response<async_graphql::Value>>> {
         let results = keys.iter().map(|key| async {
             let result = match key.to_request() {
-                Ok(req) => execute_grpc_request(&self.runtime, &self.operation, req).await,
+                Ok(req) => {
+                    execute_grpc_request(
+                        &self.runtime,
+                        &self.operation,
+                        req,
+                        self.oauth2.as_deref(),
+                    )
+                    .await
+                }
                 Err(error) => Err(error),
             };
 
@@ -75,8 +84,13 @@ impl GrpcDataLoader {
             multiple_body,
         );
 
-        let response =
-            execute_grpc_request(&self.runtime, &self.operation, multiple_request).await?;
+        let response = execute_grpc_request(
+            &self.runtime,
+            &self.operation,
+            multiple_request,
+            self.oauth2.as_deref(),
+        )
+        .await?;
 
         let path = &group_by.path();
         let response_body = response.body.group_by(path);
@@ -75,6 +75,7 @@ mod tests {
             headers: None,
             meta: None,
             proto_paths: None,
+            refresh_interval: None,
         }]);
         let method = GrpcMethod {
             package: "greetings".to_string(),
@@ -86,7 +87,7 @@ mod tests {
             "foo".to_string(),
             Type::default().fields(vec![(
                 "bar",
-                Field::default().resolvers(Resolver::Grpc(grpc).into()),
+                Field::default().resolvers(Resolver::Grpc(Box::new(grpc)).into()),
             )]),
         );
 
@@ -111,6 +112,7 @@ mod tests {
             headers: HeaderMap::new(),
             operation: get_protobuf_op().await,
             body: "{}".to_owned(),
+            timeout: None,
         };
 
         let dl_req_1 = DataLoaderRequest::new(tmpl.clone(), batch_headers.clone());
@@ -130,6 +132,7 @@ mod tests {
             )]),
             operation: get_protobuf_op().await,
             body: "{}".to_owned(),
+            timeout: None,
         };
         let tmpl_2 = tmpl_1.clone();
 
@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use prost_reflect::prost_types::FileDescriptorSet;
+use serde::Serialize;
+use serde_json::json;
+
+use super::protobuf::{ProtobufOperation, ProtobufSet};
+use super::request::{create_grpc_request, execute_grpc_request};
+use crate::core::blueprint::{GrpcHealthCheck, GrpcMethod};
+use crate::core::config::ConfigReaderContext;
+use crate::core::http::OAuth2;
+use crate::core::mustache::Mustache;
+use crate::core::runtime::TargetRuntime;
+
+/// https://github.com/grpc/grpc-proto/blob/master/grpc/health/v1/health.proto
+const HEALTH_PROTO: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/core/grpc/proto/health.proto"
+));
+
+const CHECK_METHOD: &str = "grpc.health.v1.Health.Check";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HealthStatus {
+    /// Not checked yet, or the upstream doesn't know its status.
+    #[default]
+    Unknown,
+    Serving,
+    NotServing,
+    ServiceUnknown,
+    /// The check failed before the upstream responded with a status.
+    Unreachable,
+}
+
+impl HealthStatus {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "SERVING" => HealthStatus::Serving,
+            "NOT_SERVING" => HealthStatus::NotServing,
+            "SERVICE_UNKNOWN" => HealthStatus::ServiceUnknown,
+            _ => HealthStatus::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpstreamHealth {
+    pub url: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub service: String,
+    pub required: bool,
+    pub status: HealthStatus,
+}
+
+/// Probes gRPC upstreams with `grpc.health.v1.Health/Check` and keeps the
+/// latest status of each one.
+pub struct GrpcHealth {
+    operation: ProtobufOperation,
+    checks: Vec<(GrpcHealthCheck, Option<OAuth2>, RwLock<HealthStatus>)>,
+}
+
+impl GrpcHealth {
+    pub fn try_new(checks: &[GrpcHealthCheck]) -> Result<Self> {
+        let file = protox_parse::parse("grpc/health/v1/health.proto", HEALTH_PROTO)?;
+        let set = ProtobufSet::from_proto_file(FileDescriptorSet { file: vec![file] })?;
+        let method = GrpcMethod::try_from(CHECK_METHOD).map_err(|e| anyhow::anyhow!("{e}"))?;
+        let operation = set.find_service(&method)?.find_operation(&method)?;
+
+        let checks = checks
+            .iter()
+            .map(|check| {
+                let oauth2 = check
+                    .oauth2
+                    .as_ref()
+                    .map(OAuth2::try_from)
+                    .transpose()
+                    .context("Invalid OAuth2 token URL")?;
+                Ok((check.clone(), oauth2, RwLock::new(HealthStatus::default())))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { operation, checks })
+    }
+
+    /// Spawns a task per upstream that checks it on its interval, starting
+    /// right away.
+    pub fn start(self: Arc<Self>, runtime: TargetRuntime, vars: BTreeMap<String, String>) {
+        let vars = Arc::new(vars);
+
+        for index in 0..self.checks.len() {
+            let health = self.clone();
+            let runtime = runtime.clone();
+            let vars = vars.clone();
+
+            tokio::spawn(async move {
+                let check = &health.checks[index].0;
+                let mut interval = tokio::time::interval(Duration::from_secs(check.interval));
+
+                loop {
+                    interval.tick().await;
+                    health.update(index, &runtime, &vars).await;
+                }
+            });
+        }
+    }
+
+    async fn update(&self, index: usize, runtime: &TargetRuntime, vars: &BTreeMap<String, String>) {
+        let (check, oauth2, status) = &self.checks[index];
+
+        let result = match self.check(check, oauth2.as_ref(), runtime, vars).await {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::warn!("Health check of {} failed: {err}", check.url);
+                HealthStatus::Unreachable
+            }
+        };
+
+        *status
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = result;
+    }
+
+    async fn check(
+        &self,
+        check: &GrpcHealthCheck,
+        oauth2: Option<&OAuth2>,
+        runtime: &TargetRuntime,
+        vars: &BTreeMap<String, String>,
+    ) -> Result<HealthStatus> {
+        let ctx = ConfigReaderContext::new(runtime).vars(vars);
+        let url = Mustache::parse(&check.url).render(&ctx);
+        let url = format!("{}/grpc.health.v1.Health/Check", url.trim_end_matches('/'));
+
+        let body = json!({ "service": check.service }).to_string();
+        let body = self.operation.convert_input(&body)?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in check.headers.iter() {
+            let value = Mustache::parse(value).render(&ctx);
+            headers.insert(HeaderName::from_str(name)?, HeaderValue::from_str(&value)?);
+        }
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(self.operation.content_type()),
+        );
+
+        let request = create_grpc_request(url.parse()?, headers, body);
+        let response = execute_grpc_request(runtime, &self.operation, request, oauth2).await?;
+
+        let status = match &response.body {
+            async_graphql::Value::Object(map) => map.get("status").cloned(),
+            _ => None,
+        }
+        .context("Expected status in HealthCheckResponse")?;
+
+        Ok(match status {
+            async_graphql::Value::Enum(name) => HealthStatus::from_name(name.as_str()),
+            async_graphql::Value::String(name) => HealthStatus::from_name(&name),
+            _ => HealthStatus::Unknown,
+        })
+    }
+
+    /// Returns `true` once every required upstream is serving.
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|(check, _, status)| {
+            !check.required
+                || *status.read().unwrap_or_else(|e| e.into_inner()) == HealthStatus::Serving
+        })
+    }
+
+    pub fn upstreams(&self) -> Vec<UpstreamHealth> {
+        self.checks
+            .iter()
+            .map(|(check, _, status)| UpstreamHealth {
+                url: check.url.clone(),
+                service: check.service.clone(),
+                required: check.required,
+                status: *status.read().unwrap_or_else(|e| e.into_inner()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn health_check(url: String, required: bool) -> GrpcHealthCheck {
+        GrpcHealthCheck {
+            url,
+            service: "news.NewsService".into(),
+            interval: 10,
+            required,
+            headers: BTreeMap::new(),
+            oauth2: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serving_upstream() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/grpc.health.v1.Health/Check")
+                .body("\0\0\0\0\x12\n\x10news.NewsService");
+            then.status(200).body("\0\0\0\0\x02\x08\x01");
+        });
+
+        let health = GrpcHealth::try_new(&[health_check(server.base_url(), true)]).unwrap();
+        assert!(!health.is_ready());
+
+        let runtime = crate::core::runtime::test::init(None);
+        health.update(0, &runtime, &BTreeMap::new()).await;
+
+        mock.assert();
+        assert!(health.is_ready());
+        assert_eq!(health.upstreams()[0].status, HealthStatus::Serving);
+    }
+
+    #[tokio::test]
+    async fn test_upstream_headers() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/grpc.health.v1.Health/Check")
+                .header("authorization", "Bearer secret")
+                .header("content-type", "application/grpc");
+            then.status(200).body("\0\0\0\0\x02\x08\x01");
+        });
+
+        let mut check = health_check(server.base_url(), true);
+        check.headers = BTreeMap::from([
+            ("authorization".into(), "Bearer {{.vars.token}}".into()),
+            ("content-type".into(), "application/json".into()),
+        ]);
+        let health = GrpcHealth::try_new(&[check]).unwrap();
+
+        let runtime = crate::core::runtime::test::init(None);
+        let vars = BTreeMap::from([("token".into(), "secret".into())]);
+        health.update(0, &runtime, &vars).await;
+
+        mock.assert();
+        assert!(health.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_upstream() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST);
+            then.status(200).header("grpc-status", "14");
+        });
+
+        let health = GrpcHealth::try_new(&[
+            health_check(server.base_url(), true),
+            health_check("http://localhost:1".into(), false),
+        ])
+        .unwrap();
+
+        let runtime = crate::core::runtime::test::init(None);
+        health.update(0, &runtime, &BTreeMap::new()).await;
+
+        assert!(!health.is_ready());
+        assert_eq!(health.upstreams()[0].status, HealthStatus::Unreachable);
+    }
+
+    #[tokio::test]
+    async fn test_optional_upstream() {
+        let health =
+            GrpcHealth::try_new(&[health_check("http://localhost:1".into(), false)]).unwrap();
+
+        assert!(health.is_ready());
+    }
+}
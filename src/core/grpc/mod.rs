@@ -1,8 +1,14 @@
+pub mod compression;
 pub mod data_loader;
 pub mod data_loader_request;
+pub mod health;
 pub mod protobuf;
+pub mod protocol;
 pub mod request;
 pub mod request_template;
+pub mod service;
+pub mod status;
+pub mod stream;
 
 pub use data_loader_request::DataLoaderRequest;
 pub use request_template::RequestTemplate;
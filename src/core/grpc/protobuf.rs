@@ -1,4 +1,6 @@
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 
 use anyhow::{anyhow, bail, Context, Result};
 use async_graphql::Value;
@@ -11,6 +13,9 @@ use prost_reflect::{
 };
 use serde_json::Deserializer;
 
+use super::compression::{compress, decompress, GrpcCompression};
+use super::protocol::GrpcProtocol;
+use super::stream::FrameDecoder;
 use crate::core::blueprint::GrpcMethod;
 
 fn to_message(descriptor: &MessageDescriptor, input: &str) -> Result<DynamicMessage> {
@@ -27,14 +32,22 @@ fn to_message(descriptor: &MessageDescriptor, input: &str) -> Result<DynamicMess
     Ok(message)
 }
 
-fn message_to_bytes(message: DynamicMessage) -> Result<Vec<u8>> {
-    let mut buf: Vec<u8> = Vec::with_capacity(message.encoded_len() + 5);
+/// Wraps a message with the Length-Prefixed Message Framing, compressing it
+/// with the given algorithm.
+pub fn to_frame(payload: Vec<u8>, compression: GrpcCompression) -> Result<Vec<u8>> {
+    let (flag, payload) = if compression != GrpcCompression::Identity {
+        (1, compress(compression, &payload)?)
+    } else {
+        (0, payload)
+    };
+
+    let mut buf: Vec<u8> = Vec::with_capacity(payload.len() + 5);
     // set compression flag
-    buf.put_u8(0);
+    buf.put_u8(flag);
     // next 4 bytes should encode message length
-    buf.put_u32(message.encoded_len() as u32);
-    // encode the message itself
-    message.encode(&mut buf)?;
+    buf.put_u32(payload.len() as u32);
+    // the message itself
+    buf.put_slice(&payload);
 
     Ok(buf)
 }
@@ -63,9 +76,12 @@ pub fn get_field_value_as_str(message: &DynamicMessage, field_name: &str) -> Res
     Ok(protobuf_value_as_str(&field))
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ProtobufSet {
-    descriptor_pool: DescriptorPool,
+    descriptor_pool: Arc<RwLock<DescriptorPool>>,
+    /// Incremented whenever the descriptors are swapped, so that the
+    /// operations resolve their method again.
+    generation: Arc<AtomicU64>,
 }
 
 // TODO: support for reflection
@@ -75,18 +91,52 @@ impl ProtobufSet {
     // either from file or server reflection
     pub fn from_proto_file(file_descriptor_set: FileDescriptorSet) -> Result<Self> {
         let descriptor_pool = DescriptorPool::from_file_descriptor_set(file_descriptor_set)?;
-        Ok(Self { descriptor_pool })
+        Ok(Self {
+            descriptor_pool: Arc::new(RwLock::new(descriptor_pool)),
+            generation: Default::default(),
+        })
+    }
+
+    fn descriptor_pool(&self) -> DescriptorPool {
+        match self.descriptor_pool.read() {
+            Ok(pool) => pool.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Replaces the descriptors with the ones of `other`, the operations
+    /// found in this set resolve their method from them on their next use.
+    pub fn swap(&self, other: &ProtobufSet) {
+        let descriptor_pool = other.descriptor_pool();
+
+        match self.descriptor_pool.write() {
+            Ok(mut pool) => *pool = descriptor_pool,
+            Err(poisoned) => *poisoned.into_inner() = descriptor_pool,
+        }
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// The method as defined by the current descriptors, if they still
+    /// define it.
+    fn resolve(&self, method: &MethodDescriptor) -> Option<MethodDescriptor> {
+        self.descriptor_pool()
+            .get_service_by_name(method.parent_service().full_name())
+            .and_then(|service| service.methods().find(|m| m.name() == method.name()))
     }
 
     pub fn find_service(&self, grpc_method: &GrpcMethod) -> Result<ProtobufService> {
         let service_name = format!("{}.{}", grpc_method.package, grpc_method.service);
 
         let service_descriptor = self
-            .descriptor_pool
+            .descriptor_pool()
             .get_service_by_name(&service_name)
             .with_context(|| format!("Couldn't find definitions for service {service_name}"))?;
 
-        Ok(ProtobufService { service_descriptor })
+        Ok(ProtobufService { set: self.clone(), service_descriptor })
     }
 }
 
@@ -107,6 +157,7 @@ impl ProtobufMessage {
 
 #[derive(Debug)]
 pub struct ProtobufService {
+    set: ProtobufSet,
     service_descriptor: ServiceDescriptor,
 }
 
@@ -118,18 +169,19 @@ impl ProtobufService {
             .find(|method| method.name() == grpc_method.name)
             .with_context(|| format!("Couldn't find method {}", grpc_method.name))?;
 
-        let input_type = method.input();
-        let output_type = method.output();
-
-        Ok(ProtobufOperation::new(method, input_type, output_type))
+        Ok(ProtobufOperation::new(self.set.clone(), method))
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ProtobufOperation {
-    pub method: MethodDescriptor,
-    pub input_type: MessageDescriptor,
-    pub output_type: MessageDescriptor,
+    set: ProtobufSet,
+    method: MethodDescriptor,
+    /// The method as defined by the descriptors of the set, along with the
+    /// generation of the descriptors it was resolved from.
+    resolved: Arc<RwLock<(u64, MethodDescriptor)>>,
+    pub compression: GrpcCompression,
+    pub protocol: GrpcProtocol,
     serialize_options: SerializeOptions,
 }
 
@@ -137,26 +189,109 @@ impl Eq for ProtobufOperation {}
 
 impl PartialEq for ProtobufOperation {
     fn eq(&self, other: &Self) -> bool {
-        self.method.eq(&other.method)
-            && self.input_type.eq(&other.input_type)
-            && self.output_type.eq(&other.output_type)
+        self.method().eq(&other.method())
     }
 }
 
-// TODO: support compression
 impl ProtobufOperation {
-    pub fn new(
-        method: MethodDescriptor,
-        input_type: MessageDescriptor,
-        output_type: MessageDescriptor,
-    ) -> Self {
+    pub fn new(set: ProtobufSet, method: MethodDescriptor) -> Self {
+        let resolved = Arc::new(RwLock::new((set.generation(), method.clone())));
+
         Self {
+            set,
             method,
-            input_type,
-            output_type,
+            resolved,
+            compression: GrpcCompression::default(),
+            protocol: GrpcProtocol::default(),
             serialize_options: SerializeOptions::default().skip_default_fields(false),
         }
     }
+
+    /// The set the descriptors of the operation are resolved from.
+    pub fn protobuf_set(&self) -> &ProtobufSet {
+        &self.set
+    }
+
+    /// The method as defined by the current descriptors of the set. Methods
+    /// missing from new descriptors keep their current definition.
+    pub fn method(&self) -> MethodDescriptor {
+        let generation = self.set.generation();
+        {
+            let resolved = match self.resolved.read() {
+                Ok(resolved) => resolved,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            if resolved.0 == generation {
+                return resolved.1.clone();
+            }
+        }
+
+        let mut resolved = match self.resolved.write() {
+            Ok(resolved) => resolved,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if resolved.0 != generation {
+            let method = self
+                .set
+                .resolve(&resolved.1)
+                .unwrap_or_else(|| resolved.1.clone());
+            *resolved = (generation, method);
+        }
+
+        resolved.1.clone()
+    }
+
+    pub fn input_type(&self) -> MessageDescriptor {
+        self.method().input()
+    }
+
+    pub fn output_type(&self) -> MessageDescriptor {
+        self.method().output()
+    }
+
+    /// Compresses the request messages with the given algorithm.
+    pub fn with_compression(mut self, compression: GrpcCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Calls the upstream with the given wire protocol.
+    pub fn with_protocol(mut self, protocol: GrpcProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        self.protocol.content_type(self.is_server_streaming())
+    }
+
+    /// Header naming the algorithm the messages of a response are compressed
+    /// with.
+    pub fn encoding_header(&self) -> &'static str {
+        self.protocol.encoding_header(self.is_server_streaming())
+    }
+
+    pub fn accept_encoding_header(&self) -> &'static str {
+        self.protocol
+            .accept_encoding_header(self.is_server_streaming())
+    }
+
+    /// Encodes a request message as expected by the protocol.
+    fn message_to_bytes(&self, message: DynamicMessage) -> Result<Vec<u8>> {
+        let payload = if self.protocol.is_json() {
+            serde_json::to_vec(&message)?
+        } else {
+            message.encode_to_vec()
+        };
+
+        if self.protocol.is_enveloped(self.is_server_streaming()) {
+            to_frame(payload, self.compression)
+        } else {
+            compress(self.compression, &payload)
+        }
+    }
+
     pub fn name(&self) -> &str {
         self.method.name()
     }
@@ -166,9 +301,9 @@ impl ProtobufOperation {
     }
 
     pub fn convert_input(&self, input: &str) -> Result<Vec<u8>> {
-        let message = to_message(&self.input_type, input)?;
+        let message = to_message(&self.input_type(), input)?;
 
-        message_to_bytes(message)
+        self.message_to_bytes(message)
     }
 
     pub fn convert_multiple_inputs<'a>(
@@ -177,8 +312,8 @@ impl ProtobufOperation {
         id: &str,
     ) -> Result<(Vec<u8>, Vec<String>)> {
         // Find the field of list type that should hold child messages
-        let field_descriptor = self
-            .input_type
+        let input_type = self.input_type();
+        let field_descriptor = input_type
             .fields()
             .find(|field| field.is_list())
             .ok_or(anyhow!("Unable to find list field on type"))?;
@@ -186,7 +321,7 @@ impl ProtobufOperation {
         let child_message_descriptor = field_kind
             .as_message()
             .ok_or(anyhow!("Couldn't resolve message"))?;
-        let mut message = DynamicMessage::new(self.input_type.clone());
+        let mut message = DynamicMessage::new(input_type.clone());
 
         let child_messages = child_inputs
             .map(|input| to_message(child_message_descriptor, input))
@@ -207,7 +342,11 @@ impl ProtobufOperation {
             ),
         );
 
-        message_to_bytes(message).map(|result| (result, ids))
+        self.message_to_bytes(message).map(|result| (result, ids))
+    }
+
+    pub fn is_server_streaming(&self) -> bool {
+        self.method.is_server_streaming()
     }
 
     pub fn convert_output<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
@@ -218,13 +357,63 @@ impl ProtobufOperation {
         // see https://www.oreilly.com/library/view/grpc-up-and/9781492058328/ch04.html#:~:text=Length%2DPrefixed%20Message%20Framing
         // 1st byte - compression flag
         // 2-4th bytes - length of the message
-        let message =
-            DynamicMessage::decode(self.output_type.clone(), &bytes[5..]).with_context(|| {
-                format!(
-                    "Failed to parse response for type {}",
-                    self.output_type.full_name()
-                )
-            })?;
+        self.decode_message(&bytes[5..])
+    }
+
+    /// Converts a unary response whose message may be compressed with the
+    /// `grpc-encoding` of the response.
+    pub fn convert_encoded_output<T: serde::de::DeserializeOwned>(
+        &self,
+        bytes: &[u8],
+        encoding: GrpcCompression,
+    ) -> Result<T> {
+        if !self.protocol.is_enveloped(false) {
+            return match encoding {
+                GrpcCompression::Identity => self.decode_message(bytes),
+                _ => self.decode_message(&decompress(encoding, bytes)?),
+            };
+        }
+
+        if bytes.first() != Some(&1) {
+            return self.convert_output(bytes);
+        }
+
+        let frame = FrameDecoder::decode_all(bytes, encoding)?
+            .into_iter()
+            .next()
+            .context("Empty response")?;
+        self.decode_message(&frame)
+    }
+
+    /// Converts every message of a server-streaming response, in the order
+    /// they were sent.
+    pub fn convert_output_stream<T: serde::de::DeserializeOwned>(
+        &self,
+        bytes: &[u8],
+        encoding: GrpcCompression,
+    ) -> Result<Vec<T>> {
+        FrameDecoder::decode_all(bytes, encoding)?
+            .iter()
+            .map(|frame| self.decode_message(frame))
+            .collect()
+    }
+
+    /// Converts a single message without its Length-Prefixed Message Framing.
+    pub fn decode_message<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        let output_type = self.output_type();
+        let message = if self.protocol.is_json() {
+            let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+            DynamicMessage::deserialize(output_type.clone(), &mut deserializer)
+                .map_err(anyhow::Error::from)
+        } else {
+            DynamicMessage::decode(output_type.clone(), bytes).map_err(anyhow::Error::from)
+        }
+        .with_context(|| {
+            format!(
+                "Failed to parse response for type {}",
+                output_type.full_name()
+            )
+        })?;
 
         let mut serializer = serde_json::Serializer::new(vec![]);
         message.serialize_with_options(&mut serializer, &self.serialize_options)?;
@@ -233,7 +422,7 @@ impl ProtobufOperation {
     }
 
     pub fn find_message(&self, name: &str) -> Option<ProtobufMessage> {
-        let message_descriptor = self.method.parent_pool().get_message_by_name(name)?;
+        let message_descriptor = self.set.descriptor_pool().get_message_by_name(name)?;
 
         Some(ProtobufMessage { message_descriptor })
     }
@@ -281,6 +470,7 @@ pub mod tests {
             headers: None,
             meta: None,
             proto_paths: link_config.proto_paths,
+            refresh_interval: None,
         }]);
 
         let method = GrpcMethod { package: id, service: "a".to_owned(), name: "b".to_owned() };
@@ -289,7 +479,7 @@ pub mod tests {
             "foo".to_string(),
             Type::default().fields(vec![(
                 "bar",
-                Field::default().resolvers(Resolver::Grpc(grpc).into()),
+                Field::default().resolvers(Resolver::Grpc(Box::new(grpc)).into()),
             )]),
         );
         Ok(reader
@@ -408,6 +598,38 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn news_proto_file_gzip() -> Result<()> {
+        let grpc_method = GrpcMethod::try_from("news.NewsService.GetNews").unwrap();
+
+        let file = ProtobufSet::from_proto_file(get_proto_file(protobuf::NEWS).await?)?;
+        let service = file.find_service(&grpc_method)?;
+        let operation = service
+            .find_operation(&grpc_method)?
+            .with_compression(GrpcCompression::Gzip);
+
+        let input = operation.convert_input(r#"{ "id": 1 }"#)?;
+
+        assert_eq!(input[0], 1);
+        assert_eq!(
+            crate::core::grpc::compression::decompress(GrpcCompression::Gzip, &input[5..])?,
+            b"\x08\x01"
+        );
+
+        let message = b"\x08\x01\x12\x06Note 1";
+        let compressed = compress(GrpcCompression::Gzip, message)?;
+        let mut output = vec![1];
+        output.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        output.extend_from_slice(&compressed);
+
+        let parsed = operation
+            .convert_encoded_output::<serde_json::Value>(&output, GrpcCompression::Gzip)?;
+
+        assert_eq!(parsed["title"], "Note 1");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn news_proto_file_with_proto_paths() -> Result<()> {
         let grpc_method = GrpcMethod::try_from("news.NewsService.GetNews").unwrap();
@@ -598,6 +820,37 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn well_known_types_proto_file() -> Result<()> {
+        let grpc_method = GrpcMethod::try_from("movies.Example.SearchMoviesByCast").unwrap();
+
+        let file = ProtobufSet::from_proto_file(get_proto_file(protobuf::MOVIES).await?)?;
+        let service = file.find_service(&grpc_method)?;
+        let operation = service.find_operation(&grpc_method)?;
+
+        // wrappers are represented by their value
+        let input = operation.convert_input(r#"{ "castName": "Tom" }"#)?;
+        assert_eq!(input, b"\0\0\0\0\x07\n\x05\n\x03Tom");
+
+        let movie = json!({
+            "name": "Inception",
+            "year": 2010,
+            "time": "2010-07-16T10:00:00Z",
+            "duration": "8880s"
+        });
+        let message = DynamicMessage::deserialize(operation.output_type(), movie)?;
+        let output = to_frame(message.encode_to_vec(), GrpcCompression::Identity)?;
+
+        let parsed = operation.convert_output::<serde_json::Value>(&output)?;
+
+        assert_eq!(parsed["year"], json!(2010));
+        assert_eq!(parsed["time"], json!("2010-07-16T10:00:00Z"));
+        assert_eq!(parsed["duration"], json!("8880s"));
+        assert_eq!(parsed["subMovie"], json!(null));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn scalars_proto_file() -> Result<()> {
         let grpc_method = GrpcMethod::try_from("scalars.Example.Get").unwrap();
@@ -0,0 +1,407 @@
+use anyhow::Result;
+use async_graphql_value::{ConstValue, Name};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use hyper::body::Bytes;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use tonic::Code;
+
+use super::compression::{GRPC_ACCEPT_ENCODING, GRPC_ENCODING};
+use super::protobuf::ProtobufOperation;
+use super::request::GRPC_STATUS;
+use super::status::decode_detail;
+use super::stream::FrameDecoder;
+pub use crate::core::config::GrpcProtocol;
+use crate::core::http::Response;
+use crate::core::ir::Error;
+
+pub static CONNECT_PROTOCOL_VERSION: &str = "connect-protocol-version";
+static CONNECT_CONTENT_ENCODING: &str = "connect-content-encoding";
+static CONNECT_ACCEPT_ENCODING: &str = "connect-accept-encoding";
+pub static X_GRPC_WEB: &str = "x-grpc-web";
+
+impl GrpcProtocol {
+    /// Only native gRPC needs HTTP/2, every other protocol works over
+    /// HTTP/1.1.
+    pub fn requires_http2(&self) -> bool {
+        matches!(self, GrpcProtocol::Grpc)
+    }
+
+    pub fn is_connect(&self) -> bool {
+        matches!(self, GrpcProtocol::Connect | GrpcProtocol::ConnectJson)
+    }
+
+    pub fn is_json(&self) -> bool {
+        matches!(self, GrpcProtocol::ConnectJson)
+    }
+
+    /// Whether messages are sent with the Length-Prefixed Message Framing.
+    /// Connect only frames the messages of streaming calls.
+    pub fn is_enveloped(&self, streaming: bool) -> bool {
+        !self.is_connect() || streaming
+    }
+
+    pub fn content_type(&self, streaming: bool) -> &'static str {
+        match (self, streaming) {
+            (GrpcProtocol::Grpc, _) => "application/grpc",
+            (GrpcProtocol::GrpcWeb, _) => "application/grpc-web+proto",
+            (GrpcProtocol::Connect, false) => "application/proto",
+            (GrpcProtocol::Connect, true) => "application/connect+proto",
+            (GrpcProtocol::ConnectJson, false) => "application/json",
+            (GrpcProtocol::ConnectJson, true) => "application/connect+json",
+        }
+    }
+
+    /// Header naming the algorithm messages are compressed with.
+    pub fn encoding_header(&self, streaming: bool) -> &'static str {
+        match (self.is_connect(), streaming) {
+            (false, _) => GRPC_ENCODING,
+            (true, false) => CONTENT_ENCODING.as_str(),
+            (true, true) => CONNECT_CONTENT_ENCODING,
+        }
+    }
+
+    /// Header advertising the algorithms responses can be compressed with.
+    pub fn accept_encoding_header(&self, streaming: bool) -> &'static str {
+        match (self.is_connect(), streaming) {
+            (false, _) => GRPC_ACCEPT_ENCODING,
+            (true, false) => ACCEPT_ENCODING.as_str(),
+            (true, true) => CONNECT_ACCEPT_ENCODING,
+        }
+    }
+}
+
+/// Converts a response of a gRPC-Web or Connect upstream into the shape of a
+/// native gRPC response, i.e. the messages in the body and the status in the
+/// headers, or fails with the error the upstream returned.
+pub fn into_grpc_response(
+    response: Response<Bytes>,
+    operation: &ProtobufOperation,
+) -> Result<Response<Bytes>> {
+    let streaming = operation.is_server_streaming();
+    let protocol = operation.protocol;
+
+    if protocol.is_connect() && !response.status.is_success() {
+        return Err(connect_error(response.status, &response.body, operation));
+    }
+
+    if !protocol.is_enveloped(streaming) || protocol == GrpcProtocol::Grpc {
+        return Ok(response);
+    }
+
+    let encoding = super::compression::response_encoding(
+        &response.headers,
+        protocol.encoding_header(streaming),
+    )?;
+    let (body, trailer) = FrameDecoder::split_trailer(&response.body, encoding)?;
+    let mut response = response.body(body);
+
+    if let Some(trailer) = trailer {
+        match protocol {
+            GrpcProtocol::GrpcWeb => response.headers.extend(parse_web_trailers(&trailer)),
+            _ => {
+                if let Some(error) = connect_end_stream_error(&trailer, operation) {
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    Ok(response)
+}
+
+/// Returns the error carried by the trailer a gRPC-Web or Connect stream
+/// ends with, if any.
+pub fn trailer_error(
+    trailer: &[u8],
+    response: &Response<Bytes>,
+    operation: &ProtobufOperation,
+) -> Option<anyhow::Error> {
+    match operation.protocol {
+        GrpcProtocol::Grpc => None,
+        GrpcProtocol::GrpcWeb => {
+            let mut headers = response.headers.clone();
+            headers.extend(parse_web_trailers(trailer));
+            let status = headers
+                .get(GRPC_STATUS)
+                .and_then(|status| status.to_str().ok())?;
+
+            if status == "0" {
+                return None;
+            }
+
+            Some(response.clone().headers(headers).to_grpc_error(operation))
+        }
+        GrpcProtocol::Connect | GrpcProtocol::ConnectJson => {
+            connect_end_stream_error(trailer, operation)
+        }
+    }
+}
+
+/// Parses the HTTP/1-style header block of a gRPC-Web trailer frame.
+pub fn parse_web_trailers(trailer: &[u8]) -> HeaderMap {
+    String::from_utf8_lossy(trailer)
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = HeaderName::from_bytes(name.trim().to_lowercase().as_bytes()).ok()?;
+            let value = HeaderValue::from_str(value.trim()).ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct ConnectEndStream {
+    error: Option<ConnectError>,
+}
+
+#[derive(Deserialize)]
+struct ConnectError {
+    code: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    details: Vec<ConnectErrorDetail>,
+}
+
+#[derive(Deserialize)]
+struct ConnectErrorDetail {
+    #[serde(rename = "type")]
+    type_name: String,
+    value: String,
+}
+
+fn connect_end_stream_error(
+    trailer: &[u8],
+    operation: &ProtobufOperation,
+) -> Option<anyhow::Error> {
+    match serde_json::from_slice::<ConnectEndStream>(trailer) {
+        Ok(end_stream) => Some(end_stream.error?.into_error(operation)),
+        Err(err) => Some(anyhow::anyhow!("Invalid Connect end of stream: {err}")),
+    }
+}
+
+/// Builds the error of a failed Connect call from its JSON body, falling back
+/// to the code implied by the HTTP status.
+pub fn connect_error(
+    status: StatusCode,
+    body: &[u8],
+    operation: &ProtobufOperation,
+) -> anyhow::Error {
+    match serde_json::from_slice::<ConnectError>(body) {
+        Ok(error) => error.into_error(operation),
+        Err(_) => ConnectError {
+            code: http_status_to_connect_code(status).to_owned(),
+            message: String::from_utf8_lossy(body).into_owned(),
+            details: vec![],
+        }
+        .into_error(operation),
+    }
+}
+
+impl ConnectError {
+    fn into_error(self, operation: &ProtobufOperation) -> anyhow::Error {
+        let code = connect_code(&self.code);
+        let details = self
+            .details
+            .iter()
+            .filter_map(|detail| {
+                let value = STANDARD_NO_PAD
+                    .decode(detail.value.trim_end_matches('='))
+                    .inspect_err(|_| {
+                        tracing::error!("Error while decoding detail: {}", detail.type_name)
+                    })
+                    .ok()?;
+                decode_detail(operation, &detail.type_name, &value)
+            })
+            .collect();
+
+        let mut obj: IndexMap<Name, ConstValue> = IndexMap::new();
+        obj.insert(Name::new("code"), (code as i32).into());
+        obj.insert(Name::new("message"), self.message.clone().into());
+        obj.insert(Name::new("details"), ConstValue::List(details));
+
+        anyhow::Error::new(Error::GRPC {
+            grpc_code: code as i32,
+            grpc_description: code.description().to_owned(),
+            grpc_status_message: self.message,
+            grpc_status_details: ConstValue::Object(obj),
+        })
+    }
+}
+
+fn connect_code(code: &str) -> Code {
+    match code {
+        "canceled" => Code::Cancelled,
+        "invalid_argument" => Code::InvalidArgument,
+        "deadline_exceeded" => Code::DeadlineExceeded,
+        "not_found" => Code::NotFound,
+        "already_exists" => Code::AlreadyExists,
+        "permission_denied" => Code::PermissionDenied,
+        "resource_exhausted" => Code::ResourceExhausted,
+        "failed_precondition" => Code::FailedPrecondition,
+        "aborted" => Code::Aborted,
+        "out_of_range" => Code::OutOfRange,
+        "unimplemented" => Code::Unimplemented,
+        "internal" => Code::Internal,
+        "unavailable" => Code::Unavailable,
+        "data_loss" => Code::DataLoss,
+        "unauthenticated" => Code::Unauthenticated,
+        _ => Code::Unknown,
+    }
+}
+
+/// The mapping used by Connect clients when the body isn't a Connect error.
+fn http_status_to_connect_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "internal",
+        StatusCode::UNAUTHORIZED => "unauthenticated",
+        StatusCode::FORBIDDEN => "permission_denied",
+        StatusCode::NOT_FOUND => "unimplemented",
+        StatusCode::TOO_MANY_REQUESTS
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => "unavailable",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, StatusCode};
+    use hyper::body::Bytes;
+    use prost::Message;
+    use serde_json::json;
+    use tailcall_fixtures::protobuf;
+    use tonic::Code;
+
+    use super::{into_grpc_response, parse_web_trailers, GrpcProtocol};
+    use crate::core::blueprint::GrpcMethod;
+    use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
+    use crate::core::http::Response;
+    use crate::core::ir::Error;
+
+    fn operation(protocol: GrpcProtocol) -> ProtobufOperation {
+        let file_descriptor_set = protox::compile([protobuf::GREETINGS], [protobuf::SELF]).unwrap();
+        let grpc_method = GrpcMethod::try_from("greetings.Greeter.SayHello").unwrap();
+        let file = ProtobufSet::from_proto_file(file_descriptor_set).unwrap();
+        let service = file.find_service(&grpc_method).unwrap();
+        service
+            .find_operation(&grpc_method)
+            .unwrap()
+            .with_protocol(protocol)
+    }
+
+    fn frame(flag: u8, message: &[u8]) -> Vec<u8> {
+        let mut frame = vec![flag];
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(message);
+        frame
+    }
+
+    fn response(status: StatusCode, body: Vec<u8>) -> Response<Bytes> {
+        Response { status, headers: HeaderMap::new(), body: Bytes::from(body) }
+    }
+
+    #[test]
+    fn test_parse_web_trailers() {
+        let headers = parse_web_trailers(b"grpc-status: 5\r\nGrpc-Message: not found\r\n");
+
+        assert_eq!(headers["grpc-status"], "5");
+        assert_eq!(headers["grpc-message"], "not found");
+    }
+
+    #[test]
+    fn test_grpc_web_trailers_move_to_headers() -> anyhow::Result<()> {
+        let operation = operation(GrpcProtocol::GrpcWeb);
+        let body = [frame(0, b"\n\x05hello"), frame(0x80, b"grpc-status: 0\r\n")].concat();
+
+        let response = into_grpc_response(response(StatusCode::OK, body), &operation)?;
+
+        assert_eq!(response.headers["grpc-status"], "0");
+        assert_eq!(
+            response.to_grpc_value(&operation)?.body.into_json()?,
+            json!({ "message": "hello" })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_connect_unary_json() -> anyhow::Result<()> {
+        let operation = operation(GrpcProtocol::ConnectJson);
+        let body = br#"{"message": "hello"}"#.to_vec();
+
+        let response = into_grpc_response(response(StatusCode::OK, body), &operation)?;
+
+        assert_eq!(
+            response.to_grpc_value(&operation)?.body.into_json()?,
+            json!({ "message": "hello" })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_connect_error() {
+        let operation = operation(GrpcProtocol::Connect);
+        let error_info = tonic_types::pb::ErrorInfo {
+            reason: "MISSING".to_owned(),
+            domain: "example.com".to_owned(),
+            metadata: Default::default(),
+        };
+        let body = json!({
+            "code": "not_found",
+            "message": "no greeting",
+            "details": [{
+                "type": "google.rpc.ErrorInfo",
+                "value": base64::Engine::encode(
+                    &base64::engine::general_purpose::STANDARD_NO_PAD,
+                    error_info.encode_to_vec()
+                ),
+            }]
+        });
+
+        let error = into_grpc_response(
+            response(StatusCode::NOT_FOUND, body.to_string().into_bytes()),
+            &operation,
+        )
+        .unwrap_err();
+
+        match error.downcast_ref::<Error>() {
+            Some(Error::GRPC { grpc_code, grpc_status_message, grpc_status_details, .. }) => {
+                assert_eq!(*grpc_code, Code::NotFound as i32);
+                assert_eq!(grpc_status_message, "no greeting");
+                assert_eq!(
+                    grpc_status_details.clone().into_json().unwrap(),
+                    json!({
+                        "code": 5,
+                        "message": "no greeting",
+                        "details": [{ "reason": "MISSING", "domain": "example.com" }]
+                    })
+                );
+            }
+            _ => panic!("Expected GRPCError"),
+        }
+    }
+
+    #[test]
+    fn test_connect_error_without_body() {
+        let operation = operation(GrpcProtocol::Connect);
+
+        let error = into_grpc_response(
+            response(StatusCode::SERVICE_UNAVAILABLE, vec![]),
+            &operation,
+        )
+        .unwrap_err();
+
+        match error.downcast_ref::<Error>() {
+            Some(Error::GRPC { grpc_code, .. }) => {
+                assert_eq!(*grpc_code, Code::Unavailable as i32)
+            }
+            _ => panic!("Expected GRPCError"),
+        }
+    }
+}
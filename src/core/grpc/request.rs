@@ -4,10 +4,12 @@ use reqwest::Request;
 use url::Url;
 
 use super::protobuf::ProtobufOperation;
-use crate::core::http::Response;
+use super::protocol::into_grpc_response;
+use crate::core::http::{OAuth2, Response};
 use crate::core::runtime::TargetRuntime;
 
 pub static GRPC_STATUS: &str = "grpc-status";
+pub static GRPC_TIMEOUT: &str = "grpc-timeout";
 
 pub fn create_grpc_request(url: Url, headers: HeaderMap, body: Vec<u8>) -> Request {
     let mut req = Request::new(Method::POST, url);
@@ -21,22 +23,29 @@ pub async fn execute_grpc_request(
     runtime: &TargetRuntime,
     operation: &ProtobufOperation,
     request: Request,
+    oauth2: Option<&OAuth2>,
 ) -> Result<Response<async_graphql::Value>> {
-    let response = runtime.http2_only.execute(request).await?;
+    let client = if operation.protocol.requires_http2() {
+        &runtime.http2_only
+    } else {
+        &runtime.http
+    };
+    let response = match oauth2 {
+        Some(oauth2) => oauth2.execute(runtime, client.as_ref(), request).await?,
+        None => client.execute(request).await?,
+    };
+    let response = into_grpc_response(response, operation)?;
 
     let grpc_status = response
         .headers
         .get(GRPC_STATUS)
         .and_then(|header_value| header_value.to_str().ok());
 
-    if response.status.is_success() {
-        return if grpc_status.is_none() || grpc_status == Some("0") {
-            response.to_grpc_value(operation)
-        } else {
-            Err(response.to_grpc_error(operation))
-        };
+    match grpc_status {
+        Some(status) if status != "0" => Err(response.to_grpc_error(operation)),
+        _ if response.status.is_success() => response.to_grpc_value(operation),
+        _ => bail!("Failed to execute request"),
     }
-    bail!("Failed to execute request");
 }
 
 #[cfg(test)]
@@ -65,6 +74,7 @@ mod tests {
         SuccessWithoutGrpcStatus,
         SuccessWithOkGrpcStatus,
         SuccessWithErrorGrpcStatus,
+        ErrorWithGrpcStatus,
         Error,
     }
 
@@ -94,6 +104,15 @@ mod tests {
                     status.add_header(&mut headers)?;
                     Ok(Response { status: StatusCode::OK, headers, body: Bytes::default() })
                 }
+                TestScenario::ErrorWithGrpcStatus => {
+                    let status = Status::unavailable("upstream is down");
+                    status.add_header(&mut headers)?;
+                    Ok(Response {
+                        status: StatusCode::SERVICE_UNAVAILABLE,
+                        headers,
+                        body: Bytes::default(),
+                    })
+                }
                 TestScenario::Error => Ok(Response {
                     status: StatusCode::NOT_FOUND,
                     headers,
@@ -124,7 +143,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithoutGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None).await;
 
         assert!(
             result.is_ok(),
@@ -138,7 +157,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithOkGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None).await;
 
         assert!(
             result.is_ok(),
@@ -152,7 +171,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithErrorGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None).await;
 
         assert!(
             result.is_err(),
@@ -188,12 +207,29 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_grpc_request_error_with_grpc_status() -> Result<()> {
+        let test_http = TestHttp { scenario: TestScenario::ErrorWithGrpcStatus };
+        let (runtime, operation, request) = prepare_args(test_http).await?;
+
+        let result = execute_grpc_request(&runtime, &operation, request, None).await;
+
+        match result.unwrap_err().downcast_ref::<Error>() {
+            Some(Error::GRPC { grpc_code, grpc_status_message, .. }) => {
+                assert_eq!(*grpc_code, Code::Unavailable as i32);
+                assert_eq!(*grpc_status_message, "upstream is down");
+            }
+            _ => panic!("Expected GRPCError"),
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_grpc_request_error() -> Result<()> {
         let test_http = TestHttp { scenario: TestScenario::Error };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, request, None).await;
 
         assert!(result.is_err(), "Expected error");
         assert_eq!(result.unwrap_err().to_string(), "Failed to execute request");
@@ -1,4 +1,6 @@
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use derive_setters::Setters;
@@ -6,17 +8,18 @@ use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use tailcall_hasher::TailcallHasher;
 use url::Url;
 
-use super::request::create_grpc_request;
+use super::compression::{GrpcCompression, ACCEPTED_ENCODINGS};
+use super::protocol::{GrpcProtocol, CONNECT_PROTOCOL_VERSION, X_GRPC_WEB};
+use super::request::{create_grpc_request, GRPC_TIMEOUT};
 use crate::core::config::GraphQLOperationType;
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::has_headers::HasHeaders;
 use crate::core::helpers::headers::MustacheHeaders;
+use crate::core::http::OAuth2;
 use crate::core::ir::model::{CacheKey, IoId};
 use crate::core::mustache::Mustache;
 use crate::core::path::PathString;
 
-static GRPC_MIME_TYPE: HeaderValue = HeaderValue::from_static("application/grpc");
-
 #[derive(Setters, Debug, Clone)]
 pub struct RequestTemplate {
     pub url: Mustache,
@@ -24,6 +27,9 @@ pub struct RequestTemplate {
     pub body: Option<RequestBody>,
     pub operation: ProtobufOperation,
     pub operation_type: GraphQLOperationType,
+    pub oauth2: Option<Arc<OAuth2>>,
+    /// Deadline of the call, sent to the upstream in `grpc-timeout`.
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Setters)]
@@ -48,6 +54,7 @@ pub struct RenderedRequestTemplate {
     pub headers: HeaderMap,
     pub body: String,
     pub operation: ProtobufOperation,
+    pub timeout: Option<Duration>,
 }
 
 impl Hash for RenderedRequestTemplate {
@@ -67,7 +74,31 @@ impl RequestTemplate {
     fn create_headers<C: PathString>(&self, ctx: &C) -> HeaderMap {
         let mut header_map = HeaderMap::new();
 
-        header_map.insert(CONTENT_TYPE, GRPC_MIME_TYPE.to_owned());
+        header_map.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(self.operation.content_type()),
+        );
+        // only advertise compression when it's enabled, so that requests to
+        // upstreams without it remain unchanged
+        if self.operation.compression != GrpcCompression::Identity {
+            header_map.insert(
+                self.operation.accept_encoding_header(),
+                HeaderValue::from_static(ACCEPTED_ENCODINGS),
+            );
+            header_map.insert(
+                self.operation.encoding_header(),
+                HeaderValue::from_static(self.operation.compression.as_str()),
+            );
+        }
+        match self.operation.protocol {
+            GrpcProtocol::Grpc => {}
+            GrpcProtocol::GrpcWeb => {
+                header_map.insert(X_GRPC_WEB, HeaderValue::from_static("1"));
+            }
+            GrpcProtocol::Connect | GrpcProtocol::ConnectJson => {
+                header_map.insert(CONNECT_PROTOCOL_VERSION, HeaderValue::from_static("1"));
+            }
+        }
 
         for (k, v) in &self.headers {
             if let Ok(header_value) = HeaderValue::from_str(&v.render(ctx)) {
@@ -82,7 +113,15 @@ impl RequestTemplate {
         let url = self.create_url(ctx)?;
         let headers = self.render_headers(ctx);
         let body = self.render_body(ctx);
-        Ok(RenderedRequestTemplate { url, headers, body, operation: self.operation.clone() })
+        let rendered = RenderedRequestTemplate {
+            url,
+            headers,
+            body,
+            operation: self.operation.clone(),
+            timeout: None,
+        };
+
+        Ok(rendered.with_timeout(self.timeout))
     }
 
     fn render_body<C: PathString + HasHeaders>(&self, ctx: &C) -> String {
@@ -108,6 +147,20 @@ impl RequestTemplate {
 }
 
 impl RenderedRequestTemplate {
+    /// Shortens the deadline of the call to `timeout` if it's sooner.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        let timeout = match (self.timeout, timeout) {
+            (Some(current), Some(timeout)) => Some(current.min(timeout)),
+            (current, timeout) => current.or(timeout),
+        };
+
+        if let Some(timeout) = timeout {
+            self.headers.insert(GRPC_TIMEOUT, grpc_timeout(timeout));
+        }
+        self.timeout = timeout;
+        self
+    }
+
     pub fn to_request(&self) -> Result<reqwest::Request> {
         Ok(create_grpc_request(
             self.url.clone(),
@@ -117,6 +170,25 @@ impl RenderedRequestTemplate {
     }
 }
 
+/// Encodes the duration in the coarsest unit from milliseconds up, whose value
+/// fits into the 8 digits allowed for `TimeoutValue`.
+/// see https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-HTTP2.md#requests
+fn grpc_timeout(timeout: Duration) -> HeaderValue {
+    const MAX: u128 = 99_999_999;
+
+    let value = [
+        (timeout.as_millis(), "m"),
+        (timeout.as_secs() as u128, "S"),
+        ((timeout.as_secs() / 60) as u128, "M"),
+    ]
+    .into_iter()
+    .find(|(value, _)| *value <= MAX)
+    .map(|(value, unit)| format!("{value}{unit}"))
+    .unwrap_or_else(|| format!("{}H", (timeout.as_secs() / 3600).min(MAX as u64)));
+
+    HeaderValue::from_str(&value).unwrap_or(HeaderValue::from_static("0m"))
+}
+
 impl<Ctx: PathString + HasHeaders> CacheKey<Ctx> for RequestTemplate {
     fn cache_key(&self, ctx: &Ctx) -> Option<IoId> {
         let mut hasher = TailcallHasher::default();
@@ -130,6 +202,7 @@ impl<Ctx: PathString + HasHeaders> CacheKey<Ctx> for RequestTemplate {
 mod tests {
     use std::borrow::Cow;
     use std::collections::HashSet;
+    use std::time::Duration;
 
     use derive_setters::Setters;
     use http::header::{HeaderMap, HeaderName, HeaderValue};
@@ -137,13 +210,14 @@ mod tests {
     use pretty_assertions::assert_eq;
     use tailcall_fixtures::protobuf;
 
-    use super::{RequestBody, RequestTemplate};
+    use super::{grpc_timeout, RequestBody, RequestTemplate};
     use crate::core::blueprint::GrpcMethod;
     use crate::core::config::reader::ConfigReader;
     use crate::core::config::{
         Config, Field, GraphQLOperationType, Grpc, Link, LinkType, Resolver, Type,
     };
     use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
+    use crate::core::grpc::protocol::GrpcProtocol;
     use crate::core::ir::model::CacheKey;
     use crate::core::mustache::Mustache;
 
@@ -161,6 +235,7 @@ mod tests {
             headers: None,
             meta: None,
             proto_paths: None,
+            refresh_interval: None,
         }]);
         let method = GrpcMethod {
             package: id.to_string(),
@@ -172,7 +247,7 @@ mod tests {
             "foo".to_string(),
             Type::default().fields(vec![(
                 "bar",
-                Field::default().resolvers(Resolver::Grpc(grpc).into()),
+                Field::default().resolvers(Resolver::Grpc(Box::new(grpc)).into()),
             )]),
         );
 
@@ -227,6 +302,8 @@ mod tests {
             operation: get_protobuf_op().await,
             body: None,
             operation_type: GraphQLOperationType::Query,
+            oauth2: None,
+            timeout: None,
         };
         let ctx = Context::default();
         let rendered = tmpl.render(&ctx).unwrap();
@@ -253,6 +330,45 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn request_with_connect_json() {
+        let tmpl = RequestTemplate {
+            url: Mustache::parse("http://localhost:3000/"),
+            headers: vec![],
+            operation: get_protobuf_op()
+                .await
+                .with_protocol(GrpcProtocol::ConnectJson),
+            body: Some(RequestBody {
+                mustache: Some(Mustache::parse(r#"{ "name": "test" }"#)),
+                value: Default::default(),
+            }),
+            operation_type: GraphQLOperationType::Query,
+            oauth2: None,
+            timeout: None,
+        };
+        let ctx = Context::default();
+        let rendered = tmpl.render(&ctx).unwrap();
+        let req = rendered.to_request().unwrap();
+
+        assert_eq!(
+            req.headers(),
+            &HeaderMap::from_iter([
+                (
+                    HeaderName::from_static("content-type"),
+                    HeaderValue::from_static("application/json")
+                ),
+                (
+                    HeaderName::from_static("connect-protocol-version"),
+                    HeaderValue::from_static("1")
+                )
+            ])
+        );
+
+        if let Some(body) = req.body() {
+            assert_eq!(body.as_bytes(), Some(br#"{"name":"test"}"#.as_ref()))
+        }
+    }
+
     #[tokio::test]
     async fn request_with_body() {
         let tmpl = RequestTemplate {
@@ -264,6 +380,8 @@ mod tests {
                 value: Default::default(),
             }),
             operation_type: GraphQLOperationType::Query,
+            oauth2: None,
+            timeout: None,
         };
         let ctx = Context::default();
         let rendered = tmpl.render(&ctx).unwrap();
@@ -284,6 +402,8 @@ mod tests {
                 value: Default::default(),
             }),
             operation_type: GraphQLOperationType::Query,
+            oauth2: None,
+            timeout: None,
         }
     }
 
@@ -310,4 +430,37 @@ mod tests {
 
         assert_eq!(arr.len(), tmpl_set.len());
     }
+
+    #[tokio::test]
+    async fn request_with_timeout() {
+        let tmpl = RequestTemplate {
+            url: Mustache::parse("http://localhost:3000/"),
+            headers: vec![],
+            operation: get_protobuf_op().await,
+            body: None,
+            operation_type: GraphQLOperationType::Query,
+            oauth2: None,
+            timeout: Some(Duration::from_millis(1500)),
+        };
+        let ctx = Context::default();
+
+        let rendered = tmpl.render(&ctx).unwrap();
+        assert_eq!(rendered.headers["grpc-timeout"], "1500m");
+
+        // the remaining budget of the request shortens the deadline
+        let rendered = rendered.with_timeout(Some(Duration::from_millis(200)));
+        assert_eq!(rendered.headers["grpc-timeout"], "200m");
+
+        let rendered = rendered.with_timeout(Some(Duration::from_secs(10)));
+        assert_eq!(rendered.headers["grpc-timeout"], "200m");
+    }
+
+    #[test]
+    fn test_grpc_timeout() {
+        assert_eq!(grpc_timeout(Duration::ZERO), "0m");
+        assert_eq!(grpc_timeout(Duration::from_micros(2500)), "2m");
+        assert_eq!(grpc_timeout(Duration::from_secs(100_000_000)), "1666666M");
+        assert_eq!(grpc_timeout(Duration::from_secs(100_000)), "100000S");
+        assert_eq!(grpc_timeout(Duration::from_secs(u64::MAX)), "99999999H");
+    }
 }
@@ -0,0 +1,451 @@
+pub mod proto;
+pub mod reflection;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderValue, Request, Response};
+use hyper::Body;
+use prost::Message;
+use prost_reflect::{
+    DescriptorPool, DeserializeOptions, DynamicMessage, MessageDescriptor, SerializeOptions,
+};
+use serde::Deserialize;
+use tonic::{Code, Status};
+
+use self::proto::{ProtoFile, Rpc};
+use self::reflection::{add_reflection_files, Reflection, SERVICE, V1, V1ALPHA};
+use super::compression::{response_encoding, GrpcCompression, GRPC_ENCODING};
+use super::protobuf::to_frame;
+use super::stream::FrameDecoder;
+use crate::core::app_context::AppContext;
+use crate::core::async_graphql_hyper::{GraphQLRequest, GraphQLRequestLike};
+use crate::core::blueprint::{Blueprint, GrpcService};
+use crate::core::http::RequestContext;
+use crate::core::jit::JITExecutor;
+
+#[derive(Debug)]
+struct Method {
+    rpc: Rpc,
+    input: MessageDescriptor,
+    output: MessageDescriptor,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecutionError {
+    message: String,
+    #[serde(default)]
+    extensions: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecutionResult {
+    #[serde(default)]
+    data: serde_json::Value,
+    #[serde(default)]
+    errors: Vec<ExecutionError>,
+}
+
+/// Serves the schema as a gRPC service, every call is executed as the
+/// GraphQL operation generated for its method.
+#[derive(Debug)]
+pub struct GrpcServer {
+    methods: HashMap<String, Method>,
+    reflection: Reflection,
+}
+
+impl GrpcServer {
+    pub fn try_new(blueprint: &Blueprint, options: &GrpcService) -> Result<Self> {
+        let proto = ProtoFile::new(blueprint, options);
+
+        let mut pool = DescriptorPool::global();
+        pool.add_file_descriptor_proto(proto.file.clone())?;
+        add_reflection_files(&mut pool)?;
+
+        let mut methods = HashMap::new();
+        for rpc in proto.rpcs.iter() {
+            let message = |name: &str| {
+                pool.get_message_by_name(name)
+                    .with_context(|| format!("Couldn't find message {name}"))
+            };
+            let method = Method {
+                input: message(&rpc.input)?,
+                output: message(&rpc.output)?,
+                rpc: rpc.clone(),
+            };
+
+            methods.insert(rpc.path.clone(), method);
+        }
+
+        let services = proto
+            .file
+            .service
+            .iter()
+            .map(|service| format!("{}.{}", options.package, service.name()))
+            .chain([V1ALPHA, V1].map(|package| format!("{package}.{SERVICE}")))
+            .collect();
+
+        Ok(Self { methods, reflection: Reflection::new(pool, services) })
+    }
+
+    /// Checks if the request is a gRPC call in the protobuf format.
+    pub fn is_grpc(req: &Request<Body>) -> bool {
+        matches!(
+            req.headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok()),
+            Some("application/grpc" | "application/grpc+proto")
+        )
+    }
+
+    pub async fn handle(
+        &self,
+        req: Request<Body>,
+        app_ctx: &Arc<AppContext>,
+        req_ctx: Arc<RequestContext>,
+    ) -> Result<Response<Body>> {
+        let path = req.uri().path().to_string();
+        let encoding = match response_encoding(req.headers(), GRPC_ENCODING) {
+            Ok(encoding) => encoding,
+            Err(err) => return status_response(Status::unimplemented(err.to_string())),
+        };
+
+        if let Some(version) = Reflection::version(&path) {
+            let reflection = self.reflection.clone();
+            let (mut sender, body) = Body::channel();
+
+            tokio::spawn(async move {
+                let status = match reflection
+                    .serve(version, req.into_body(), &mut sender, encoding)
+                    .await
+                {
+                    Ok(()) => Status::ok(""),
+                    Err(err) => Status::internal(err.to_string()),
+                };
+
+                if let Ok(trailers) = status_headers(&status) {
+                    let _ = sender.send_trailers(trailers).await;
+                }
+            });
+
+            return Ok(grpc_response(body, HeaderMap::new()));
+        }
+
+        let Some(method) = self.methods.get(&path) else {
+            return status_response(Status::unimplemented(format!("Unknown method {path}")));
+        };
+
+        match execute(method, req, encoding, app_ctx, req_ctx).await {
+            Ok(message) => {
+                let (mut sender, body) = Body::channel();
+                sender
+                    .try_send_data(to_frame(message, GrpcCompression::Identity)?.into())
+                    .map_err(|_| anyhow!("Failed to send the gRPC response"))?;
+                sender
+                    .send_trailers(status_headers(&Status::ok(""))?)
+                    .await?;
+
+                Ok(grpc_response(body, HeaderMap::new()))
+            }
+            Err(status) => status_response(status),
+        }
+    }
+}
+
+/// Executes the operation of the method, returning the encoded response
+/// message.
+async fn execute(
+    method: &Method,
+    req: Request<Body>,
+    encoding: GrpcCompression,
+    app_ctx: &Arc<AppContext>,
+    req_ctx: Arc<RequestContext>,
+) -> std::result::Result<Vec<u8>, Status> {
+    let (parts, body) = req.into_parts();
+    let invalid_argument = |err: anyhow::Error| Status::invalid_argument(err.to_string());
+
+    let bytes = hyper::body::to_bytes(body)
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+    // every method is unary, the body must hold exactly one message
+    let frame = match <[_; 1]>::try_from(
+        FrameDecoder::decode_all(&bytes, encoding).map_err(invalid_argument)?,
+    ) {
+        Ok([frame]) => frame,
+        Err(frames) if frames.is_empty() => {
+            return Err(Status::invalid_argument("Missing request message"))
+        }
+        Err(frames) => {
+            return Err(Status::invalid_argument(format!(
+                "Expected a single request message, received {}",
+                frames.len()
+            )))
+        }
+    };
+    let input = DynamicMessage::decode(method.input.clone(), frame)
+        .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+    let mut serializer = serde_json::Serializer::new(vec![]);
+    input
+        .serialize_with_options(
+            &mut serializer,
+            &SerializeOptions::new().skip_default_fields(false),
+        )
+        .map_err(|err| Status::invalid_argument(err.to_string()))?;
+    let variables: serde_json::Value = serde_json::from_slice(&serializer.into_inner())
+        .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+    let request = GraphQLRequest(
+        async_graphql::Request::new(&method.rpc.query)
+            .variables(async_graphql::Variables::from_json(variables)),
+    );
+    let operation_id = request.operation_id(&parts.headers);
+    let response = JITExecutor::new(app_ctx.clone(), req_ctx, operation_id)
+        .execute(request.0)
+        .await;
+
+    let result: ExecutionResult =
+        serde_json::from_slice(&response.body).map_err(|err| Status::internal(err.to_string()))?;
+
+    if let Some(error) = result.errors.into_iter().next() {
+        let code = error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("grpcCode"))
+            .and_then(|code| code.as_i64())
+            .map_or(Code::Unknown, |code| Code::from(code as i32));
+
+        return Err(Status::new(code, error.message));
+    }
+
+    let output = DynamicMessage::deserialize_with_options(
+        method.output.clone(),
+        result.data,
+        &DeserializeOptions::new().deny_unknown_fields(false),
+    )
+    .map_err(|err| Status::internal(err.to_string()))?;
+
+    Ok(output.encode_to_vec())
+}
+
+fn status_headers(status: &Status) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    status
+        .add_header(&mut headers)
+        .map_err(|status| anyhow!("Invalid gRPC status: {}", status.message()))?;
+
+    Ok(headers)
+}
+
+fn grpc_response(body: Body, headers: HeaderMap) -> Response<Body> {
+    let mut response = Response::new(body);
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+    response.headers_mut().extend(headers);
+
+    response
+}
+
+/// A Trailers-Only response, carrying the status in its headers.
+fn status_response(status: Status) -> Result<Response<Body>> {
+    Ok(grpc_response(Body::empty(), status_headers(&status)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http::Request;
+    use hyper::Body;
+    use prost::Message;
+    use prost_reflect::{DescriptorPool, DynamicMessage};
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::reflection::add_reflection_files;
+    use crate::core::app_context::AppContext;
+    use crate::core::blueprint::{Blueprint, GrpcService};
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::grpc::compression::GrpcCompression;
+    use crate::core::grpc::protobuf::to_frame;
+    use crate::core::grpc::service::proto::ProtoFile;
+    use crate::core::grpc::stream::FrameDecoder;
+    use crate::core::http::RequestContext;
+    use crate::core::rest::EndpointSet;
+    use crate::core::runtime::test::init;
+
+    const SDL: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          greet(name: String!): Greeting @expr(body: {message: "Hello {{.args.name}}"})
+        }
+
+        type Greeting {
+          message: String!
+        }
+    "#;
+
+    struct Setup {
+        app_ctx: Arc<AppContext>,
+        pool: DescriptorPool,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            let config = Config::from_sdl(SDL).to_result().unwrap();
+            let mut blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+            let options = GrpcService { package: "tailcall".to_string(), max_depth: 3 };
+            blueprint.server.grpc_service = Some(options.clone());
+
+            let mut pool = DescriptorPool::global();
+            pool.add_file_descriptor_proto(ProtoFile::new(&blueprint, &options).file)
+                .unwrap();
+            add_reflection_files(&mut pool).unwrap();
+
+            let app_ctx = AppContext::new(blueprint, init(None), EndpointSet::default()).unwrap();
+
+            Self { app_ctx: Arc::new(app_ctx), pool }
+        }
+
+        async fn call(
+            &self,
+            path: &str,
+            input: &str,
+            output: &str,
+            body: serde_json::Value,
+        ) -> (http::HeaderMap, Vec<serde_json::Value>) {
+            let input = self.pool.get_message_by_name(input).unwrap();
+            let message = DynamicMessage::deserialize(input, body).unwrap();
+            let frame = to_frame(message.encode_to_vec(), GrpcCompression::Identity).unwrap();
+
+            let req = Request::post(format!("http://localhost:8000{path}"))
+                .header("content-type", "application/grpc")
+                .body(Body::from(frame))
+                .unwrap();
+            let req_ctx = Arc::new(RequestContext::from(self.app_ctx.as_ref()));
+            let grpc_server = self.app_ctx.grpc_server.clone().unwrap();
+            let resp = grpc_server
+                .handle(req, &self.app_ctx, req_ctx)
+                .await
+                .unwrap();
+
+            let headers = resp.headers().clone();
+            let bytes = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+            let output = self.pool.get_message_by_name(output).unwrap();
+            let messages = FrameDecoder::decode_all(&bytes, GrpcCompression::Identity)
+                .unwrap()
+                .into_iter()
+                .map(|frame| {
+                    let message = DynamicMessage::decode(output.clone(), frame).unwrap();
+                    serde_json::to_value(message).unwrap()
+                })
+                .collect();
+
+            (headers, messages)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unary_call() {
+        let setup = Setup::new();
+        let (headers, messages) = setup
+            .call(
+                "/tailcall.Query/Greet",
+                "tailcall.QueryGreetRequest",
+                "tailcall.QueryGreetResponse",
+                json!({ "name": "tailcall" }),
+            )
+            .await;
+
+        assert_eq!(headers.get("content-type").unwrap(), "application/grpc");
+        assert_eq!(
+            messages,
+            vec![json!({ "greet": { "message": "Hello tailcall" } })]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method() {
+        let setup = Setup::new();
+        let (headers, messages) = setup
+            .call(
+                "/tailcall.Query/Unknown",
+                "tailcall.QueryGreetRequest",
+                "tailcall.QueryGreetResponse",
+                json!({}),
+            )
+            .await;
+
+        assert_eq!(headers.get("grpc-status").unwrap(), "12");
+        assert!(messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_multiple_request_messages() {
+        let setup = Setup::new();
+        let input = setup
+            .pool
+            .get_message_by_name("tailcall.QueryGreetRequest")
+            .unwrap();
+        let message = DynamicMessage::deserialize(input, json!({ "name": "tailcall" })).unwrap();
+        let frame = to_frame(message.encode_to_vec(), GrpcCompression::Identity).unwrap();
+
+        let req = Request::post("http://localhost:8000/tailcall.Query/Greet")
+            .header("content-type", "application/grpc")
+            .body(Body::from([frame.clone(), frame].concat()))
+            .unwrap();
+        let req_ctx = Arc::new(RequestContext::from(setup.app_ctx.as_ref()));
+        let grpc_server = setup.app_ctx.grpc_server.clone().unwrap();
+        let resp = grpc_server
+            .handle(req, &setup.app_ctx, req_ctx)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.headers().get("grpc-status").unwrap(), "3");
+    }
+
+    #[tokio::test]
+    async fn test_reflection() {
+        let setup = Setup::new();
+
+        for version in ["grpc.reflection.v1alpha", "grpc.reflection.v1"] {
+            let (_, messages) = setup
+                .call(
+                    &format!("/{version}.ServerReflection/ServerReflectionInfo"),
+                    &format!("{version}.ServerReflectionRequest"),
+                    &format!("{version}.ServerReflectionResponse"),
+                    json!({ "listServices": "" }),
+                )
+                .await;
+
+            assert_eq!(
+                messages[0]["listServicesResponse"],
+                json!({ "service": [
+                    { "name": "tailcall.Query" },
+                    { "name": "grpc.reflection.v1alpha.ServerReflection" },
+                    { "name": "grpc.reflection.v1.ServerReflection" },
+                ]})
+            );
+
+            let (_, messages) = setup
+                .call(
+                    &format!("/{version}.ServerReflection/ServerReflectionInfo"),
+                    &format!("{version}.ServerReflectionRequest"),
+                    &format!("{version}.ServerReflectionResponse"),
+                    json!({ "fileContainingSymbol": "tailcall.Query.Greet" }),
+                )
+                .await;
+
+            let files = messages[0]["fileDescriptorResponse"]["fileDescriptorProto"]
+                .as_array()
+                .unwrap();
+            assert_eq!(files.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,581 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use convert_case::{Case, Casing};
+use prost_reflect::prost_types::field_descriptor_proto::{Label, Type as ProtoType};
+use prost_reflect::prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, MethodDescriptorProto,
+    OneofDescriptorProto, ServiceDescriptorProto,
+};
+use tailcall_valid::{Valid, Validator};
+
+use crate::core::blueprint::{
+    Blueprint, BlueprintError, Definition, FieldDefinition, GrpcService, InputFieldDefinition,
+};
+use crate::core::Type;
+
+const STRUCT_PROTO: &str = "google/protobuf/struct.proto";
+const VALUE: &str = ".google.protobuf.Value";
+const STRUCT: &str = ".google.protobuf.Struct";
+
+/// A method of the generated services along with the GraphQL operation it is
+/// executed with.
+#[derive(Clone, Debug)]
+pub struct Rpc {
+    /// Path the method is called on, e.g. `/tailcall.Query/Users`.
+    pub path: String,
+    /// Operation selecting the root field, the fields of the request message
+    /// are passed as its variables.
+    pub query: String,
+    /// Fully qualified name of the request message.
+    pub input: String,
+    /// Fully qualified name of the response message.
+    pub output: String,
+}
+
+/// The `.proto` generated from a [Blueprint], with one service per root type
+/// and one message per object, interface and input type.
+#[derive(Clone, Debug)]
+pub struct ProtoFile {
+    pub file: FileDescriptorProto,
+    pub rpcs: Vec<Rpc>,
+}
+
+enum Kind {
+    Scalar(ProtoType),
+    Message(String),
+}
+
+struct Generator<'a> {
+    definitions: HashMap<&'a str, &'a Definition>,
+    roots: Vec<String>,
+    package: &'a str,
+    max_depth: usize,
+    uses_struct: bool,
+}
+
+impl<'a> Generator<'a> {
+    fn kind(&mut self, name: &str) -> Kind {
+        match name {
+            "Int" | "Int8" | "Int16" | "Int32" => Kind::Scalar(ProtoType::Int32),
+            "UInt8" | "UInt16" | "UInt32" => Kind::Scalar(ProtoType::Uint32),
+            "Int64" => Kind::Scalar(ProtoType::Int64),
+            "UInt64" => Kind::Scalar(ProtoType::Uint64),
+            "Float" => Kind::Scalar(ProtoType::Double),
+            "Boolean" => Kind::Scalar(ProtoType::Bool),
+            "JSON" | "Int128" | "UInt128" | "Empty" => self.well_known(VALUE),
+            "Upload" => self.well_known(STRUCT),
+            // Root types share their name with the services
+            _ if self.roots.iter().any(|root| root == name) => self.well_known(STRUCT),
+            _ => match self.definitions.get(name) {
+                Some(Definition::Object(_))
+                | Some(Definition::Interface(_))
+                | Some(Definition::InputObject(_)) => {
+                    Kind::Message(format!(".{}.{}", self.package, name))
+                }
+                Some(Definition::Union(_)) => self.well_known(STRUCT),
+                // Enums and the remaining scalars are represented as strings
+                _ => Kind::Scalar(ProtoType::String),
+            },
+        }
+    }
+
+    fn well_known(&mut self, name: &str) -> Kind {
+        self.uses_struct = true;
+        Kind::Message(name.to_string())
+    }
+
+    /// Adds a field of the given GraphQL type to the message. Nullable scalars
+    /// become `optional` fields, so that `null` can be told apart from the
+    /// default value.
+    fn add_field(&mut self, message: &mut DescriptorProto, name: &str, of_type: &Type) {
+        let (kind, repeated) = match of_type {
+            Type::List { of_type, .. } if of_type.is_list() => (self.well_known(VALUE), false),
+            Type::List { of_type, .. } => (self.kind(of_type.name()), true),
+            Type::Named { name, .. } => (self.kind(name), false),
+        };
+
+        let mut field = FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(message.field.len() as i32 + 1),
+            json_name: Some(name.to_string()),
+            label: Some(if repeated {
+                Label::Repeated
+            } else {
+                Label::Optional
+            } as i32),
+            ..Default::default()
+        };
+
+        match kind {
+            Kind::Scalar(ty) => {
+                field.r#type = Some(ty as i32);
+
+                if !repeated && of_type.is_nullable() {
+                    field.proto3_optional = Some(true);
+                    field.oneof_index = Some(message.oneof_decl.len() as i32);
+                    message.oneof_decl.push(OneofDescriptorProto {
+                        name: Some(format!("_{name}")),
+                        ..Default::default()
+                    });
+                }
+            }
+            Kind::Message(type_name) => {
+                field.r#type = Some(ProtoType::Message as i32);
+                field.type_name = Some(type_name);
+            }
+        }
+
+        message.field.push(field);
+    }
+
+    fn message<'b>(
+        &mut self,
+        name: &str,
+        fields: impl Iterator<Item = (&'b str, &'b Type)>,
+    ) -> DescriptorProto {
+        let mut message = DescriptorProto { name: Some(name.to_string()), ..Default::default() };
+        for (field_name, of_type) in fields {
+            self.add_field(&mut message, field_name, of_type);
+        }
+
+        message
+    }
+
+    fn fields(&self, name: &str) -> Option<&'a [FieldDefinition]> {
+        match self.definitions.get(name)? {
+            Definition::Object(def) => Some(&def.fields),
+            Definition::Interface(def) => Some(&def.fields),
+            _ => None,
+        }
+    }
+
+    fn is_composite(&self, name: &str) -> bool {
+        matches!(
+            self.definitions.get(name),
+            Some(Definition::Object(_) | Definition::Interface(_) | Definition::Union(_))
+        )
+    }
+
+    /// Builds the selection set of the given type, `None` for leaf types.
+    /// Cycles are broken by not selecting objects deeper than `max_depth`.
+    fn selection(&self, name: &str, depth: usize) -> Option<String> {
+        let selection = match self.definitions.get(name)? {
+            Definition::Union(def) => {
+                let mut selection = vec!["__typename".to_string()];
+                for member in def.types.iter() {
+                    if let Some(member_selection) = self.selection(member, depth) {
+                        selection.push(format!("... on {member} {member_selection}"));
+                    }
+                }
+
+                selection
+            }
+            Definition::Object(_) | Definition::Interface(_) => self
+                .fields(name)?
+                .iter()
+                .filter(|field| !field.args.iter().any(is_required))
+                .filter_map(|field| {
+                    if !self.is_composite(field.of_type.name()) {
+                        Some(field.name.clone())
+                    } else if depth < self.max_depth {
+                        let selection = self.selection(field.of_type.name(), depth + 1)?;
+                        Some(format!("{} {selection}", field.name))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            _ => return None,
+        };
+
+        if selection.is_empty() {
+            Some("{ __typename }".to_string())
+        } else {
+            Some(format!("{{ {} }}", selection.join(" ")))
+        }
+    }
+
+    fn operation(&self, operation_type: &str, field: &FieldDefinition) -> String {
+        let mut query = operation_type.to_string();
+        if !field.args.is_empty() {
+            let variables: Vec<_> = field
+                .args
+                .iter()
+                .map(|arg| format!("${}: {:?}", arg.name, arg.of_type))
+                .collect();
+            let arguments: Vec<_> = field
+                .args
+                .iter()
+                .map(|arg| format!("{0}: ${0}", arg.name))
+                .collect();
+
+            let _ = write!(
+                query,
+                "({}) {{ {}({})",
+                variables.join(", "),
+                field.name,
+                arguments.join(", ")
+            );
+        } else {
+            let _ = write!(query, " {{ {}", field.name);
+        }
+
+        if let Some(selection) = self.selection(field.of_type.name(), 1) {
+            let _ = write!(query, " {selection}");
+        }
+        query.push_str(" }");
+
+        query
+    }
+}
+
+/// Names of the method generated for a root field, along with the names of
+/// its request and response messages.
+fn method_names(root: &str, field: &FieldDefinition) -> (String, String, String) {
+    let method = field.name.to_case(Case::Pascal);
+    let input = format!("{root}{method}Request");
+    let output = format!("{root}{method}Response");
+
+    (method, input, output)
+}
+
+/// Checks that the messages generated for the methods of the service don't
+/// collide with the types of the schema, nor with each other.
+pub fn validate_message_names(blueprint: &Blueprint) -> Valid<(), BlueprintError> {
+    let roots: Vec<_> = [Some(blueprint.query()), blueprint.mutation()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let names: HashSet<&str> = blueprint
+        .definitions
+        .iter()
+        .map(|def| def.name())
+        .filter(|name| !roots.iter().any(|root| root == name))
+        .collect();
+    let mut messages = HashSet::new();
+
+    Valid::from_iter(roots.iter(), |root| {
+        let fields = blueprint
+            .definitions
+            .iter()
+            .find(|def| def.name() == root)
+            .and_then(|def| match def {
+                Definition::Object(def) => Some(def.fields.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Valid::from_iter(fields.iter(), |field| {
+            let (_, input, output) = method_names(root, field);
+            Valid::from_iter([input, output], |name| {
+                if names.contains(name.as_str()) || !messages.insert(name.clone()) {
+                    Valid::fail(BlueprintError::GrpcServiceMessageConflict(name))
+                } else {
+                    Valid::succeed(())
+                }
+            })
+            .trace(&field.name)
+        })
+        .trace(root)
+    })
+    .unit()
+}
+
+fn is_required(arg: &InputFieldDefinition) -> bool {
+    !arg.of_type.is_nullable() && arg.default_value.is_none()
+}
+
+impl ProtoFile {
+    pub fn new(blueprint: &Blueprint, options: &GrpcService) -> Self {
+        let mut generator = Generator {
+            definitions: blueprint
+                .definitions
+                .iter()
+                .map(|def| (def.name(), def))
+                .collect(),
+            roots: [Some(blueprint.query()), blueprint.mutation()]
+                .into_iter()
+                .flatten()
+                .collect(),
+            package: &options.package,
+            max_depth: options.max_depth,
+            uses_struct: false,
+        };
+
+        let mut file = FileDescriptorProto {
+            name: Some(format!("{}.proto", options.package.replace('.', "/"))),
+            package: Some(options.package.clone()),
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        };
+        let mut rpcs = vec![];
+
+        let roots = [
+            ("query", Some(blueprint.query())),
+            ("mutation", blueprint.mutation()),
+        ];
+        for (operation_type, root) in roots {
+            let Some(root) = root else { continue };
+            let Some(fields) = generator.fields(&root) else {
+                continue;
+            };
+
+            let mut service =
+                ServiceDescriptorProto { name: Some(root.clone()), ..Default::default() };
+
+            for field in fields {
+                let (method, input, output) = method_names(&root, field);
+
+                file.message_type.push(
+                    generator.message(
+                        &input,
+                        field
+                            .args
+                            .iter()
+                            .map(|arg| (arg.name.as_str(), &arg.of_type)),
+                    ),
+                );
+                file.message_type.push(generator.message(
+                    &output,
+                    std::iter::once((field.name.as_str(), &field.of_type)),
+                ));
+
+                rpcs.push(Rpc {
+                    path: format!("/{}.{root}/{method}", options.package),
+                    query: generator.operation(operation_type, field),
+                    input: format!("{}.{input}", options.package),
+                    output: format!("{}.{output}", options.package),
+                });
+                service.method.push(MethodDescriptorProto {
+                    name: Some(method),
+                    input_type: Some(format!(".{}.{input}", options.package)),
+                    output_type: Some(format!(".{}.{output}", options.package)),
+                    ..Default::default()
+                });
+            }
+
+            file.service.push(service);
+        }
+
+        for def in blueprint.definitions.iter() {
+            if generator.roots.iter().any(|root| root == def.name()) {
+                continue;
+            }
+
+            let message = match def {
+                Definition::Object(def) => generator.message(
+                    &def.name,
+                    def.fields.iter().map(|f| (f.name.as_str(), &f.of_type)),
+                ),
+                Definition::Interface(def) => generator.message(
+                    &def.name,
+                    def.fields.iter().map(|f| (f.name.as_str(), &f.of_type)),
+                ),
+                Definition::InputObject(def) => generator.message(
+                    &def.name,
+                    def.fields.iter().map(|f| (f.name.as_str(), &f.of_type)),
+                ),
+                _ => continue,
+            };
+
+            file.message_type.push(message);
+        }
+
+        if generator.uses_struct {
+            file.dependency.push(STRUCT_PROTO.to_string());
+        }
+
+        Self { file, rpcs }
+    }
+
+    /// Renders the file in the protobuf language.
+    pub fn to_proto_string(&self) -> String {
+        let file = &self.file;
+        let package = file.package();
+        let mut out = format!("syntax = \"proto3\";\n\npackage {package};\n");
+
+        if !file.dependency.is_empty() {
+            out.push('\n');
+        }
+        for dependency in file.dependency.iter() {
+            let _ = writeln!(out, "import \"{dependency}\";");
+        }
+
+        let type_name = |name: &str| {
+            let name = name.trim_start_matches('.');
+            name.strip_prefix(&format!("{package}."))
+                .unwrap_or(name)
+                .to_string()
+        };
+
+        for service in file.service.iter() {
+            let _ = writeln!(out, "\nservice {} {{", service.name());
+            for method in service.method.iter() {
+                let _ = writeln!(
+                    out,
+                    "  rpc {}({}) returns ({});",
+                    method.name(),
+                    type_name(method.input_type()),
+                    type_name(method.output_type())
+                );
+            }
+            out.push_str("}\n");
+        }
+
+        for message in file.message_type.iter() {
+            let _ = writeln!(out, "\nmessage {} {{", message.name());
+            for field in message.field.iter() {
+                let label = if field.label() == Label::Repeated {
+                    "repeated "
+                } else if field.proto3_optional() {
+                    "optional "
+                } else {
+                    ""
+                };
+                let ty = match field.r#type() {
+                    ProtoType::Message => type_name(field.type_name()),
+                    ty => ty.as_str_name().trim_start_matches("TYPE_").to_lowercase(),
+                };
+                let options = if field.name().contains('_') {
+                    format!(" [json_name = \"{}\"]", field.json_name())
+                } else {
+                    String::new()
+                };
+
+                let _ = writeln!(
+                    out,
+                    "  {label}{ty} {} = {}{options};",
+                    field.name(),
+                    field.number()
+                );
+            }
+            out.push_str("}\n");
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::{validate_message_names, ProtoFile};
+    use crate::core::blueprint::{Blueprint, GrpcService};
+    use crate::core::config::{Config, ConfigModule};
+
+    fn proto_file(sdl: &str) -> ProtoFile {
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let options = GrpcService { package: "tailcall".to_string(), max_depth: 2 };
+
+        ProtoFile::new(&blueprint, &options)
+    }
+
+    const SDL: &str = r#"
+        schema {
+          query: Query
+          mutation: Mutation
+        }
+
+        type Query {
+          user(id: Int!): User @http(url: "http://localhost:8000/users/{{.args.id}}")
+          users: [User!]! @http(url: "http://localhost:8000/users")
+          search(term: String): [Item] @http(url: "http://localhost:8000/search")
+        }
+
+        type Mutation {
+          createUser(input: UserInput!): User @http(url: "http://localhost:8000/users", method: POST, body: "{{.args.input}}")
+        }
+
+        input UserInput {
+          name: String!
+          email: String
+        }
+
+        type User {
+          id: Int!
+          name: String!
+          email: String
+          tags: [[String]]
+          friend: User @http(url: "http://localhost:8000/users/{{.value.id}}/friend")
+          posts(first: Int!): [Post] @http(url: "http://localhost:8000/users/{{.value.id}}/posts")
+        }
+
+        type Post {
+          title: String
+        }
+
+        union Item = User | Post
+    "#;
+
+    #[test]
+    fn test_proto_string() {
+        insta::assert_snapshot!(proto_file(SDL).to_proto_string());
+    }
+
+    #[test]
+    fn test_operations() {
+        let rpcs = proto_file(SDL).rpcs;
+        let rpcs: Vec<_> = rpcs
+            .iter()
+            .map(|rpc| format!("{}: {}", rpc.path, rpc.query))
+            .collect();
+
+        insta::assert_snapshot!(rpcs.join("\n"));
+    }
+
+    #[test]
+    fn test_valid_descriptor() {
+        let file = proto_file(SDL).file;
+        let mut pool = prost_reflect::DescriptorPool::global();
+        pool.add_file_descriptor_proto(file).unwrap();
+
+        let user = pool.get_message_by_name("tailcall.User").unwrap();
+        let email = user.get_field_by_name("email").unwrap();
+        assert!(email.supports_presence());
+        assert!(pool.get_service_by_name("tailcall.Mutation").is_some());
+    }
+
+    #[test]
+    fn test_message_name_conflicts() {
+        let sdl = r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              user: User @http(url: "http://localhost:8000/user")
+              userName: String @http(url: "http://localhost:8000/user/name")
+              user_name: String @http(url: "http://localhost:8000/user/name")
+            }
+
+            type User {
+              name: String
+              previous: QueryUserResponse
+            }
+
+            type QueryUserResponse {
+              user: User
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let errors = validate_message_names(&blueprint).to_result().unwrap_err();
+        let messages: Vec<_> = errors
+            .as_vec()
+            .iter()
+            .map(|cause| cause.message.to_string())
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "Message QueryUserResponse of the gRPC service conflicts with another message of the same name",
+                "Message QueryUserNameRequest of the gRPC service conflicts with another message of the same name",
+                "Message QueryUserNameResponse of the gRPC service conflicts with another message of the same name",
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hyper::body::{HttpBody, Sender};
+use hyper::Body;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, FileDescriptor};
+use serde::Deserialize;
+use serde_json::json;
+use tonic::Code;
+
+use crate::core::grpc::compression::GrpcCompression;
+use crate::core::grpc::protobuf::to_frame;
+use crate::core::grpc::stream::FrameDecoder;
+use crate::core::proto_reader::reflection_file;
+pub use crate::core::proto_reader::{REFLECTION_V1 as V1, REFLECTION_V1ALPHA as V1ALPHA};
+
+pub const SERVICE: &str = "ServerReflection";
+const METHOD: &str = "ServerReflectionInfo";
+
+/// Adds both versions of the reflection service to the pool, they only differ
+/// by their package.
+pub fn add_reflection_files(pool: &mut DescriptorPool) -> Result<()> {
+    for package in [V1ALPHA, V1] {
+        pool.add_file_descriptor_proto(reflection_file(package)?)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReflectionRequest {
+    file_by_filename: Option<String>,
+    file_containing_symbol: Option<String>,
+    list_services: Option<String>,
+}
+
+/// Implements the `ServerReflection` service, so that clients like grpcurl
+/// can discover the services without the `.proto` file.
+#[derive(Clone, Debug)]
+pub struct Reflection {
+    pool: DescriptorPool,
+    services: Vec<String>,
+}
+
+impl Reflection {
+    pub fn new(pool: DescriptorPool, services: Vec<String>) -> Self {
+        Self { pool, services }
+    }
+
+    /// Returns the package of the reflection service the path belongs to.
+    pub fn version(path: &str) -> Option<&'static str> {
+        [V1ALPHA, V1]
+            .into_iter()
+            .find(|package| path == format!("/{package}.{SERVICE}/{METHOD}"))
+    }
+
+    /// Answers the requests of the bidirectional stream as they arrive.
+    pub async fn serve(
+        &self,
+        version: &str,
+        mut body: Body,
+        sender: &mut Sender,
+        encoding: GrpcCompression,
+    ) -> Result<()> {
+        let mut decoder = FrameDecoder::new(encoding);
+
+        while let Some(chunk) = body.data().await {
+            decoder.push(&chunk?);
+
+            while let Some(frame) = decoder.next_frame()? {
+                let response = self.answer(version, &frame)?;
+                let frame = to_frame(response.encode_to_vec(), GrpcCompression::Identity)?;
+                sender.send_data(frame.into()).await?;
+            }
+        }
+
+        decoder.finish()
+    }
+
+    fn answer(&self, version: &str, bytes: &[u8]) -> Result<DynamicMessage> {
+        let message = |name: &str| {
+            self.pool
+                .get_message_by_name(&format!("{version}.{name}"))
+                .ok_or_else(|| anyhow::anyhow!("Couldn't find message {version}.{name}"))
+        };
+
+        let request = DynamicMessage::decode(message("ServerReflectionRequest")?, bytes)?;
+        let query: ReflectionRequest = serde_json::from_value(serde_json::to_value(&request)?)?;
+
+        let response = if query.list_services.is_some() {
+            let services: Vec<_> = self
+                .services
+                .iter()
+                .map(|name| json!({ "name": name }))
+                .collect();
+
+            json!({ "listServicesResponse": { "service": services } })
+        } else if let Some(file_name) = query.file_by_filename {
+            self.files(self.pool.get_file_by_name(&file_name), &file_name)
+        } else if let Some(symbol) = query.file_containing_symbol {
+            self.files(self.find_symbol(&symbol), &symbol)
+        } else {
+            error_response(Code::Unimplemented, "Unsupported reflection request")
+        };
+
+        let mut response =
+            DynamicMessage::deserialize(message("ServerReflectionResponse")?, response)?;
+        response.set_field_by_name("original_request", prost_reflect::Value::Message(request));
+
+        Ok(response)
+    }
+
+    fn find_symbol(&self, symbol: &str) -> Option<FileDescriptor> {
+        let pool = &self.pool;
+
+        pool.get_service_by_name(symbol)
+            .map(|service| service.parent_file())
+            .or_else(|| pool.get_message_by_name(symbol).map(|m| m.parent_file()))
+            .or_else(|| pool.get_enum_by_name(symbol).map(|e| e.parent_file()))
+            .or_else(|| {
+                // symbols of methods are qualified by their service
+                let (service, _) = symbol.rsplit_once('.')?;
+                pool.get_service_by_name(service)
+                    .map(|service| service.parent_file())
+            })
+    }
+
+    /// Responds with the file and all of its transitive dependencies.
+    fn files(&self, file: Option<FileDescriptor>, name: &str) -> serde_json::Value {
+        let Some(file) = file else {
+            return error_response(Code::NotFound, &format!("{name} not found"));
+        };
+
+        let mut files = vec![file];
+        let mut i = 0;
+        while let Some(file) = files.get(i).cloned() {
+            for dependency in file.dependencies() {
+                if !files.contains(&dependency) {
+                    files.push(dependency);
+                }
+            }
+            i += 1;
+        }
+
+        let files: Vec<_> = files
+            .iter()
+            .map(|file| BASE64_STANDARD.encode(file.file_descriptor_proto().encode_to_vec()))
+            .collect();
+
+        json!({ "fileDescriptorResponse": { "fileDescriptorProto": files } })
+    }
+}
+
+fn error_response(code: Code, message: &str) -> serde_json::Value {
+    json!({ "errorResponse": { "errorCode": code as i32, "errorMessage": message } })
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use async_graphql_value::ConstValue;
+use http::StatusCode;
+use once_cell::sync::Lazy;
+use prost_reflect::DescriptorPool;
+use tonic::Code;
+
+use super::protobuf::{ProtobufMessage, ProtobufOperation};
+
+/// Descriptors of the standard `google.rpc` error detail messages, e.g.
+/// `ErrorInfo`, `BadRequest` and `RetryInfo`.
+static ERROR_DETAILS: Lazy<Option<DescriptorPool>> = Lazy::new(|| {
+    DescriptorPool::decode(tonic_types::pb::FILE_DESCRIPTOR_SET)
+        .inspect_err(|err| tracing::error!("Failed to decode google.rpc descriptors: {err}"))
+        .ok()
+});
+
+/// Finds the descriptor of an error detail message carried by
+/// `grpc-status-details-bin`. Messages of the upstream's own proto files take
+/// precedence over the standard `google.rpc` ones.
+pub fn find_detail_message(operation: &ProtobufOperation, name: &str) -> Option<ProtobufMessage> {
+    operation.find_message(name).or_else(|| {
+        let message_descriptor = ERROR_DETAILS.as_ref()?.get_message_by_name(name)?;
+
+        Some(ProtobufMessage { message_descriptor })
+    })
+}
+
+/// Decodes an error detail packed in a `google.protobuf.Any`.
+pub fn decode_detail(
+    operation: &ProtobufOperation,
+    type_url: &str,
+    value: &[u8],
+) -> Option<ConstValue> {
+    let type_name = type_url.split('/').last().unwrap_or("");
+
+    match find_detail_message(operation, type_name) {
+        Some(message) => message
+            .decode(value)
+            .inspect_err(|_| tracing::error!("Error while decoding message: {type_name}"))
+            .ok(),
+        None => {
+            tracing::error!("Error while searching descriptor for message: {type_name}");
+            None
+        }
+    }
+}
+
+/// Maps gRPC status codes to the HTTP status codes of `@rest` responses.
+#[derive(Clone, Debug, Default)]
+pub struct StatusMapping {
+    overrides: HashMap<i32, StatusCode>,
+}
+
+impl StatusMapping {
+    pub fn new(overrides: impl IntoIterator<Item = (Code, StatusCode)>) -> Self {
+        Self {
+            overrides: overrides
+                .into_iter()
+                .map(|(code, status)| (code as i32, status))
+                .collect(),
+        }
+    }
+
+    pub fn http_status(&self, code: i32) -> StatusCode {
+        self.overrides
+            .get(&code)
+            .copied()
+            .unwrap_or_else(|| default_http_status(Code::from(code)))
+    }
+}
+
+/// The mapping documented in `google/rpc/code.proto`.
+fn default_http_status(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::Cancelled => StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use prost::Message;
+    use serde_json::json;
+    use tailcall_fixtures::protobuf;
+    use tonic::Code;
+
+    use super::{find_detail_message, StatusMapping};
+    use crate::core::blueprint::GrpcMethod;
+    use crate::core::grpc::protobuf::ProtobufSet;
+
+    #[test]
+    fn test_default_mapping() {
+        let mapping = StatusMapping::default();
+
+        assert_eq!(
+            mapping.http_status(Code::NotFound as i32),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            mapping.http_status(Code::Unauthenticated as i32),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            mapping.http_status(Code::Unavailable as i32),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(mapping.http_status(99), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_mapping_overrides() {
+        let mapping = StatusMapping::new([(Code::NotFound, StatusCode::GONE)]);
+
+        assert_eq!(mapping.http_status(Code::NotFound as i32), StatusCode::GONE);
+        assert_eq!(
+            mapping.http_status(Code::InvalidArgument as i32),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[test]
+    fn test_standard_error_details() -> anyhow::Result<()> {
+        let file_descriptor_set = protox::compile([protobuf::GREETINGS], [protobuf::SELF])?;
+        let grpc_method = GrpcMethod::try_from("greetings.Greeter.SayHello")?;
+        let file = ProtobufSet::from_proto_file(file_descriptor_set)?;
+        let operation = file
+            .find_service(&grpc_method)?
+            .find_operation(&grpc_method)?;
+
+        let error_info = tonic_types::pb::ErrorInfo {
+            reason: "USER_NOT_FOUND".to_owned(),
+            domain: "example.com".to_owned(),
+            metadata: Default::default(),
+        };
+        let message = find_detail_message(&operation, "google.rpc.ErrorInfo").unwrap();
+        let decoded = message.decode(&error_info.encode_to_vec())?;
+
+        assert_eq!(
+            serde_json::to_value(decoded)?,
+            json!({ "reason": "USER_NOT_FOUND", "domain": "example.com" })
+        );
+        assert!(find_detail_message(&operation, "google.rpc.Unknown").is_none());
+
+        Ok(())
+    }
+}
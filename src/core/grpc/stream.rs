@@ -0,0 +1,505 @@
+use anyhow::{anyhow, bail, Result};
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use http::{HeaderMap, StatusCode};
+use hyper::body::Bytes;
+use prost::bytes::{Buf, BytesMut};
+use reqwest::Request;
+
+use super::compression::{check_message_size, decompress, response_encoding, GrpcCompression};
+use super::protobuf::ProtobufOperation;
+use super::protocol::{connect_error, trailer_error, GrpcProtocol};
+use super::request::GRPC_STATUS;
+use crate::core::http::{OAuth2, Response};
+use crate::core::runtime::TargetRuntime;
+use crate::core::BodyFrame;
+
+/// Size of the prefix of every message, the compression flag followed by the
+/// length of the message.
+const PREFIX_LEN: usize = 5;
+
+/// Flags marking the trailer frame gRPC-Web streams end with (`0x80`) and the
+/// end-of-stream frame of Connect streams (`0x02`). Native gRPC never sets
+/// them.
+const TRAILER_FLAGS: u8 = 0x80 | 0x02;
+
+/// Splits a gRPC body into its Length-Prefixed Messages. Chunks can be pushed
+/// as they arrive, messages are returned as soon as they are complete and
+/// decompressed with the `grpc-encoding` of the response. The trailer frame
+/// of gRPC-Web and Connect streams is kept aside.
+#[derive(Default, Debug)]
+pub struct FrameDecoder {
+    buf: BytesMut,
+    encoding: GrpcCompression,
+    trailer: Option<Bytes>,
+}
+
+impl FrameDecoder {
+    pub fn new(encoding: GrpcCompression) -> Self {
+        Self { buf: BytesMut::new(), encoding, trailer: None }
+    }
+
+    /// Separates the messages of a complete body from its trailer frame.
+    pub fn split_trailer(
+        body: &Bytes,
+        encoding: GrpcCompression,
+    ) -> Result<(Bytes, Option<Bytes>)> {
+        let mut offset = 0;
+
+        while body.len() >= offset + PREFIX_LEN {
+            let flag = body[offset];
+            let len = u32::from_be_bytes([
+                body[offset + 1],
+                body[offset + 2],
+                body[offset + 3],
+                body[offset + 4],
+            ]) as usize;
+            let end = offset + PREFIX_LEN + len;
+
+            if end > body.len() {
+                break;
+            }
+
+            if flag & TRAILER_FLAGS != 0 {
+                let trailer = body.slice(offset + PREFIX_LEN..end);
+                let trailer = if flag & 1 == 0 {
+                    trailer
+                } else {
+                    decompress(encoding, &trailer)?.into()
+                };
+                return Ok((body.slice(..offset), Some(trailer)));
+            }
+
+            offset = end;
+        }
+
+        Ok((body.clone(), None))
+    }
+
+    /// The trailer frame, once it has been received.
+    pub fn take_trailer(&mut self) -> Option<Bytes> {
+        self.trailer.take()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// Returns the next complete message, if any.
+    pub fn next_frame(&mut self) -> Result<Option<Bytes>> {
+        if self.buf.len() < PREFIX_LEN {
+            return Ok(None);
+        }
+
+        let compressed = self.buf[0];
+        let len = u32::from_be_bytes([self.buf[1], self.buf[2], self.buf[3], self.buf[4]]) as usize;
+        check_message_size(len)?;
+
+        if self.buf.len() < PREFIX_LEN + len {
+            return Ok(None);
+        }
+
+        self.buf.advance(PREFIX_LEN);
+        let message = self.buf.split_to(len).freeze();
+
+        let message = if compressed & 1 == 0 {
+            message
+        } else {
+            decompress(self.encoding, &message)?.into()
+        };
+
+        if compressed & TRAILER_FLAGS != 0 {
+            self.trailer = Some(message);
+            return self.next_frame();
+        }
+
+        Ok(Some(message))
+    }
+
+    /// Checks that the body didn't end in the middle of a message.
+    pub fn finish(&self) -> Result<()> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            bail!("gRPC stream ended with an incomplete message")
+        }
+    }
+
+    /// Decodes every message of a complete body.
+    pub fn decode_all(bytes: &[u8], encoding: GrpcCompression) -> Result<Vec<Bytes>> {
+        let mut decoder = Self::new(encoding);
+        decoder.push(bytes);
+
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.next_frame()? {
+            frames.push(frame);
+        }
+        decoder.finish()?;
+
+        Ok(frames)
+    }
+}
+
+/// Executes a server-streaming request and returns every message of the
+/// response as soon as it arrives. The stream ends with the error of a
+/// non-OK `grpc-status`, read from the trailers of native gRPC calls or from
+/// the trailer frame of gRPC-Web and Connect streams.
+pub async fn execute_grpc_stream(
+    runtime: &TargetRuntime,
+    operation: ProtobufOperation,
+    request: Request,
+    oauth2: Option<&OAuth2>,
+) -> Result<BoxStream<'static, Result<async_graphql::Value>>> {
+    let request = match oauth2 {
+        Some(oauth2) => oauth2.with_token(runtime, request).await?,
+        None => request,
+    };
+
+    let client = if operation.protocol.requires_http2() {
+        &runtime.http2_only
+    } else {
+        &runtime.http
+    };
+    let response = client.execute_stream(request).await?;
+
+    if operation.protocol.is_connect() && !response.status.is_success() {
+        let body = response
+            .body
+            .try_filter_map(|frame| async move {
+                match frame {
+                    BodyFrame::Data(chunk) => Ok(Some(chunk)),
+                    BodyFrame::Trailers(_) => Ok(None),
+                }
+            })
+            .try_collect::<Vec<_>>()
+            .await?
+            .concat();
+        let error = connect_error(response.status, &body, &operation);
+        return Ok(stream::once(async move { Err(error) }).boxed());
+    }
+
+    // Trailers-Only responses carry the status in the headers
+    if let Some(error) = status_error(response.status, &response.headers, &operation) {
+        return Ok(stream::once(async move { Err(error) }).boxed());
+    }
+
+    if !response.status.is_success() {
+        let error = anyhow!("HTTP status error ({})", response.status);
+        return Ok(stream::once(async move { Err(error) }).boxed());
+    }
+
+    let decoder = FrameDecoder::new(response_encoding(
+        &response.headers,
+        operation.encoding_header(),
+    )?);
+    let head = Response::<Bytes> {
+        status: response.status,
+        headers: response.headers,
+        body: Bytes::new(),
+    };
+
+    Ok(stream::unfold(
+        Some((response.body, decoder, operation, head, None)),
+        |state| async move {
+            let (mut body, mut decoder, operation, head, mut trailers) = state?;
+
+            loop {
+                match decoder.next_frame() {
+                    Ok(Some(frame)) => {
+                        let value = operation.decode_message(&frame);
+                        return Some((value, Some((body, decoder, operation, head, trailers))));
+                    }
+                    Ok(None) => {}
+                    Err(err) => return Some((Err(err), None)),
+                }
+
+                match body.next().await {
+                    Some(Ok(BodyFrame::Data(chunk))) => decoder.push(&chunk),
+                    Some(Ok(BodyFrame::Trailers(headers))) => trailers = Some(headers),
+                    Some(Err(err)) => return Some((Err(err), None)),
+                    None => {
+                        if let Err(err) = decoder.finish() {
+                            return Some((Err(err), None));
+                        }
+                        let error = end_of_stream_error(&mut decoder, trailers, &head, &operation)?;
+                        return Some((Err(error), None));
+                    }
+                }
+            }
+        },
+    )
+    .boxed())
+}
+
+/// Returns the error a stream ended with, if any. Native gRPC sends the
+/// status in the trailers of the response, the other protocols in their
+/// trailer frame.
+fn end_of_stream_error(
+    decoder: &mut FrameDecoder,
+    trailers: Option<HeaderMap>,
+    head: &Response<Bytes>,
+    operation: &ProtobufOperation,
+) -> Option<anyhow::Error> {
+    if operation.protocol != GrpcProtocol::Grpc {
+        return match decoder.take_trailer() {
+            Some(trailer) => trailer_error(&trailer, head, operation),
+            None => Some(anyhow!("gRPC stream ended without a trailer frame")),
+        };
+    }
+
+    // the status of Trailers-Only responses is in the headers
+    let trailers = trailers.unwrap_or_else(|| head.headers.clone());
+    if grpc_status(&trailers).is_none() {
+        return Some(anyhow!("gRPC stream ended without a grpc-status"));
+    }
+
+    status_error(head.status, &trailers, operation)
+}
+
+fn grpc_status(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(GRPC_STATUS)
+        .and_then(|status| status.to_str().ok())
+}
+
+/// Returns the error of a non-OK `grpc-status` in `headers`.
+fn status_error(
+    status: StatusCode,
+    headers: &HeaderMap,
+    operation: &ProtobufOperation,
+) -> Option<anyhow::Error> {
+    if grpc_status(headers)? == "0" {
+        return None;
+    }
+
+    let response = Response::<Bytes> { status, headers: headers.clone(), body: Bytes::new() };
+
+    Some(response.to_grpc_error(operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use futures_util::StreamExt;
+    use http::{HeaderMap, HeaderValue, StatusCode};
+    use hyper::body::Bytes;
+    use reqwest::{Method, Request};
+    use serde_json::json;
+    use tailcall_fixtures::protobuf;
+
+    use super::{execute_grpc_stream, FrameDecoder, GrpcCompression, GRPC_STATUS};
+    use crate::core::blueprint::GrpcMethod;
+    use crate::core::grpc::compression::compress;
+    use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
+    use crate::core::grpc::protocol::GrpcProtocol;
+    use crate::core::http::Response;
+    use crate::core::ir::Error;
+    use crate::core::{BodyFrame, BodyStream, HttpIO};
+
+    #[derive(Default)]
+    struct TestHttp {
+        headers: HeaderMap,
+        body: Vec<u8>,
+        trailers: Option<HeaderMap>,
+    }
+
+    #[async_trait]
+    impl HttpIO for TestHttp {
+        async fn execute(&self, _request: Request) -> anyhow::Result<Response<Bytes>> {
+            Ok(Response {
+                status: StatusCode::OK,
+                headers: self.headers.clone(),
+                body: Bytes::from(self.body.clone()),
+            })
+        }
+
+        async fn execute_stream(&self, _request: Request) -> anyhow::Result<Response<BodyStream>> {
+            let mut frames = vec![Ok(BodyFrame::Data(Bytes::from(self.body.clone())))];
+            if let Some(trailers) = &self.trailers {
+                frames.push(Ok(BodyFrame::Trailers(trailers.clone())));
+            }
+
+            Ok(Response {
+                status: StatusCode::OK,
+                headers: self.headers.clone(),
+                body: futures_util::stream::iter(frames).boxed(),
+            })
+        }
+    }
+
+    fn trailers(status: &'static str) -> Option<HeaderMap> {
+        let mut trailers = HeaderMap::new();
+        trailers.insert(GRPC_STATUS, HeaderValue::from_static(status));
+        Some(trailers)
+    }
+
+    fn operation() -> ProtobufOperation {
+        let file_descriptor_set = protox::compile([protobuf::GREETINGS], [protobuf::SELF]).unwrap();
+        let grpc_method = GrpcMethod::try_from("greetings.Greeter.SayHello").unwrap();
+        let file = ProtobufSet::from_proto_file(file_descriptor_set).unwrap();
+        let service = file.find_service(&grpc_method).unwrap();
+        service.find_operation(&grpc_method).unwrap()
+    }
+
+    async fn collect(http: TestHttp) -> Vec<anyhow::Result<async_graphql::Value>> {
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http2_only = Arc::new(http);
+        let request = Request::new(Method::POST, "http://example.com".parse().unwrap());
+
+        execute_grpc_stream(&runtime, operation(), request, None)
+            .await
+            .unwrap()
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_stream_every_message() {
+        let body = [frame(b"\n\x05first"), frame(b"\n\x06second")].concat();
+        let values =
+            collect(TestHttp { body, trailers: trailers("0"), ..Default::default() }).await;
+        let values = values
+            .into_iter()
+            .map(|value| value.unwrap().into_json().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![json!({"message": "first"}), json!({"message": "second"})]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_trailers_only_error() {
+        let mut headers = HeaderMap::new();
+        headers.insert(GRPC_STATUS, HeaderValue::from_static("5"));
+        let values = collect(TestHttp { headers, ..Default::default() }).await;
+
+        assert_eq!(values.len(), 1);
+        assert!(values[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stream_trailer_error() {
+        let body = frame(b"\n\x05first");
+        let values =
+            collect(TestHttp { body, trailers: trailers("13"), ..Default::default() }).await;
+
+        assert_eq!(values.len(), 2);
+        assert!(values[0].is_ok());
+        match values[1].as_ref().unwrap_err().downcast_ref::<Error>() {
+            Some(Error::GRPC { grpc_code, .. }) => assert_eq!(*grpc_code, 13),
+            _ => panic!("Expected GRPCError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_without_status() {
+        let body = frame(b"\n\x05first");
+        let values = collect(TestHttp { body, ..Default::default() }).await;
+
+        assert_eq!(values.len(), 2);
+        assert!(values[0].is_ok());
+        assert!(values[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_grpc_web_stream_trailer_error() {
+        let body = [
+            frame(b"\n\x05first"),
+            [vec![0x80, 0, 0, 0, 16], b"grpc-status: 13\r\n".to_vec()].concat(),
+        ]
+        .concat();
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = Arc::new(TestHttp { body, ..Default::default() });
+        let request = Request::new(Method::POST, "http://example.com".parse().unwrap());
+        let operation = operation().with_protocol(GrpcProtocol::GrpcWeb);
+
+        let values = execute_grpc_stream(&runtime, operation, request, None)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(values.len(), 2);
+        assert!(values[0].is_ok());
+        assert!(values[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_grpc_web_stream_without_trailer() {
+        let body = frame(b"\n\x05first");
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = Arc::new(TestHttp { body, ..Default::default() });
+        let request = Request::new(Method::POST, "http://example.com".parse().unwrap());
+        let operation = operation().with_protocol(GrpcProtocol::GrpcWeb);
+
+        let values = execute_grpc_stream(&runtime, operation, request, None)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(values.len(), 2);
+        assert!(values[0].is_ok());
+        assert_eq!(
+            values[1].as_ref().unwrap_err().to_string(),
+            "gRPC stream ended without a trailer frame"
+        );
+    }
+
+    fn frame(message: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0];
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(message);
+        frame
+    }
+
+    #[test]
+    fn test_decode_frames_across_chunks() {
+        let body = [frame(b"first"), frame(b""), frame(b"second")].concat();
+        let mut decoder = FrameDecoder::default();
+        let mut frames = vec![];
+
+        for chunk in body.chunks(3) {
+            decoder.push(chunk);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame);
+            }
+        }
+
+        assert!(decoder.finish().is_ok());
+        assert_eq!(frames, vec!["first".as_bytes(), b"", b"second"]);
+    }
+
+    #[test]
+    fn test_incomplete_message() {
+        let body = frame(b"message");
+        let result = FrameDecoder::decode_all(&body[..body.len() - 1], Default::default());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_message_too_large() {
+        // the length is checked before the message is buffered
+        let mut decoder = FrameDecoder::default();
+        decoder.push(&[0, 0xff, 0xff, 0xff, 0xff]);
+
+        assert!(decoder.next_frame().is_err());
+    }
+
+    #[test]
+    fn test_compressed_message() {
+        let compressed = compress(GrpcCompression::Gzip, b"message").unwrap();
+        let mut body = frame(&compressed);
+        body[0] = 1;
+
+        let frames = FrameDecoder::decode_all(&body, GrpcCompression::Gzip).unwrap();
+        assert_eq!(frames, vec!["message".as_bytes()]);
+
+        // compressed messages require the grpc-encoding of the response
+        assert!(FrameDecoder::decode_all(&body, GrpcCompression::Identity).is_err());
+    }
+}
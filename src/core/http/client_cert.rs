@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use rustls_pki_types::CertificateDer;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Identity of a client that presented a certificate during the TLS handshake.
+/// The certificate chain has already been verified against the linked CA
+/// bundles by the time this is created.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientCert {
+    /// Distinguished name of the leaf certificate eg: `CN=orders,O=acme`
+    pub subject: String,
+    /// Subject alternative names of the leaf certificate
+    pub san: Vec<String>,
+    /// Names of the authorities of the CA bundles the chain was verified
+    /// against
+    pub issuers: Vec<String>,
+}
+
+impl ClientCert {
+    /// Extracts the identity from a certificate chain where the first
+    /// certificate is the leaf. The issuers are the authorities the chain was
+    /// verified against, the certificates presented along with the leaf are
+    /// not trusted on their own.
+    pub fn from_chain(chain: &[CertificateDer<'_>], issuers: Vec<String>) -> Option<Self> {
+        let leaf = chain.first()?;
+        let (_, leaf) = X509Certificate::from_der(leaf.as_ref()).ok()?;
+
+        let san = leaf
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|ext| {
+                ext.value
+                    .general_names
+                    .iter()
+                    .filter_map(general_name_to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self { subject: leaf.subject().to_string(), san, issuers })
+    }
+
+    /// Checks if the chain was verified against one of the given authorities.
+    pub fn is_issued_by(&self, authorities: &HashSet<String>) -> bool {
+        self.issuers
+            .iter()
+            .any(|issuer| authorities.contains(issuer))
+    }
+}
+
+/// Returns the subject names of the given certificates, typically the CA
+/// certificates that client certificates are issued by.
+pub fn subject_names(certs: &[CertificateDer<'_>]) -> HashSet<String> {
+    certs
+        .iter()
+        .filter_map(|cert| {
+            X509Certificate::from_der(cert.as_ref())
+                .ok()
+                .map(|(_, cert)| cert.subject().to_string())
+        })
+        .collect()
+}
+
+fn general_name_to_string(name: &GeneralName<'_>) -> Option<String> {
+    match name {
+        GeneralName::DNSName(name) => Some(format!("DNS:{}", name)),
+        GeneralName::RFC822Name(name) => Some(format!("email:{}", name)),
+        GeneralName::URI(uri) => Some(format!("URI:{}", uri)),
+        GeneralName::IPAddress(bytes) => match bytes.len() {
+            4 => Some(format!(
+                "IP:{}",
+                std::net::Ipv4Addr::from(<[u8; 4]>::try_from(*bytes).ok()?)
+            )),
+            16 => Some(format!(
+                "IP:{}",
+                std::net::Ipv6Addr::from(<[u8; 16]>::try_from(*bytes).ok()?)
+            )),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::ClientCert;
+
+    fn client_cert() -> ClientCert {
+        ClientCert {
+            subject: "CN=orders".to_string(),
+            san: vec!["DNS:orders.internal".to_string()],
+            issuers: vec!["CN=Intermediate CA".to_string(), "CN=Root CA".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_is_issued_by_leaf_issuer() {
+        let authorities = HashSet::from(["CN=Intermediate CA".to_string()]);
+        assert!(client_cert().is_issued_by(&authorities));
+    }
+
+    #[test]
+    fn test_is_issued_by_chain_issuer() {
+        let authorities = HashSet::from(["CN=Root CA".to_string()]);
+        assert!(client_cert().is_issued_by(&authorities));
+    }
+
+    #[test]
+    fn test_is_not_issued_by_unknown_authority() {
+        let authorities = HashSet::from(["CN=Other CA".to_string()]);
+        assert!(!client_cert().is_issued_by(&authorities));
+    }
+
+    #[test]
+    fn test_from_empty_chain() {
+        assert_eq!(ClientCert::from_chain(&[], vec![]), None);
+    }
+}
@@ -5,13 +5,14 @@ use std::time::Duration;
 use async_graphql::async_trait;
 use async_graphql::futures_util::future::join_all;
 use async_graphql_value::ConstValue;
+use hyper::body::Bytes;
 use tailcall_valid::Validator;
 
 use super::transformations::{BodyBatching, QueryBatching};
 use crate::core::config::group_by::GroupBy;
 use crate::core::config::Batch;
 use crate::core::data_loader::{DataLoader, Loader};
-use crate::core::http::{DataLoaderRequest, Response};
+use crate::core::http::{DataLoaderRequest, OAuth2, Response};
 use crate::core::json::JsonLike;
 use crate::core::runtime::TargetRuntime;
 use crate::core::transform::TransformerOps;
@@ -40,10 +41,27 @@ pub struct HttpDataLoader {
     pub runtime: TargetRuntime,
     pub group_by: Option<GroupBy>,
     is_list: bool,
+    oauth2: Option<Arc<OAuth2>>,
 }
 impl HttpDataLoader {
     pub fn new(runtime: TargetRuntime, group_by: Option<GroupBy>, is_list: bool) -> Self {
-        HttpDataLoader { runtime, group_by, is_list }
+        HttpDataLoader { runtime, group_by, is_list, oauth2: None }
+    }
+
+    pub fn with_oauth2(mut self, oauth2: Option<Arc<OAuth2>>) -> Self {
+        self.oauth2 = oauth2;
+        self
+    }
+
+    async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+        match &self.oauth2 {
+            Some(oauth2) => {
+                oauth2
+                    .execute(&self.runtime, self.runtime.http.as_ref(), request)
+                    .await
+            }
+            None => self.runtime.http.execute(request).await,
+        }
     }
 
     pub fn to_data_loader(self, batch: Batch) -> DataLoader<DataLoaderRequest, HttpDataLoader> {
@@ -88,12 +106,7 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
                 };
 
                 // Dispatch request
-                let res = self
-                    .runtime
-                    .http
-                    .execute(base_request)
-                    .await?
-                    .to_json::<ConstValue>()?;
+                let res = self.execute(base_request).await?.to_json::<ConstValue>()?;
 
                 // Create a response HashMap
                 #[allow(clippy::mutable_key_type)]
@@ -147,7 +160,7 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
             }
         } else {
             let results = keys.iter().map(|key| async {
-                let result = self.runtime.http.execute(key.to_request()).await;
+                let result = self.execute(key.to_request()).await;
                 (key.clone(), result)
             });
 
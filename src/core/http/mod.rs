@@ -1,24 +1,31 @@
 pub use cache::*;
+pub use client_cert::{subject_names, ClientCert};
 pub use data_loader::*;
 pub use data_loader_request::*;
 use http::HeaderValue;
 pub use method::Method;
+pub use oauth2::{OAuth2, OAuth2Tokens};
 pub use query_encoder::QueryEncoder;
 pub use request_context::RequestContext;
 pub use request_handler::{handle_request, API_URL_PREFIX};
 pub use request_template::RequestTemplate;
 pub use response::*;
+pub use signing::{AwsSigV4, Hmac, Signing};
 
 mod cache;
+mod client_cert;
 mod data_loader;
 mod data_loader_request;
 mod method;
+pub mod multipart;
+mod oauth2;
 mod query_encoder;
 mod request_context;
 mod request_handler;
 mod request_template;
 mod response;
 pub mod showcase;
+mod signing;
 mod telemetry;
 mod transformations;
 
@@ -0,0 +1,196 @@
+use anyhow::{anyhow, Result};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+
+/// A file of a GraphQL multipart request, it's passed around as the value of
+/// the `Upload` scalar with its content encoded in base64.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upload {
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub content: Vec<u8>,
+}
+
+impl Upload {
+    pub fn to_value(&self) -> Value {
+        json!({
+            "filename": self.filename,
+            "contentType": self.content_type,
+            "content": BASE64_STANDARD.encode(&self.content),
+        })
+    }
+
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        let filename = object.get("filename")?.as_str()?.to_string();
+        let content = BASE64_STANDARD
+            .decode(object.get("content")?.as_str()?)
+            .ok()?;
+        let content_type = object
+            .get("contentType")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        Some(Self { filename, content_type, content })
+    }
+}
+
+/// Encodes the rendered body of `@http` as `multipart/form-data`, every field
+/// of the object is a part and `Upload` values are sent as files. Returns the
+/// content type along with the encoded body.
+pub fn encode(body: &Value) -> Result<(String, Vec<u8>)> {
+    let fields = match body {
+        // a single file is sent as the `file` field
+        body if Upload::from_value(body).is_some() => vec![("file", body)],
+        Value::Object(object) => object.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+        _ => return Err(anyhow!("Multipart body must be an object, found: {body}")),
+    };
+
+    let mut parts = Vec::new();
+    for (name, value) in fields {
+        let name = escape(name);
+        let mut part = Vec::new();
+        match Upload::from_value(value) {
+            Some(upload) => {
+                let content_type = upload
+                    .content_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream");
+                if content_type.contains(['\r', '\n']) {
+                    return Err(anyhow!("Invalid content type of file `{name}`"));
+                }
+                part.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{name}\"; filename=\"{}\"\r\n\
+                         Content-Type: {content_type}\r\n\r\n",
+                        escape(&upload.filename)
+                    )
+                    .as_bytes(),
+                );
+                part.extend_from_slice(&upload.content);
+            }
+            None => {
+                let text = match value {
+                    Value::String(text) => text.clone(),
+                    value => value.to_string(),
+                };
+                part.extend_from_slice(
+                    format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n{text}")
+                        .as_bytes(),
+                );
+            }
+        }
+        parts.push(part);
+    }
+
+    // the boundary must not appear in the content of any part
+    let boundary = loop {
+        let boundary = format!("tailcall-{:016x}", rand::random::<u64>());
+        if !parts.iter().any(|part| contains(part, boundary.as_bytes())) {
+            break boundary;
+        }
+    };
+
+    let mut bytes = Vec::new();
+    for part in parts {
+        bytes.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        bytes.extend_from_slice(&part);
+        bytes.extend_from_slice(b"\r\n");
+    }
+    bytes.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    Ok((format!("multipart/form-data; boundary={boundary}"), bytes))
+}
+
+/// Percent-encodes the characters that would end a quoted parameter of the
+/// `Content-Disposition` header, like browsers do.
+fn escape(value: &str) -> String {
+    value
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+        .replace('"', "%22")
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use hyper::body::Bytes;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_encode() {
+        let upload = Upload {
+            filename: "report.pdf".to_string(),
+            content_type: Some("application/pdf".to_string()),
+            content: b"%PDF-1.7".to_vec(),
+        };
+        let body = json!({ "title": "Report", "pages": 3, "file": upload.to_value() });
+        let (content_type, bytes) = encode(&body).unwrap();
+
+        let boundary = multer::parse_boundary(&content_type).unwrap();
+        let stream = futures_util::stream::once(async { Ok::<_, Infallible>(Bytes::from(bytes)) });
+        let mut multipart = multer::Multipart::new(stream, boundary);
+
+        let mut parts = vec![];
+        while let Some(field) = multipart.next_field().await.unwrap() {
+            let name = field.name().unwrap().to_string();
+            let filename = field.file_name().map(str::to_string);
+            let content_type = field.content_type().map(ToString::to_string);
+            parts.push((name, filename, content_type, field.text().await.unwrap()));
+        }
+
+        assert_eq!(
+            parts,
+            vec![
+                ("title".into(), None, None, "Report".into()),
+                ("pages".into(), None, None, "3".into()),
+                (
+                    "file".into(),
+                    Some("report.pdf".into()),
+                    Some("application/pdf".into()),
+                    "%PDF-1.7".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_escapes_headers() {
+        let upload = Upload {
+            filename: "a\"\r\nb.txt".to_string(),
+            content_type: None,
+            content: b"hello".to_vec(),
+        };
+        let body = json!({ "na\"me": upload.to_value() });
+        let (_, bytes) = encode(&body).unwrap();
+
+        assert!(String::from_utf8(bytes).unwrap().contains(
+            "Content-Disposition: form-data; name=\"na%22me\"; filename=\"a%22%0D%0Ab.txt\"\r\n"
+        ));
+
+        let upload = Upload {
+            content_type: Some("text/plain\r\nX: y".to_string()),
+            ..upload
+        };
+        let error = encode(&upload.to_value()).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid content type of file `file`");
+    }
+
+    #[test]
+    fn test_encode_scalar() {
+        let error = encode(&json!("text")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Multipart body must be an object, found: \"text\""
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use http::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use hyper::body::Bytes;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use url::Url;
+
+use super::Response;
+use crate::core::config::reader_context::ConfigReaderContext;
+use crate::core::grpc::request::GRPC_STATUS;
+use crate::core::mustache::Mustache;
+use crate::core::runtime::TargetRuntime;
+use crate::core::{config, HttpIO};
+
+/// Lifetime of tokens that are issued without `expires_in`.
+const DEFAULT_EXPIRES_IN: u64 = 3600;
+
+/// gRPC status code returned when the credentials are missing or invalid.
+const GRPC_UNAUTHENTICATED: &str = "16";
+
+/// Obtains access tokens with the OAuth2 client credentials grant and attaches
+/// them to upstream requests. Tokens are kept until `refresh_before` seconds
+/// before they expire, so that they are refreshed before upstreams start
+/// rejecting them.
+#[derive(Clone, Debug)]
+pub struct OAuth2 {
+    pub token_url: Url,
+    pub client_id: Mustache,
+    pub client_secret: Mustache,
+    pub scopes: Vec<String>,
+    pub audience: Option<String>,
+    pub refresh_before: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct Credentials {
+    client_id: String,
+    client_secret: String,
+}
+
+struct Token {
+    access_token: String,
+    refresh_at: DateTime<Utc>,
+}
+
+/// Provider settings the tokens are issued for.
+#[derive(PartialEq, Eq, Hash)]
+struct Provider {
+    token_url: Url,
+    client_id: String,
+    scopes: Vec<String>,
+    audience: Option<String>,
+}
+
+/// Token slots by provider, shared by every operator of a runtime using the
+/// same provider settings.
+#[derive(Default)]
+pub struct OAuth2Tokens(Mutex<HashMap<Provider, Arc<TokenSlot>>>);
+
+/// Token of a provider. The lock is held while a new token is requested, so
+/// that concurrent requests wait for it instead of requesting their own.
+#[derive(Default)]
+struct TokenSlot(tokio::sync::Mutex<Option<Token>>);
+
+impl TryFrom<&config::OAuth2> for OAuth2 {
+    type Error = url::ParseError;
+
+    fn try_from(oauth2: &config::OAuth2) -> Result<Self, Self::Error> {
+        Ok(Self {
+            token_url: Url::parse(&oauth2.token_url)?,
+            client_id: Mustache::parse(&oauth2.client_id),
+            client_secret: Mustache::parse(&oauth2.client_secret),
+            scopes: oauth2.scopes.clone(),
+            audience: oauth2.audience.clone(),
+            refresh_before: oauth2.get_refresh_before(),
+        })
+    }
+}
+
+impl OAuth2 {
+    /// Executes the request with a bearer token. If the upstream rejects the
+    /// token, a new one is requested and the request is retried once.
+    pub async fn execute(
+        &self,
+        runtime: &TargetRuntime,
+        http: &dyn HttpIO,
+        request: reqwest::Request,
+    ) -> Result<Response<Bytes>> {
+        let credentials = self.credentials(runtime);
+        let retry = request.try_clone();
+
+        let token = self.token(runtime, &credentials, None).await?;
+        let response = http.execute(authorize(request, &token)?).await;
+
+        match retry {
+            Some(retry) if is_unauthorized(&response) => {
+                let token = self.token(runtime, &credentials, Some(&token)).await?;
+                http.execute(authorize(retry, &token)?).await
+            }
+            _ => response,
+        }
+    }
+
+    /// Attaches a bearer token to the request without retrying it, for
+    /// responses that are consumed as a stream.
+    pub async fn with_token(
+        &self,
+        runtime: &TargetRuntime,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Request> {
+        let credentials = self.credentials(runtime);
+        let token = self.token(runtime, &credentials, None).await?;
+
+        authorize(request, &token)
+    }
+
+    fn credentials(&self, runtime: &TargetRuntime) -> Credentials {
+        let ctx = ConfigReaderContext::new(runtime);
+
+        Credentials {
+            client_id: self.client_id.render(&ctx),
+            client_secret: self.client_secret.render(&ctx),
+        }
+    }
+
+    fn slot(&self, runtime: &TargetRuntime, credentials: &Credentials) -> Arc<TokenSlot> {
+        let provider = Provider {
+            token_url: self.token_url.clone(),
+            client_id: credentials.client_id.clone(),
+            scopes: self.scopes.clone(),
+            audience: self.audience.clone(),
+        };
+
+        let mut tokens = runtime.oauth2_tokens.0.lock().unwrap();
+        tokens.entry(provider).or_default().clone()
+    }
+
+    /// Returns the current token of the provider unless it's about to expire
+    /// or it's the `rejected` one, in which case a new token is requested.
+    async fn token(
+        &self,
+        runtime: &TargetRuntime,
+        credentials: &Credentials,
+        rejected: Option<&str>,
+    ) -> Result<String> {
+        let slot = self.slot(runtime, credentials);
+        let mut current = slot.0.lock().await;
+
+        if let Some(token) = current.as_ref() {
+            let is_rejected = rejected == Some(token.access_token.as_str());
+            if !is_rejected && token.refresh_at > Utc::now() {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let response = runtime
+            .http
+            .execute(self.token_request(credentials)?)
+            .await
+            .context("OAuth2 token request failed")?;
+        if !response.status.is_success() {
+            bail!(
+                "OAuth2 token request failed with status {}: {}",
+                response.status,
+                String::from_utf8_lossy(&response.body)
+            );
+        }
+        let token: TokenResponse =
+            serde_json::from_slice(&response.body).context("Invalid OAuth2 token response")?;
+
+        let expires_in = token.expires_in.unwrap_or(DEFAULT_EXPIRES_IN);
+        let ttl = if expires_in > self.refresh_before {
+            expires_in - self.refresh_before
+        } else {
+            expires_in / 2
+        };
+
+        *current = Some(Token {
+            access_token: token.access_token.clone(),
+            refresh_at: Utc::now() + Duration::seconds(ttl as i64),
+        });
+
+        Ok(token.access_token)
+    }
+
+    fn token_request(&self, credentials: &Credentials) -> Result<reqwest::Request> {
+        let mut form = vec![
+            ("grant_type", "client_credentials".to_string()),
+            ("client_id", credentials.client_id.clone()),
+            ("client_secret", credentials.client_secret.clone()),
+        ];
+        if !self.scopes.is_empty() {
+            form.push(("scope", self.scopes.join(" ")));
+        }
+        if let Some(audience) = &self.audience {
+            form.push(("audience", audience.clone()));
+        }
+
+        let mut request = reqwest::Request::new(reqwest::Method::POST, self.token_url.clone());
+        request.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        request
+            .body_mut()
+            .replace(serde_urlencoded::to_string(form)?.into());
+
+        Ok(request)
+    }
+}
+
+fn authorize(mut request: reqwest::Request, token: &str) -> Result<reqwest::Request> {
+    let value = HeaderValue::from_str(&format!("Bearer {}", token))
+        .map_err(|_| anyhow!("OAuth2 access token is not a valid header value"))?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+
+    Ok(request)
+}
+
+/// Checks if the upstream rejected the credentials, either with a `401` status
+/// or with the `UNAUTHENTICATED` gRPC status.
+fn is_unauthorized(response: &Result<Response<Bytes>>) -> bool {
+    match response {
+        Ok(response) => {
+            response.status == StatusCode::UNAUTHORIZED
+                || response
+                    .headers
+                    .get(GRPC_STATUS)
+                    .is_some_and(|status| status == GRPC_UNAUTHENTICATED)
+        }
+        Err(err) => err
+            .downcast_ref::<reqwest::Error>()
+            .and_then(|err| err.status())
+            .is_some_and(|status| status == StatusCode::UNAUTHORIZED),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::core::tests::TestEnvIO;
+
+    fn oauth2(server: &httpmock::MockServer) -> OAuth2 {
+        OAuth2 {
+            token_url: Url::parse(&server.url("/token")).unwrap(),
+            client_id: Mustache::parse("{{.env.CLIENT_ID}}"),
+            client_secret: Mustache::parse("secret"),
+            scopes: vec!["read".to_string(), "write".to_string()],
+            audience: None,
+            refresh_before: 60,
+        }
+    }
+
+    fn runtime() -> TargetRuntime {
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.env = Arc::new(TestEnvIO::from_iter([(
+            "CLIENT_ID".to_owned(),
+            "orders".to_owned(),
+        )]));
+        runtime
+    }
+
+    fn request(server: &httpmock::MockServer) -> reqwest::Request {
+        reqwest::Request::new(
+            reqwest::Method::GET,
+            Url::parse(&server.url("/orders")).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached() {
+        let server = httpmock::MockServer::start();
+        let token = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/token")
+                .body("grant_type=client_credentials&client_id=orders&client_secret=secret&scope=read+write");
+            then.status(200)
+                .body(r#"{"access_token": "abc", "token_type": "Bearer", "expires_in": 3600}"#);
+        });
+        let orders = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/orders")
+                .header("authorization", "Bearer abc");
+            then.status(200).body("[]");
+        });
+
+        let runtime = runtime();
+        let oauth2 = oauth2(&server);
+        for _ in 0..2 {
+            let response = oauth2
+                .execute(&runtime, runtime.http.as_ref(), request(&server))
+                .await
+                .unwrap();
+            assert_eq!(response.status, StatusCode::OK);
+        }
+
+        token.assert_hits(1);
+        orders.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_on_unauthorized() {
+        let server = httpmock::MockServer::start();
+        let token = server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/token");
+            then.status(200).body(r#"{"access_token": "abc"}"#);
+        });
+        let orders = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/orders");
+            then.status(401);
+        });
+
+        let runtime = runtime();
+        let result = oauth2(&server)
+            .execute(&runtime, runtime.http.as_ref(), request(&server))
+            .await;
+
+        assert!(result.is_err());
+        token.assert_hits(2);
+        orders.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_token() {
+        let server = httpmock::MockServer::start();
+        let token = server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/token");
+            then.status(200).body(r#"{"access_token": "abc"}"#);
+        });
+        let orders = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/orders")
+                .header("authorization", "Bearer abc");
+            then.status(200).body("[]");
+        });
+
+        let runtime = runtime();
+        let oauth2 = oauth2(&server);
+        let responses = futures_util::future::join_all(
+            (0..4).map(|_| oauth2.execute(&runtime, runtime.http.as_ref(), request(&server))),
+        )
+        .await;
+
+        assert!(responses.iter().all(|response| response.is_ok()));
+        token.assert_hits(1);
+        orders.assert_hits(4);
+    }
+
+    #[tokio::test]
+    async fn test_token_request_error_status() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/token");
+            then.status(401).body(r#"{"error": "invalid_client"}"#);
+        });
+
+        let runtime = runtime();
+        let error = oauth2(&server)
+            .execute(&runtime, runtime.http.as_ref(), request(&server))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().starts_with("OAuth2 token request failed"));
+    }
+
+    #[tokio::test]
+    async fn test_tokens_per_runtime() {
+        let server = httpmock::MockServer::start();
+        let token = server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/token");
+            then.status(200).body(r#"{"access_token": "abc"}"#);
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/orders");
+            then.status(200).body("[]");
+        });
+
+        let oauth2 = oauth2(&server);
+        for runtime in [runtime(), runtime()] {
+            let response = oauth2
+                .execute(&runtime, runtime.http.as_ref(), request(&server))
+                .await
+                .unwrap();
+            assert_eq!(response.status, StatusCode::OK);
+        }
+
+        token.assert_hits(2);
+    }
+}
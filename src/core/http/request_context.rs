@@ -4,6 +4,7 @@ use std::sync::{Arc, Mutex};
 
 use async_graphql_value::ConstValue;
 use cache_control::{Cachability, CacheControl};
+use chrono::{DateTime, Utc};
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderName, HeaderValue};
 
@@ -12,7 +13,7 @@ use crate::core::blueprint::{Server, Upstream};
 use crate::core::data_loader::{DataLoader, DedupeResult};
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc::data_loader::GrpcDataLoader;
-use crate::core::http::{DataLoaderRequest, HttpDataLoader};
+use crate::core::http::{ClientCert, DataLoaderRequest, HttpDataLoader};
 use crate::core::ir::model::IoId;
 use crate::core::ir::Error;
 use crate::core::runtime::TargetRuntime;
@@ -27,6 +28,8 @@ pub struct RequestContext {
     // A subset of all the headers received in the GraphQL Request that will be sent to the
     // upstream.
     pub allowed_headers: HeaderMap,
+    // Identity of the client when it presented a verified certificate over mTLS.
+    pub client_cert: Option<ClientCert>,
     pub http_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, HttpDataLoader>>>,
     pub gql_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, GraphqlDataLoader>>>,
     pub grpc_data_loaders: Arc<Vec<DataLoader<grpc::DataLoaderRequest, GrpcDataLoader>>>,
@@ -35,6 +38,9 @@ pub struct RequestContext {
     pub runtime: TargetRuntime,
     pub cache: DedupeResult<IoId, ConstValue, Error>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
+    // Point in time after which the response is no longer used, derived from
+    // `globalResponseTimeout`.
+    pub deadline: Option<DateTime<Utc>>,
 }
 
 impl RequestContext {
@@ -53,6 +59,8 @@ impl RequestContext {
             cache: DedupeResult::new(true),
             dedupe_handler: Arc::new(DedupeResult::new(false)),
             allowed_headers: HeaderMap::new(),
+            client_cert: None,
+            deadline: None,
         }
     }
     fn set_min_max_age_conc(&self, min_max_age: i32) {
@@ -66,6 +74,13 @@ impl RequestContext {
         *self.cache_public.lock().unwrap() = Some(false);
     }
 
+    /// Returns the time left until the deadline of the request.
+    pub fn remaining_time(&self) -> Option<std::time::Duration> {
+        let remaining = self.deadline? - Utc::now();
+
+        Some(remaining.to_std().unwrap_or_default())
+    }
+
     pub fn is_cache_public(&self) -> Option<bool> {
         *self.cache_public.lock().unwrap()
     }
@@ -193,6 +208,7 @@ impl From<&AppContext> for RequestContext {
             x_response_headers: Arc::new(Mutex::new(HeaderMap::new())),
             cookie_headers,
             allowed_headers: HeaderMap::new(),
+            client_cert: None,
             http_data_loaders: app_ctx.http_data_loaders.clone(),
             gql_data_loaders: app_ctx.gql_data_loaders.clone(),
             grpc_data_loaders: app_ctx.grpc_data_loaders.clone(),
@@ -201,10 +217,16 @@ impl From<&AppContext> for RequestContext {
             runtime: app_ctx.runtime.clone(),
             cache: DedupeResult::new(true),
             dedupe_handler: app_ctx.dedupe_handler.clone(),
+            deadline: deadline(app_ctx.blueprint.server.global_response_timeout),
         }
     }
 }
 
+fn deadline(global_response_timeout: i64) -> Option<DateTime<Utc>> {
+    (global_response_timeout > 0)
+        .then(|| Utc::now() + chrono::Duration::milliseconds(global_response_timeout))
+}
+
 #[cfg(test)]
 mod test {
     use cache_control::Cachability;
@@ -294,4 +316,17 @@ mod test {
             create_req_ctx_with_batch(Batch { delay: 1, max_size: Some(1), ..Default::default() });
         assert!(req_ctx.is_batching_enabled());
     }
+
+    #[test]
+    fn test_remaining_time() {
+        let req_ctx = RequestContext::default();
+        assert_eq!(req_ctx.remaining_time(), None);
+
+        let req_ctx = req_ctx.deadline(super::deadline(60_000));
+        let remaining = req_ctx.remaining_time().unwrap();
+        assert!(remaining > std::time::Duration::from_secs(59));
+
+        let req_ctx = req_ctx.deadline(Some(chrono::Utc::now() - chrono::Duration::seconds(1)));
+        assert_eq!(req_ctx.remaining_time(), Some(std::time::Duration::ZERO));
+    }
 }
@@ -2,8 +2,9 @@ use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_graphql::ServerError;
+use futures_util::stream::{self, StreamExt};
 use hyper::header::{self, HeaderValue, CONTENT_TYPE};
 use hyper::http::request::Parts;
 use hyper::http::Method;
@@ -17,14 +18,19 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use super::request_context::RequestContext;
 use super::telemetry::{get_response_status_code, RequestCounter};
-use super::{showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
+use super::{showcase, telemetry, ClientCert, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
 use crate::core::app_context::AppContext;
-use crate::core::async_graphql_hyper::{GraphQLRequestLike, GraphQLResponse};
+use crate::core::async_graphql_hyper::{read_multipart, GraphQLRequestLike, GraphQLResponse};
 use crate::core::blueprint::telemetry::TelemetryExporter;
 use crate::core::config::{PrometheusExporter, PrometheusFormat};
+use crate::core::grpc::service::GrpcServer;
 use crate::core::jit::JITExecutor;
 
 pub const API_URL_PREFIX: &str = "/api";
+const TEXT_EVENT_STREAM: &str = "text/event-stream";
+/// Browsers send multipart forms cross-origin without a preflight, a custom
+/// header is required to prevent CSRF.
+const APOLLO_REQUIRE_PREFLIGHT: &str = "apollo-require-preflight";
 
 fn prometheus_metrics(prometheus_exporter: &PrometheusExporter) -> Result<Response<Body>> {
     let metric_families = prometheus::default_registry().gather();
@@ -54,10 +60,58 @@ fn not_found() -> Result<Response<Body>> {
         .body(Body::empty())?)
 }
 
+/// Responds with `503` until the upstreams required by the health checks are
+/// serving, so that no traffic is routed to the server before.
+fn readiness(app_ctx: &AppContext) -> Result<Response<Body>> {
+    let response = Response::builder().header(CONTENT_TYPE, "application/json");
+
+    match app_ctx.grpc_health.as_ref() {
+        Some(grpc_health) if !grpc_health.is_ready() => {
+            let body = serde_json::to_vec(&serde_json::json!({
+                "message": "not ready",
+                "upstreams": grpc_health.upstreams(),
+            }))?;
+            Ok(response
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .body(Body::from(body))?)
+        }
+        _ => Ok(response
+            .status(StatusCode::OK)
+            .body(Body::from(r#"{"message": "ready"}"#))?),
+    }
+}
+
+/// Serves the OpenAPI document of the `@rest` endpoints, as YAML if the client
+/// accepts it and as JSON otherwise.
+fn openapi(req: &Request<Body>, app_ctx: &AppContext) -> Result<Response<Body>> {
+    let yaml = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("yaml"));
+
+    let (content_type, body) = if yaml {
+        (
+            "application/yaml",
+            serde_yaml_ng::to_string(&app_ctx.openapi)?,
+        )
+    } else {
+        ("application/json", serde_json::to_string(&app_ctx.openapi)?)
+    };
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from(body))?)
+}
+
 fn create_request_context(req: &Request<Body>, app_ctx: &AppContext) -> RequestContext {
     let allowed_headers =
         create_allowed_headers(req.headers(), &app_ctx.blueprint.upstream.allowed_headers);
-    RequestContext::from(app_ctx).allowed_headers(allowed_headers)
+    let client_cert = req.extensions().get::<ClientCert>().cloned();
+    RequestContext::from(app_ctx)
+        .allowed_headers(allowed_headers)
+        .client_cert(client_cert)
 }
 
 pub fn update_response_headers(
@@ -90,19 +144,38 @@ pub async fn graphql_request<T: DeserializeOwned + GraphQLRequestLike>(
     req_counter.set_http_route("/graphql");
     let req_ctx = Arc::new(create_request_context(&req, app_ctx));
     let (req, body) = req.into_parts();
-    let bytes = hyper::body::to_bytes(body).await?;
-    let graphql_request = serde_json::from_slice::<T>(&bytes);
+    let content_type = req
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let graphql_request = if content_type.starts_with("multipart/form-data") {
+        if req.headers.contains_key(APOLLO_REQUIRE_PREFLIGHT) {
+            read_multipart::<T>(content_type, body, &app_ctx.blueprint.server.uploads).await
+        } else {
+            Err(anyhow!(
+                "Multipart requests must set the `Apollo-Require-Preflight` header"
+            ))
+        }
+    } else {
+        let bytes = hyper::body::to_bytes(body).await?;
+        serde_json::from_slice::<T>(&bytes).map_err(|err| {
+            tracing::error!(
+                "Failed to parse request: {}",
+                String::from_utf8_lossy(&bytes)
+            );
+            err.into()
+        })
+    };
     match graphql_request {
-        Ok(request) => {
+        Ok(mut request) => {
+            if request.is_subscription() {
+                return subscribe(app_ctx, &req_ctx, request, &req);
+            }
             let resp = execute_query(app_ctx, &req_ctx, request, req).await?;
             Ok(resp)
         }
         Err(err) => {
-            tracing::error!(
-                "Failed to parse request: {}",
-                String::from_utf8(bytes.to_vec()).unwrap()
-            );
-
             let mut response = async_graphql::Response::default();
             let server_error =
                 ServerError::new(format!("Unexpected GraphQL Request: {}", err), None);
@@ -135,6 +208,50 @@ async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
     Ok(response)
 }
 
+/// Streams the events of a subscription as Server-Sent Events, following the
+/// distinct connections mode of the GraphQL over SSE protocol.
+fn subscribe<T: GraphQLRequestLike>(
+    app_ctx: &Arc<AppContext>,
+    req_ctx: &Arc<RequestContext>,
+    request: T,
+    req: &Parts,
+) -> Result<Response<Body>> {
+    let accepts_event_stream = req
+        .headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains(TEXT_EVENT_STREAM));
+
+    if !accepts_event_stream {
+        let error = ServerError::new(
+            format!(
+                "Subscriptions are only sent as {TEXT_EVENT_STREAM}, set it in the Accept header"
+            ),
+            None,
+        );
+        let response = async_graphql::Response::from_errors(vec![error]);
+        return GraphQLResponse::from(response).into_response();
+    }
+
+    let events = request
+        .data(req_ctx.clone())
+        .execute_stream(&app_ctx.schema)
+        .map(|response| {
+            serde_json::to_string(&response).map(|data| format!("event: next\ndata: {data}\n\n"))
+        })
+        .chain(stream::once(async {
+            Ok("event: complete\ndata:\n\n".to_owned())
+        }));
+
+    let mut response = Response::builder()
+        .header(CONTENT_TYPE, TEXT_EVENT_STREAM)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(Body::wrap_stream(events))?;
+    update_response_headers(&mut response, req_ctx, app_ctx);
+
+    Ok(response)
+}
+
 fn create_allowed_headers(headers: &HeaderMap, allowed: &BTreeSet<String>) -> HeaderMap {
     let mut new_headers = HeaderMap::with_capacity(allowed.len());
     for (k, v) in headers.iter() {
@@ -251,17 +368,38 @@ async fn handle_rest_apis(
             { HTTP_ROUTE } = http_route
         );
         return async {
-            let graphql_request = p_request.into_request(request).await?;
-            let mut response = graphql_request
-                .data(req_ctx.clone())
-                .execute(&app_ctx.schema)
-                .await
+            let server = &app_ctx.blueprint.server;
+            // only a query fetched with GET reports a `null` root field as
+            // not found, mutations may legitimately return `null`
+            let not_found_on_null = request.method() == Method::GET && p_request.is_query();
+            let response = match p_request.into_request(request).await {
+                Ok(graphql_request) => {
+                    graphql_request
+                        .data(req_ctx.clone())
+                        .execute(&app_ctx.schema)
+                        .await
+                }
+                // raised before the execution, so the response has no path and
+                // is reported as a bad request
+                Err(error) => GraphQLResponse::from(async_graphql::Response::from_errors(
+                    error
+                        .messages()
+                        .into_iter()
+                        .map(|message| ServerError::new(message, None))
+                        .collect(),
+                )),
+            };
+            let mut response = response
                 .set_cache_control(
-                    app_ctx.blueprint.server.enable_cache_control_header,
+                    server.enable_cache_control_header,
                     req_ctx.get_min_max_age().unwrap_or(0),
                     req_ctx.is_cache_public().unwrap_or(true),
                 )
-                .into_rest_response()?;
+                .into_rest_response(
+                    &server.grpc_status_mapping,
+                    &server.rest_errors,
+                    not_found_on_null,
+                )?;
             update_response_headers(&mut response, &req_ctx, &app_ctx);
             Ok(response)
         }
@@ -281,7 +419,17 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
         return handle_rest_apis(req, app_ctx, req_counter).await;
     }
 
+    if let Some(grpc_server) = app_ctx.grpc_server.clone() {
+        if GrpcServer::is_grpc(&req) {
+            req_counter.set_http_route(req.uri().path());
+            let req_ctx = Arc::new(create_request_context(&req, &app_ctx));
+            return grpc_server.handle(req, &app_ctx, req_ctx).await;
+        }
+    }
+
     let health_check_endpoint = app_ctx.blueprint.server.routes.status();
+    let readiness_endpoint = app_ctx.blueprint.server.routes.readiness();
+    let openapi_endpoint = app_ctx.blueprint.server.routes.openapi();
     let graphql_endpoint = app_ctx.blueprint.server.routes.graphql();
 
     match *req.method() {
@@ -304,12 +452,21 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
             graphql_request::<T>(req, &Arc::new(app_ctx), req_counter).await
         }
         Method::GET if req.uri().path() == health_check_endpoint => {
+            let body = match app_ctx.grpc_health.as_ref() {
+                Some(grpc_health) => Body::from(serde_json::to_vec(&serde_json::json!({
+                    "message": "ready",
+                    "upstreams": grpc_health.upstreams(),
+                }))?),
+                None => Body::from(r#"{"message": "ready"}"#),
+            };
             let status_response = Response::builder()
                 .status(StatusCode::OK)
                 .header(CONTENT_TYPE, "application/json")
-                .body(Body::from(r#"{"message": "ready"}"#))?;
+                .body(body)?;
             Ok(status_response)
         }
+        Method::GET if req.uri().path() == readiness_endpoint => readiness(&app_ctx),
+        Method::GET if req.uri().path() == openapi_endpoint => openapi(&req, &app_ctx),
         Method::GET => {
             if let Some(TelemetryExporter::Prometheus(prometheus)) =
                 app_ctx.blueprint.telemetry.export.as_ref()
@@ -368,9 +525,10 @@ mod test {
 
     use super::*;
     use crate::core::async_graphql_hyper::GraphQLRequest;
-    use crate::core::blueprint::Blueprint;
+    use crate::core::blueprint::{Blueprint, GrpcHealthCheck};
     use crate::core::config::{Config, ConfigModule, Routes};
-    use crate::core::rest::EndpointSet;
+    use crate::core::grpc::health::GrpcHealth;
+    use crate::core::rest::{EndpointSet, Unchecked};
     use crate::core::runtime::test::init;
 
     #[tokio::test]
@@ -383,7 +541,7 @@ mod test {
             blueprint,
             init(None),
             EndpointSet::default(),
-        ));
+        )?);
 
         let req = Request::builder()
             .method(Method::GET)
@@ -399,6 +557,176 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_readiness_endpoint() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let mut app_ctx = AppContext::new(blueprint, init(None), EndpointSet::default())?;
+        let check = GrpcHealthCheck {
+            url: "http://localhost:50051".into(),
+            service: String::new(),
+            interval: 10,
+            required: true,
+            headers: Default::default(),
+            oauth2: None,
+        };
+        app_ctx.grpc_health = Some(Arc::new(GrpcHealth::try_new(&[check])?));
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("http://localhost:8000/ready".to_string())
+            .body(Body::empty())?;
+
+        let resp = handle_request::<GraphQLRequest>(req, Arc::new(app_ctx)).await?;
+
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        let body: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(body["message"], "not ready");
+        assert_eq!(body["upstreams"][0]["status"], "UNKNOWN");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_openapi_endpoint() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        )?);
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("http://localhost:8000/openapi.json".to_string())
+            .header(header::ACCEPT, "application/yaml")
+            .body(Body::empty())?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[CONTENT_TYPE], "application/yaml");
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        assert!(body.starts_with(b"openapi: 3.1.0\n"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multipart_requires_preflight() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        )?);
+
+        let body = "--X\r\nContent-Disposition: form-data; name=\"operations\"\r\n\r\n\
+            {\"query\": \"{ __typename }\"}\r\n--X--\r\n";
+        let request = |preflight: bool| {
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost:8000/graphql")
+                .header(CONTENT_TYPE, "multipart/form-data; boundary=X");
+            if preflight {
+                req = req.header("Apollo-Require-Preflight", "true");
+            }
+            req.body(Body::from(body))
+        };
+
+        let resp = handle_request::<GraphQLRequest>(request(false)?, app_ctx.clone()).await?;
+        let body: serde_json::Value =
+            serde_json::from_slice(&hyper::body::to_bytes(resp.into_body()).await?)?;
+        assert_eq!(
+            body["errors"][0]["message"],
+            "Unexpected GraphQL Request: Multipart requests must set the \
+             `Apollo-Require-Preflight` header"
+        );
+
+        let resp = handle_request::<GraphQLRequest>(request(true)?, app_ctx).await?;
+        let body: serde_json::Value =
+            serde_json::from_slice(&hyper::body::to_bytes(resp.into_body()).await?)?;
+        assert_eq!(body["data"]["__typename"], "Query");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rest_invalid_query_param() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let operation = r#"
+            query ($id: Int) @rest(method: GET, path: "/post", query: {id: $id}) {
+                post(id: $id) { title }
+            }
+        "#;
+        let endpoints = EndpointSet::<Unchecked>::try_new(operation)?
+            .into_checked(&blueprint, init(None))
+            .await?;
+        let app_ctx = Arc::new(AppContext::new(blueprint, init(None), endpoints)?);
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("http://localhost:8000/api/post?id=one".to_string())
+            .body(Body::empty())?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rest_invalid_body() -> anyhow::Result<()> {
+        let sdl = r#"
+            schema { query: Query mutation: Mutation }
+            type Query { user: User @expr(body: {name: "John"}) }
+            type Mutation { createUser(input: UserInput!): User @expr(body: {name: "John"}) }
+            input UserInput { name: String! age: Int }
+            type User { name: String }
+        "#;
+        let config = Config::from_sdl(sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let operation = r#"
+            mutation ($input: UserInput) @rest(method: POST, path: "/users", body: $input) {
+                createUser(input: $input) { name }
+            }
+        "#;
+        let endpoints = EndpointSet::<Unchecked>::try_new(operation)?
+            .into_checked(&blueprint, init(None))
+            .await?;
+        let app_ctx = Arc::new(AppContext::new(blueprint, init(None), endpoints)?);
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/api/users".to_string())
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("age=forty-two"))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value =
+            serde_json::from_slice(&hyper::body::to_bytes(resp.into_body()).await?)?;
+        assert_eq!(
+            body["errors"],
+            serde_json::json!([
+                { "message": "input.age: expected a value of type Int" },
+                { "message": "input.name: missing required field" }
+            ])
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_graphql_endpoint() -> anyhow::Result<()> {
         let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
@@ -409,7 +737,7 @@ mod test {
             blueprint,
             init(None),
             EndpointSet::default(),
-        ));
+        )?);
 
         let query = r#"{"query": "{ __schema { queryType { name } } }"}"#;
         let req = Request::builder()
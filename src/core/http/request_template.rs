@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderValue};
@@ -7,6 +8,7 @@ use tailcall_hasher::TailcallHasher;
 use url::Url;
 
 use super::query_encoder::QueryEncoder;
+use super::{multipart, OAuth2, Signing};
 use crate::core::config::Encoding;
 use crate::core::endpoint::Endpoint;
 use crate::core::has_headers::HasHeaders;
@@ -30,6 +32,8 @@ pub struct RequestTemplate {
     pub endpoint: Endpoint,
     pub encoding: Encoding,
     pub query_encoder: QueryEncoder,
+    pub oauth2: Option<Arc<OAuth2>>,
+    pub signing: Option<Arc<Signing>>,
 }
 
 #[derive(Setters, Debug, Clone)]
@@ -148,6 +152,15 @@ impl RequestTemplate {
                     req.body_mut().replace(form_data.into());
                     None
                 }
+                Encoding::MultipartFormData => {
+                    let body = serde_json::from_str(&body_path.render(ctx))?;
+                    let (content_type, bytes) = multipart::encode(&body)?;
+
+                    req.headers_mut()
+                        .insert(reqwest::header::CONTENT_TYPE, content_type.parse()?);
+                    req.body_mut().replace(bytes.into());
+                    None
+                }
             }
         } else {
             None
@@ -178,6 +191,8 @@ impl RequestTemplate {
                     Encoding::ApplicationXWwwFormUrlencoded => {
                         HeaderValue::from_static("application/x-www-form-urlencoded")
                     }
+                    // the boundary is added along with the body
+                    Encoding::MultipartFormData => HeaderValue::from_static("multipart/form-data"),
                 },
             );
         }
@@ -196,6 +211,8 @@ impl RequestTemplate {
             endpoint: Endpoint::new(root_url.to_string()),
             encoding: Default::default(),
             query_encoder: Default::default(),
+            oauth2: None,
+            signing: None,
         })
     }
 
@@ -247,6 +264,8 @@ impl TryFrom<Endpoint> for RequestTemplate {
             endpoint,
             encoding,
             query_encoder: Default::default(),
+            oauth2: None,
+            signing: None,
         })
     }
 }
@@ -708,6 +727,33 @@ mod tests {
         assert_eq!(body, "baz");
     }
 
+    #[test]
+    fn test_body_encoding_multipart_form_data() {
+        let tmpl = RequestTemplate::new("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .encoding(crate::core::config::Encoding::MultipartFormData)
+            .body_path(Some(Mustache::parse(r#"{"name": "{{foo.bar}}"}"#)));
+        let ctx = Context::default().value(json!({
+          "foo": {
+            "bar": "baz"
+          }
+        }));
+        let request_wrapper = tmpl.to_request(&ctx).unwrap();
+        let req = request_wrapper.request();
+        let content_type = req.headers().get("Content-Type").unwrap().to_str().unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = req.body().unwrap().as_bytes().unwrap();
+        assert_eq!(
+            std::str::from_utf8(body).unwrap(),
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nbaz\r\n--{boundary}--\r\n"
+            )
+        );
+    }
+
     mod endpoint {
         use http::header::HeaderMap;
         use serde_json::json;
@@ -8,7 +8,9 @@ use prost::Message;
 use tonic::Status;
 use tonic_types::Status as GrpcStatus;
 
+use crate::core::grpc::compression::response_encoding;
 use crate::core::grpc::protobuf::ProtobufOperation;
+use crate::core::grpc::status::decode_detail;
 use crate::core::ir::Error;
 
 #[derive(Clone, Debug, Default, Setters)]
@@ -92,7 +94,12 @@ impl Response<Bytes> {
         operation: &ProtobufOperation,
     ) -> Result<Response<async_graphql::Value>> {
         let mut resp = Response::default();
-        let body = operation.convert_output::<async_graphql::Value>(&self.body)?;
+        let encoding = response_encoding(&self.headers, operation.encoding_header())?;
+        let body = if operation.is_server_streaming() {
+            ConstValue::List(operation.convert_output_stream(&self.body, encoding)?)
+        } else {
+            operation.convert_encoded_output::<async_graphql::Value>(&self.body, encoding)?
+        };
         resp.body = body;
         resp.status = self.status;
         resp.headers = self.headers;
@@ -112,22 +119,9 @@ impl Response<Bytes> {
                 obj.insert(Name::new("code"), status.code.into());
                 obj.insert(Name::new("message"), status.message.clone().into());
 
-                for detail in status.details {
-                    let type_url = &detail.type_url;
-                    let type_name = type_url.split('/').last().unwrap_or("");
-
-                    if let Some(message) = operation.find_message(type_name) {
-                        if let Ok(decoded) = message.decode(detail.value.as_slice()) {
-                            status_details.push(decoded);
-                        } else {
-                            tracing::error!("Error while decoding message: {type_name}");
-                        }
-                    } else {
-                        tracing::error!(
-                            "Error while searching descriptor for message: {type_name}"
-                        );
-                    }
-                }
+                status_details.extend(status.details.iter().filter_map(|detail| {
+                    decode_detail(operation, &detail.type_url, &detail.value)
+                }));
             } else {
                 tracing::error!("Error while decoding gRPC status details");
             }
@@ -62,11 +62,15 @@ pub async fn create_app_ctx<T: DeserializeOwned + GraphQLRequestLike>(
         }
     };
 
-    Ok(Ok(AppContext::new(
-        blueprint,
-        runtime,
-        EndpointSet::default(),
-    )))
+    match AppContext::new(blueprint, runtime, EndpointSet::default()) {
+        Ok(app_ctx) => Ok(Ok(app_ctx)),
+        Err(e) => {
+            let mut response = async_graphql::Response::default();
+            let server_error = ServerError::new(format!("{:#}", e), None);
+            response.errors = vec![server_error];
+            Ok(Err(GraphQLResponse::from(response).into_response()?))
+        }
+    }
 }
 
 #[cfg(test)]
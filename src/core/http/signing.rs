@@ -0,0 +1,372 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac as HmacImpl, Mac};
+use http::header::{HeaderName, HeaderValue, CONTENT_TYPE, HOST};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::core::config::HmacAlgorithm;
+use crate::core::mustache::Mustache;
+use crate::core::path::PathString;
+
+const AWS_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const X_AMZ_DATE: &str = "x-amz-date";
+const X_AMZ_CONTENT_SHA256: &str = "x-amz-content-sha256";
+const X_AMZ_SECURITY_TOKEN: &str = "x-amz-security-token";
+
+/// Signs upstream requests right before they are sent. Credentials are mustache
+/// templates that are rendered against the evaluation context, so they can be
+/// read from the environment or vars.
+#[derive(Clone, Debug)]
+pub enum Signing {
+    AwsSigV4(AwsSigV4),
+    Hmac(Hmac),
+}
+
+#[derive(Clone, Debug)]
+pub struct AwsSigV4 {
+    pub region: String,
+    pub service: String,
+    pub access_key_id: Mustache,
+    pub secret_access_key: Mustache,
+    pub session_token: Option<Mustache>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hmac {
+    pub secret: Mustache,
+    pub algorithm: HmacAlgorithm,
+    pub header: HeaderName,
+}
+
+impl Signing {
+    /// Adds the signature headers to the request.
+    pub fn sign<C: PathString>(&self, request: &mut reqwest::Request, ctx: &C) -> Result<()> {
+        match self {
+            Signing::AwsSigV4(aws) => aws.sign(request, ctx, Utc::now()),
+            Signing::Hmac(hmac) => hmac.sign(request, ctx),
+        }
+    }
+}
+
+impl AwsSigV4 {
+    fn sign<C: PathString>(
+        &self,
+        request: &mut reqwest::Request,
+        ctx: &C,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let access_key_id = self.access_key_id.render(ctx);
+        let secret_access_key = self.secret_access_key.render(ctx);
+        let session_token = self.session_token.as_ref().map(|token| token.render(ctx));
+
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(body(request)));
+
+        let headers = request.headers_mut();
+        headers.insert(X_AMZ_DATE, HeaderValue::from_str(&amz_date)?);
+        if self.is_s3() {
+            headers.insert(X_AMZ_CONTENT_SHA256, HeaderValue::from_str(&payload_hash)?);
+        }
+        if let Some(token) = &session_token {
+            headers.insert(X_AMZ_SECURITY_TOKEN, HeaderValue::from_str(token)?);
+        }
+
+        let mut signed_headers = vec![
+            (HOST.to_string(), host(request.url())?),
+            (X_AMZ_DATE.to_string(), amz_date.clone()),
+        ];
+        for name in [
+            CONTENT_TYPE.as_str(),
+            X_AMZ_CONTENT_SHA256,
+            X_AMZ_SECURITY_TOKEN,
+        ] {
+            if let Some(value) = request.headers().get(name) {
+                signed_headers.push((name.to_string(), value.to_str()?.trim().to_string()));
+            }
+        }
+        signed_headers.sort();
+
+        let canonical_headers =
+            signed_headers
+                .iter()
+                .fold(String::new(), |mut headers, (name, value)| {
+                    headers.push_str(&format!("{}:{}\n", name, value));
+                    headers
+                });
+        let signed_header_names = signed_headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = [
+            request.method().as_str(),
+            &self.canonical_uri(request.url()),
+            &canonical_query(request.url()),
+            &canonical_headers,
+            &signed_header_names,
+            &payload_hash,
+        ]
+        .join("\n");
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = [
+            AWS_ALGORITHM,
+            &amz_date,
+            &scope,
+            &hex::encode(Sha256::digest(canonical_request.as_bytes())),
+        ]
+        .join("\n");
+
+        let key = [self.region.as_str(), self.service.as_str(), "aws4_request"]
+            .iter()
+            .fold(
+                hmac_sha256(
+                    format!("AWS4{}", secret_access_key).as_bytes(),
+                    date.as_bytes(),
+                ),
+                |key, part| hmac_sha256(&key, part.as_bytes()),
+            );
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            AWS_ALGORITHM, access_key_id, scope, signed_header_names, signature
+        );
+        request.headers_mut().insert(
+            http::header::AUTHORIZATION,
+            HeaderValue::from_str(&authorization)?,
+        );
+
+        Ok(())
+    }
+
+    fn is_s3(&self) -> bool {
+        self.service == "s3"
+    }
+
+    /// Every path segment is URI encoded twice, except for S3 which expects
+    /// it to be encoded only once.
+    fn canonical_uri(&self, url: &url::Url) -> String {
+        let path = url
+            .path()
+            .split('/')
+            .map(|segment| {
+                let segment = urlencoding::decode(segment)
+                    .map(|segment| urlencoding::encode(&segment).into_owned())
+                    .unwrap_or(segment.to_string());
+                if self.is_s3() {
+                    segment
+                } else {
+                    urlencoding::encode(&segment).into_owned()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if path.is_empty() {
+            "/".to_string()
+        } else {
+            path
+        }
+    }
+}
+
+impl Hmac {
+    fn sign<C: PathString>(&self, request: &mut reqwest::Request, ctx: &C) -> Result<()> {
+        let secret = self.secret.render(ctx);
+        let body = body(request);
+
+        let signature = match self.algorithm {
+            HmacAlgorithm::Sha256 => {
+                let mut mac = HmacImpl::<Sha256>::new_from_slice(secret.as_bytes())?;
+                mac.update(body);
+                hex::encode(mac.finalize().into_bytes())
+            }
+            HmacAlgorithm::Sha512 => {
+                let mut mac = HmacImpl::<Sha512>::new_from_slice(secret.as_bytes())?;
+                mac.update(body);
+                hex::encode(mac.finalize().into_bytes())
+            }
+        };
+
+        request
+            .headers_mut()
+            .insert(self.header.clone(), HeaderValue::from_str(&signature)?);
+
+        Ok(())
+    }
+}
+
+/// Returns the body of the request, streaming bodies are treated as empty.
+fn body(request: &reqwest::Request) -> &[u8] {
+    request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .unwrap_or_default()
+}
+
+fn host(url: &url::Url) -> Result<String> {
+    let host = url
+        .host_str()
+        .ok_or(anyhow!("Can not sign a request without a host: {}", url))?;
+
+    Ok(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+fn canonical_query(url: &url::Url) -> String {
+    let mut pairs = url
+        .query_pairs()
+        .map(|(key, value)| {
+            (
+                urlencoding::encode(&key).into_owned(),
+                urlencoding::encode(&value).into_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    pairs.sort();
+
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    // HMAC accepts keys of any length, so this can not fail.
+    let mut mac = HmacImpl::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::core::config::reader_context::ConfigReaderContext;
+    use crate::core::tests::TestEnvIO;
+
+    fn aws(service: &str) -> AwsSigV4 {
+        AwsSigV4 {
+            region: "us-east-1".to_string(),
+            service: service.to_string(),
+            access_key_id: Mustache::parse("{{.env.AWS_ACCESS_KEY_ID}}"),
+            secret_access_key: Mustache::parse("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY"),
+            session_token: None,
+        }
+    }
+
+    fn runtime() -> crate::core::runtime::TargetRuntime {
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.env = Arc::new(TestEnvIO::from_iter([(
+            "AWS_ACCESS_KEY_ID".to_owned(),
+            "AKIDEXAMPLE".to_owned(),
+        )]));
+        runtime
+    }
+
+    fn list_users() -> reqwest::Request {
+        let mut request = reqwest::Request::new(
+            reqwest::Method::GET,
+            "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08"
+                .parse()
+                .unwrap(),
+        );
+        request.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded; charset=utf-8"),
+        );
+        request
+    }
+
+    #[test]
+    fn test_aws_sigv4() {
+        let runtime = runtime();
+        let ctx = ConfigReaderContext::new(&runtime);
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        let mut request = list_users();
+        aws("iam").sign(&mut request, &ctx, now).unwrap();
+
+        assert_eq!(request.headers()[X_AMZ_DATE], "20150830T123600Z");
+        assert_eq!(
+            request.headers()[http::header::AUTHORIZATION],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[test]
+    fn test_aws_sigv4_s3_headers() {
+        let runtime = runtime();
+        let ctx = ConfigReaderContext::new(&runtime);
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        let mut request = list_users();
+        let mut s3 = aws("s3");
+        s3.session_token = Some(Mustache::parse("token"));
+        s3.sign(&mut request, &ctx, now).unwrap();
+
+        let headers = request.headers();
+        assert_eq!(
+            headers[X_AMZ_CONTENT_SHA256],
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(headers[X_AMZ_SECURITY_TOKEN], "token");
+        assert!(headers[http::header::AUTHORIZATION]
+            .to_str()
+            .unwrap()
+            .contains(
+            "SignedHeaders=content-type;host;x-amz-content-sha256;x-amz-date;x-amz-security-token"
+        ));
+    }
+
+    #[test]
+    fn test_canonical_uri() {
+        let url = "https://example.com/documents and settings/"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            aws("execute-api").canonical_uri(&url),
+            "/documents%2520and%2520settings/"
+        );
+        assert_eq!(
+            aws("s3").canonical_uri(&url),
+            "/documents%20and%20settings/"
+        );
+    }
+
+    #[test]
+    fn test_hmac() {
+        let runtime = runtime();
+        let ctx = ConfigReaderContext::new(&runtime);
+        let hmac = Hmac {
+            secret: Mustache::parse("key"),
+            algorithm: HmacAlgorithm::Sha256,
+            header: HeaderName::from_static("x-signature"),
+        };
+
+        let mut request = reqwest::Request::new(
+            reqwest::Method::POST,
+            "https://example.com/webhooks".parse().unwrap(),
+        );
+        request
+            .body_mut()
+            .replace("The quick brown fox jumps over the lazy dog".into());
+        hmac.sign(&mut request, &ctx).unwrap();
+
+        assert_eq!(
+            request.headers()["x-signature"],
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
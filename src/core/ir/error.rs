@@ -8,6 +8,14 @@ use thiserror::Error;
 use crate::core::jit::graphql_error::{Error as ExtensionError, ErrorExtensions};
 use crate::core::{auth, cache, worker, Errata};
 
+/// `code` extension of the errors raised when a request fails
+/// authentication.
+pub const UNAUTHENTICATED: &str = "UNAUTHENTICATED";
+
+/// `code` extension of the errors raised when an authenticated request isn't
+/// allowed, e.g. reported by an upstream.
+pub const FORBIDDEN: &str = "FORBIDDEN";
+
 #[derive(From, Debug, Error, Clone)]
 pub enum Error {
     IO(String),
@@ -87,6 +95,10 @@ impl ErrorExtensions for Error {
                 e.set("grpcStatusMessage", grpc_status_message);
                 e.set("grpcStatusDetails", grpc_status_details.clone());
             }
+
+            if let Error::Auth(_) = self {
+                e.set("code", UNAUTHENTICATED);
+            }
         })
     }
 }
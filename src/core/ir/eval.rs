@@ -4,9 +4,10 @@ use std::ops::Deref;
 
 use async_graphql_value::ConstValue;
 use futures_util::future::join_all;
+use futures_util::stream::BoxStream;
 use indexmap::IndexMap;
 
-use super::eval_io::eval_io;
+use super::eval_io::{eval_io, eval_io_stream};
 use super::model::{Cache, CacheKey, Map, IR};
 use super::{Error, EvalContext, ResolverContextLike, TypedValue};
 use crate::core::auth::verify::{AuthVerifier, Verify};
@@ -168,6 +169,31 @@ impl IR {
             }
         })
     }
+
+    /// Evaluates the resolver of a subscription field, every value of the
+    /// stream is an event of the subscription.
+    pub async fn eval_stream<Ctx>(
+        &self,
+        ctx: &mut EvalContext<'_, Ctx>,
+    ) -> Result<BoxStream<'static, Result<ConstValue, Error>>, Error>
+    where
+        Ctx: ResolverContextLike + Sync,
+    {
+        match self {
+            IR::Protect(auth, expr) => {
+                let verifier = AuthVerifier::from(auth.clone());
+                verifier.verify(ctx.request_ctx).await.to_result()?;
+
+                Box::pin(expr.eval_stream(ctx)).await
+            }
+            IR::IO(io) => eval_io_stream(io, ctx).await,
+            // streams are never cached
+            IR::Cache(Cache { io, .. }) => eval_io_stream(io, ctx).await,
+            _ => Err(Error::IO(
+                "Only server-streaming @grpc methods can be subscribed to".to_owned(),
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -216,7 +242,7 @@ mod tests {
             );
 
             let ir = IR::Merge([a, b, c].into_iter().map(IR::Dynamic).collect());
-            let runtime = crate::cli::runtime::init(&Blueprint::default());
+            let runtime = crate::cli::runtime::init(&Blueprint::default()).unwrap();
             let req_ctx = RequestContext::new(runtime);
             let res_ctx = EmptyResolverContext {};
             let mut eval_ctx = EvalContext::new(&req_ctx, &res_ctx);
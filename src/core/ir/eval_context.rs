@@ -7,7 +7,7 @@ use http::header::HeaderMap;
 
 use super::{GraphQLOperationContext, RelatedFields, ResolverContextLike, SelectionField};
 use crate::core::document::print_directives;
-use crate::core::http::RequestContext;
+use crate::core::http::{ClientCert, RequestContext};
 
 // TODO: rename to ResolverContext
 #[derive(Clone)]
@@ -89,6 +89,10 @@ impl<'a, Ctx: ResolverContextLike> EvalContext<'a, Ctx> {
         value.to_str().ok()
     }
 
+    pub fn client_cert(&self) -> Option<&ClientCert> {
+        self.request_ctx.client_cert.as_ref()
+    }
+
     pub fn env_var(&self, key: &str) -> Option<Cow<'_, str>> {
         self.request_ctx.runtime.env.get(key)
     }
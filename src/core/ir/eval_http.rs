@@ -12,7 +12,7 @@ use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::grpc::request::execute_grpc_request;
 use crate::core::grpc::request_template::RenderedRequestTemplate;
 use crate::core::http::{
-    cache_policy, DataLoaderRequest, HttpDataLoader, RequestTemplate, Response,
+    cache_policy, DataLoaderRequest, HttpDataLoader, OAuth2, RequestTemplate, Response,
 };
 use crate::core::ir::Error;
 use crate::core::json::JsonLike;
@@ -76,14 +76,19 @@ impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context>
 
     pub async fn execute(
         &self,
-        req: DynamicRequest<String>,
+        mut req: DynamicRequest<String>,
     ) -> Result<Response<async_graphql::Value>, Error> {
         let ctx = &self.evaluation_ctx;
+        // sign last, so that the changes of the `onRequest` hook are signed too
+        if let Some(signing) = &self.request_template.signing {
+            signing.sign(req.request_mut(), *ctx)?;
+        }
+
         let dl = &self.data_loader;
         let response = if dl.is_some() {
             execute_request_with_dl(ctx, req, self.data_loader).await?
         } else {
-            execute_raw_request(ctx, req).await?
+            execute_raw_request(ctx, req, self.request_template.oauth2.as_deref()).await?
         };
 
         if ctx.request_ctx.server.get_enable_http_validation() {
@@ -212,15 +217,19 @@ fn set_cookie_headers<Ctx: ResolverContextLike>(
 pub async fn execute_raw_request<Ctx: ResolverContextLike>(
     ctx: &EvalContext<'_, Ctx>,
     req: DynamicRequest<String>,
+    oauth2: Option<&OAuth2>,
 ) -> Result<Response<async_graphql::Value>, Error> {
-    let response = ctx
-        .request_ctx
-        .runtime
-        .http
-        .execute(req.into_request())
-        .await
-        .map_err(Error::from)?
-        .to_json()?;
+    let runtime = &ctx.request_ctx.runtime;
+    let response = match oauth2 {
+        Some(oauth2) => {
+            oauth2
+                .execute(runtime, runtime.http.as_ref(), req.into_request())
+                .await
+        }
+        None => runtime.http.execute(req.into_request()).await,
+    }
+    .map_err(Error::from)?
+    .to_json()?;
 
     Ok(response)
 }
@@ -229,8 +238,9 @@ pub async fn execute_raw_grpc_request<Ctx: ResolverContextLike>(
     ctx: &EvalContext<'_, Ctx>,
     req: Request,
     operation: &ProtobufOperation,
+    oauth2: Option<&OAuth2>,
 ) -> Result<Response<async_graphql::Value>, Error> {
-    execute_grpc_request(&ctx.request_ctx.runtime, operation, req)
+    execute_grpc_request(&ctx.request_ctx.runtime, operation, req, oauth2)
         .await
         .map_err(Error::from)
 }
@@ -1,4 +1,5 @@
 use async_graphql_value::ConstValue;
+use futures_util::stream::{BoxStream, StreamExt, TryStreamExt};
 
 use super::eval_http::{
     execute_grpc_request_with_dl, execute_raw_grpc_request, execute_raw_request,
@@ -11,6 +12,7 @@ use crate::core::data_loader::DataLoader;
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc;
 use crate::core::grpc::data_loader::GrpcDataLoader;
+use crate::core::grpc::stream::execute_grpc_stream;
 use crate::core::http::DataLoaderRequest;
 use crate::core::ir::Error;
 
@@ -40,6 +42,33 @@ where
     }
 }
 
+/// Starts the stream of a server-streaming gRPC call.
+pub async fn eval_io_stream<Ctx>(
+    io: &IO,
+    ctx: &EvalContext<'_, Ctx>,
+) -> Result<BoxStream<'static, Result<ConstValue, Error>>, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    match io {
+        IO::Grpc { req_template, .. } => {
+            let request = req_template.render(ctx)?.to_request()?;
+            let stream = execute_grpc_stream(
+                &ctx.request_ctx.runtime,
+                req_template.operation.clone(),
+                request,
+                req_template.oauth2.as_deref(),
+            )
+            .await?;
+
+            Ok(stream.map_err(Error::from).boxed())
+        }
+        _ => Err(Error::IO(
+            "Only server-streaming @grpc methods can be subscribed to".to_owned(),
+        )),
+    }
+}
+
 async fn eval_io_inner<Ctx>(io: &IO, ctx: &mut EvalContext<'_, Ctx>) -> Result<ConstValue, Error>
 where
     Ctx: ResolverContextLike + Sync,
@@ -70,14 +99,20 @@ where
                     dl_id.and_then(|dl| ctx.request_ctx.gql_data_loaders.get(dl.as_usize()));
                 execute_request_with_dl(ctx, request, data_loader).await?
             } else {
-                execute_raw_request(ctx, request).await?
+                execute_raw_request(ctx, request, req_template.oauth2.as_deref()).await?
             };
 
             set_headers(ctx, &res);
-            parse_graphql_response(ctx, res, field_name)
+            let value = parse_graphql_response(ctx, res, field_name)?;
+            match &req_template.entity {
+                Some(entity) => Ok(entity.extract(value)),
+                None => Ok(value),
+            }
         }
         IO::Grpc { req_template, dl_id, hook, .. } => {
-            let rendered = req_template.render(ctx)?;
+            let rendered = req_template
+                .render(ctx)?
+                .with_timeout(ctx.request_ctx.remaining_time());
             let worker = &ctx.request_ctx.runtime.worker;
 
             let res = if ctx.request_ctx.upstream.batch.is_some() &&
@@ -89,7 +124,13 @@ where
                 execute_grpc_request_with_dl(ctx, rendered, data_loader).await?
             } else {
                 let req = rendered.to_request()?;
-                execute_raw_grpc_request(ctx, req, &req_template.operation).await?
+                execute_raw_grpc_request(
+                    ctx,
+                    req,
+                    &req_template.operation,
+                    req_template.oauth2.as_deref(),
+                )
+                .await?
             };
 
             let res = match (worker.as_ref(), hook.as_ref()) {
@@ -133,7 +133,7 @@ impl<'a> Builder<'a> {
         selection: &SelectionSet,
         type_condition: &str,
         fragments: &HashMap<&str, &FragmentDefinition>,
-    ) -> Vec<Field<Value>> {
+    ) -> Result<Vec<Field<Value>>, BuildError> {
         let mut fields = vec![];
 
         for selection in &selection.items {
@@ -206,7 +206,7 @@ impl<'a> Builder<'a> {
                             &gql_field.selection_set.node,
                             type_of.name(),
                             fragments,
-                        );
+                        )?;
 
                         let ir = match field_def {
                             QueryField::Field((field_def, _)) => field_def.resolver.clone(),
@@ -267,6 +267,11 @@ impl<'a> Builder<'a> {
                         };
 
                         fields.push(typename_field);
+                    } else if self.index.is_inaccessible(type_condition, field_name) {
+                        return Err(BuildError::UnknownField {
+                            field_name: field_name.to_string(),
+                            type_name: type_condition.to_string(),
+                        });
                     }
                 }
                 Selection::FragmentSpread(Positioned { node: fragment_spread, .. }) => {
@@ -278,7 +283,7 @@ impl<'a> Builder<'a> {
                             &fragment.selection_set.node,
                             fragment.type_condition.node.on.node.as_str(),
                             fragments,
-                        ));
+                        )?);
                     }
                 }
                 Selection::InlineFragment(Positioned { node: fragment, .. }) => {
@@ -292,12 +297,12 @@ impl<'a> Builder<'a> {
                         &fragment.selection_set.node,
                         type_of,
                         fragments,
-                    ));
+                    )?);
                 }
             }
         }
 
-        fields
+        Ok(fields)
     }
     #[inline(always)]
     fn get_type(&self, ty: OperationType) -> Option<&str> {
@@ -348,7 +353,7 @@ impl<'a> Builder<'a> {
         let name = self
             .get_type(operation.ty)
             .ok_or(BuildError::RootOperationTypeNotDefined { operation: operation.ty })?;
-        let fields = self.iter(None, &operation.selection_set.node, name, &fragments);
+        let fields = self.iter(None, &operation.selection_set.node, name, &fragments)?;
 
         let is_introspection_query = operation.selection_set.node.items.iter().any(|f| {
             if let Selection::Field(Positioned { node: gql_field, .. }) = &f.node {
@@ -359,6 +364,23 @@ impl<'a> Builder<'a> {
             }
         });
 
+        let variable_definitions = operation
+            .variable_definitions
+            .iter()
+            .map(|definition| {
+                let definition = &definition.node;
+                let name = definition.name.node.to_string();
+                let default_value = definition
+                    .default_value
+                    .as_ref()
+                    .map(|value| format!(" = {}", value.node))
+                    .unwrap_or_default();
+                let printed = format!("${}: {}{}", name, definition.var_type.node, default_value);
+
+                (name, printed)
+            })
+            .collect();
+
         let plan = OperationPlan::new(
             name,
             fields,
@@ -366,6 +388,7 @@ impl<'a> Builder<'a> {
             self.index.clone(),
             is_introspection_query,
             Some(self.index.get_interfaces()),
+            variable_definitions,
         );
         Ok(plan)
     }
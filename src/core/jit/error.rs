@@ -14,6 +14,11 @@ pub enum BuildError {
     OperationNotFound(String),
     #[error("Operation name required in request")]
     OperationNameRequired,
+    #[error(r#"Unknown field "{field_name}" on type "{type_name}""#)]
+    UnknownField {
+        field_name: String,
+        type_name: String,
+    },
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -100,7 +100,11 @@ impl ConstValueExecutor {
 
         if is_introspection_query {
             let async_req = async_graphql::Request::from(request).only_introspection();
-            let async_resp = app_ctx.execute(async_req).await;
+            let async_resp = if app_ctx.protected_schema.is_empty() {
+                app_ctx.execute(async_req).await
+            } else {
+                app_ctx.protected_schema.execute(req_ctx, async_req).await
+            };
 
             resp.merge_with(&async_resp).into()
         } else {
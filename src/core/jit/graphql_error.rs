@@ -186,6 +186,20 @@ impl Error {
         Self { message: message.into(), extensions: None }
     }
 
+    /// Convert the error to an `async_graphql::Error` keeping the extensions,
+    /// which the blanket conversion through `Display` would drop.
+    pub fn into_async_graphql_error(self) -> async_graphql::Error {
+        let mut error = async_graphql::Error::new(self.message);
+        if let Some(extensions) = self.extensions {
+            let mut values = async_graphql::ErrorExtensionValues::default();
+            for (name, value) in extensions.0 {
+                values.set(name, value);
+            }
+            error.extensions = Some(values);
+        }
+        error
+    }
+
     /// Convert the error to a server error.
     #[must_use]
     pub fn into_server_error(self, pos: Pos) -> GraphQLError {
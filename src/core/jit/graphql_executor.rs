@@ -104,15 +104,19 @@ impl JITExecutor {
 
             let is_const = exec.plan.is_const;
             let is_protected = exec.plan.is_protected;
+            // Filtered introspection results depend on the caller, so they can
+            // neither be shared with concurrent requests nor cached.
+            let is_filtered = exec.plan.is_introspection_query
+                && self.req_ctx.server.get_enable_introspection_filter();
 
-            let response = if exec.plan.can_dedupe() {
+            let response = if exec.plan.can_dedupe() && !is_filtered {
                 self.dedupe_and_exec(exec, jit_request).await
             } else {
                 self.exec(exec, jit_request).await
             };
 
             // Cache the response if it's constant and not wrapped with protected.
-            if is_const && !is_protected {
+            if is_const && !is_protected && !is_filtered {
                 self.app_ctx
                     .const_execution_cache
                     .insert(hash, response.clone());
@@ -0,0 +1,448 @@
+use std::collections::{HashMap, HashSet};
+
+use async_graphql::dynamic::{self, SchemaBuilder};
+use async_graphql::ServerError;
+use dashmap::DashMap;
+use futures_util::future::join_all;
+
+use super::transform::resolver_auth;
+use crate::core::auth::verify::{AuthVerifier, Verify};
+use crate::core::blueprint::{Auth, Blueprint, Definition, FieldDefinition};
+use crate::core::http::RequestContext;
+
+const INACCESSIBLE: &str = "inaccessible";
+
+/// Auth requirements of the `@protected` fields of the schema. Introspection
+/// queries are executed against a schema without the fields and types whose
+/// auth providers the caller doesn't satisfy, so that they can't be selected
+/// in any way. Fields and types marked `@inaccessible` are hidden from every
+/// caller.
+#[derive(Default)]
+pub struct ProtectedSchema {
+    /// Distinct auth requirements, verified once per request
+    auths: Vec<Auth>,
+    /// Protected fields of every type, pointing to their auth requirement
+    fields: HashMap<String, HashMap<String, usize>>,
+    /// Inaccessible fields of every type
+    inaccessible_fields: HashMap<String, HashSet<String>>,
+    /// Inaccessible types
+    inaccessible_types: HashSet<String>,
+    /// Blueprint without resolvers, the introspection schemas are built from
+    blueprint: Blueprint,
+    /// Introspection schemas by the auth requirements the caller satisfies
+    schemas: DashMap<Vec<bool>, Option<dynamic::Schema>>,
+}
+
+/// Fields and types that are hidden from the current caller.
+#[derive(Default, Debug, PartialEq)]
+struct Hidden {
+    fields: HashMap<String, HashSet<String>>,
+    types: HashSet<String>,
+}
+
+impl ProtectedSchema {
+    /// Collects the `@inaccessible` fields and types of the blueprint, along
+    /// with the `@protected` fields when `protected` is set.
+    pub fn new(blueprint: &Blueprint, protected: bool) -> Self {
+        let mut schema = Self::default();
+
+        for definition in &blueprint.definitions {
+            if definition
+                .directives()
+                .iter()
+                .any(|directive| directive.name == INACCESSIBLE)
+            {
+                schema
+                    .inaccessible_types
+                    .insert(definition.name().to_string());
+            }
+
+            let fields = match definition {
+                Definition::Object(def) => &def.fields,
+                Definition::Interface(def) => &def.fields,
+                _ => continue,
+            };
+
+            if protected {
+                schema.insert(definition.name(), fields);
+            }
+
+            let inaccessible: HashSet<String> = fields
+                .iter()
+                .filter(|field| field.directives.iter().any(|d| d.name == INACCESSIBLE))
+                .map(|field| field.name.clone())
+                .collect();
+
+            if !inaccessible.is_empty() {
+                schema
+                    .inaccessible_fields
+                    .insert(definition.name().to_string(), inaccessible);
+            }
+        }
+
+        if !schema.is_empty() {
+            schema.blueprint = without_resolvers(blueprint);
+        }
+
+        schema
+    }
+
+    fn insert(&mut self, type_name: &str, fields: &[FieldDefinition]) {
+        for field in fields {
+            let Some(auth) = field.resolver.as_ref().and_then(resolver_auth) else {
+                continue;
+            };
+
+            let index = match self.auths.iter().position(|a| a == &auth) {
+                Some(index) => index,
+                None => {
+                    self.auths.push(auth);
+                    self.auths.len() - 1
+                }
+            };
+
+            self.fields
+                .entry(type_name.to_string())
+                .or_default()
+                .insert(field.name.clone(), index);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.auths.is_empty()
+            && self.inaccessible_fields.is_empty()
+            && self.inaccessible_types.is_empty()
+    }
+
+    /// Executes the introspection query against the schema of the fields and
+    /// types the caller is allowed to query.
+    pub async fn execute(
+        &self,
+        req_ctx: &RequestContext,
+        request: async_graphql::Request,
+    ) -> async_graphql::Response {
+        let verified = join_all(self.auths.iter().map(|auth| async {
+            AuthVerifier::from(auth.clone())
+                .verify(req_ctx)
+                .await
+                .to_result()
+                .is_ok()
+        }))
+        .await;
+
+        let schema = match self.schemas.get(&verified) {
+            Some(schema) => schema.clone(),
+            None => self
+                .schemas
+                .entry(verified.clone())
+                .or_insert_with(|| self.schema(&verified))
+                .clone(),
+        };
+
+        match schema {
+            Some(schema) => schema.execute(request).await,
+            None => async_graphql::Response::from_errors(vec![ServerError::new(
+                "Introspection is not available",
+                None,
+            )]),
+        }
+    }
+
+    fn schema(&self, verified: &[bool]) -> Option<dynamic::Schema> {
+        let blueprint = self.hide(self.hidden(verified));
+
+        SchemaBuilder::from(&blueprint)
+            .finish()
+            .inspect_err(|err| tracing::error!("Failed to build the introspection schema: {err}"))
+            .ok()
+    }
+
+    fn hidden(&self, verified: &[bool]) -> Hidden {
+        let mut hidden = Hidden {
+            fields: self.inaccessible_fields.clone(),
+            types: self.inaccessible_types.clone(),
+        };
+
+        for (type_name, fields) in &self.fields {
+            hidden.fields.entry(type_name.clone()).or_default().extend(
+                fields
+                    .iter()
+                    .filter(|(_, index)| !verified[**index])
+                    .map(|(field, _)| field.clone()),
+            );
+        }
+
+        hidden
+    }
+
+    /// Removes the hidden fields and types from the blueprint, along with the
+    /// fields returning hidden types and the types left without fields.
+    fn hide(&self, mut hidden: Hidden) -> Blueprint {
+        let query = self.blueprint.query();
+
+        loop {
+            let mut changed = false;
+
+            for definition in &self.blueprint.definitions {
+                let name = definition.name();
+                if hidden.types.contains(name) {
+                    continue;
+                }
+
+                let fields = match definition {
+                    Definition::Object(def) => &def.fields,
+                    Definition::Interface(def) => &def.fields,
+                    Definition::Union(def) => {
+                        if def.types.iter().all(|type_| hidden.types.contains(type_)) {
+                            hidden.types.insert(name.to_string());
+                            changed = true;
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                let hidden_fields = hidden.fields.entry(name.to_string()).or_default();
+                for field in fields {
+                    if hidden.types.contains(field.of_type.name())
+                        && hidden_fields.insert(field.name.clone())
+                    {
+                        changed = true;
+                    }
+                }
+
+                let is_empty = fields
+                    .iter()
+                    .all(|field| hidden_fields.contains(&field.name));
+                if is_empty && name != query {
+                    hidden.types.insert(name.to_string());
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let mut blueprint = self.blueprint.clone();
+        if blueprint
+            .mutation()
+            .is_some_and(|mutation| hidden.types.contains(&mutation))
+        {
+            blueprint.schema.mutation = None;
+        }
+
+        blueprint
+            .definitions
+            .retain(|definition| !hidden.types.contains(definition.name()));
+
+        for definition in blueprint.definitions.iter_mut() {
+            let (name, fields, implements) = match definition {
+                Definition::Object(def) => (&def.name, &mut def.fields, &mut def.implements),
+                Definition::Interface(def) => (&def.name, &mut def.fields, &mut def.implements),
+                Definition::Union(def) => {
+                    def.types.retain(|type_| !hidden.types.contains(type_));
+                    continue;
+                }
+                _ => continue,
+            };
+
+            implements.retain(|interface| !hidden.types.contains(interface));
+            if let Some(hidden_fields) = hidden.fields.get(name) {
+                fields.retain(|field| !hidden_fields.contains(&field.name));
+            }
+        }
+
+        blueprint
+    }
+}
+
+/// Introspection doesn't evaluate resolvers, they're dropped so that the
+/// schemas don't hold on to them.
+fn without_resolvers(blueprint: &Blueprint) -> Blueprint {
+    let mut blueprint = blueprint.clone();
+
+    for definition in blueprint.definitions.iter_mut() {
+        let fields = match definition {
+            Definition::Object(def) => &mut def.fields,
+            Definition::Interface(def) => &mut def.fields,
+            _ => continue,
+        };
+
+        for field in fields.iter_mut() {
+            field.resolver = None;
+        }
+    }
+
+    blueprint
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::{Basic, Provider};
+    use crate::core::config::{Config, ConfigModule};
+
+    const SDL: &str = r#"
+        schema {
+            query: Query
+        }
+
+        type Query {
+            users: [User] @expr(body: [])
+            secret: Secret @expr(body: {value: "secret"})
+        }
+
+        type User {
+            name: String
+        }
+
+        type Secret {
+            value: String
+        }
+    "#;
+
+    fn blueprint() -> Blueprint {
+        let config = Config::from_sdl(SDL).to_result().unwrap();
+        Blueprint::try_from(&ConfigModule::from(config)).unwrap()
+    }
+
+    fn protected_schema() -> ProtectedSchema {
+        let auth = Auth::Provider(Provider::Basic(Basic { htpasswd: "".to_string() }));
+        ProtectedSchema {
+            auths: vec![auth],
+            fields: HashMap::from([
+                (
+                    "Query".to_string(),
+                    HashMap::from([("secret".to_string(), 0)]),
+                ),
+                (
+                    "Secret".to_string(),
+                    HashMap::from([("value".to_string(), 0)]),
+                ),
+            ]),
+            blueprint: without_resolvers(&blueprint()),
+            ..Default::default()
+        }
+    }
+
+    async fn execute(schema: &ProtectedSchema, query: &str) -> serde_json::Value {
+        let request = async_graphql::Request::new(query).only_introspection();
+        let response = schema.execute(&RequestContext::default(), request).await;
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    fn names(value: &serde_json::Value) -> Vec<&str> {
+        let mut names = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|value| value.as_object()?.values().next()?.as_str())
+            .filter(|name| !name.starts_with("__"))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_hidden_unauthorized() {
+        let schema = protected_schema();
+
+        assert_eq!(
+            schema.hidden(&[false]),
+            Hidden {
+                fields: HashMap::from([
+                    ("Query".to_string(), HashSet::from(["secret".to_string()])),
+                    ("Secret".to_string(), HashSet::from(["value".to_string()])),
+                ]),
+                types: HashSet::new(),
+            }
+        );
+        assert_eq!(
+            schema.hidden(&[true]),
+            Hidden {
+                fields: HashMap::from([
+                    ("Query".to_string(), HashSet::new()),
+                    ("Secret".to_string(), HashSet::new()),
+                ]),
+                types: HashSet::new(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_aliases() {
+        let data = execute(
+            &protected_schema(),
+            "{ __schema { types { n: name fields { x: name } } } }",
+        )
+        .await;
+        let types = &data["__schema"]["types"];
+        let query = types
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|type_| type_["n"] == "Query")
+            .unwrap();
+
+        assert_eq!(names(&query["fields"]), vec!["users"]);
+        assert_eq!(
+            names(types),
+            vec!["Boolean", "Float", "ID", "Int", "Query", "String", "User"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_name() {
+        let data = execute(
+            &protected_schema(),
+            "{ __type(name: \"Query\") { fields { type { kind ofType { name } } } } }",
+        )
+        .await;
+
+        assert_eq!(
+            data,
+            json!({
+                "__type": {
+                    "fields": [{ "type": { "kind": "LIST", "ofType": { "name": "User" } } }]
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_hidden_type() {
+        let data = execute(&protected_schema(), "{ __type(name: \"Secret\") { name } }").await;
+
+        assert_eq!(data, json!({ "__type": null }));
+    }
+
+    #[tokio::test]
+    async fn test_execute_inaccessible() {
+        let schema = ProtectedSchema {
+            inaccessible_fields: HashMap::from([(
+                "Query".to_string(),
+                HashSet::from(["users".to_string()]),
+            )]),
+            blueprint: without_resolvers(&blueprint()),
+            ..Default::default()
+        };
+        let data = execute(&schema, "{ __schema { types { name } } }").await;
+
+        assert_eq!(
+            names(&data["__schema"]["types"]),
+            vec!["Boolean", "Float", "ID", "Int", "Query", "Secret", "String"]
+        );
+    }
+
+    #[test]
+    fn test_without_protected_fields() {
+        assert!(ProtectedSchema::new(&blueprint(), true).is_empty());
+    }
+}
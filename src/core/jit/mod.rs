@@ -17,11 +17,13 @@ mod response;
 mod builder;
 pub mod fixtures;
 mod graphql_executor;
+mod introspection;
 
 // Public Exports
 pub use error::*;
 pub use exec_const::*;
 pub use graphql_executor::*;
+pub use introspection::ProtectedSchema;
 pub use model::*;
 pub use request::*;
 pub use response::*;
@@ -318,6 +318,9 @@ pub struct OperationPlan<Input> {
     pub selection: Vec<Field<Input>>,
     pub before: Option<IR>,
     pub interfaces: Option<HashSet<String>>,
+    /// Definitions of the variables of the operation keyed by their names,
+    /// e.g. `$id: ID!`.
+    pub variable_definitions: HashMap<String, String>,
 }
 
 impl<Input> OperationPlan<Input> {
@@ -343,6 +346,7 @@ impl<Input> OperationPlan<Input> {
             min_cache_ttl: self.min_cache_ttl,
             before: self.before,
             interfaces: None,
+            variable_definitions: self.variable_definitions,
         })
     }
 }
@@ -356,6 +360,7 @@ impl<Input> OperationPlan<Input> {
         index: Arc<Index>,
         is_introspection_query: bool,
         interfaces: Option<HashSet<String>>,
+        variable_definitions: HashMap<String, String>,
     ) -> Self
     where
         Input: Clone,
@@ -372,6 +377,7 @@ impl<Input> OperationPlan<Input> {
             min_cache_ttl: None,
             before: Default::default(),
             interfaces,
+            variable_definitions,
         }
     }
 
@@ -37,6 +37,15 @@ impl<A: Debug> Transform for AuthPlanner<A> {
     }
 }
 
+/// Collects the auth requirements of a resolver, combining every
+/// IR::Protect of its chain the same way the planner does for an operation.
+pub fn resolver_auth(ir: &IR) -> Option<Auth> {
+    let mut auth = Vec::new();
+    update_ir(&mut ir.clone(), &mut auth);
+
+    auth.into_iter().reduce(|a, b| a.and(b))
+}
+
 /// Used to recursively update the field ands its selections to remove
 /// IR::Protected
 fn update_field<A>(auth: &mut Vec<Auth>, field: &mut Field<A>) {
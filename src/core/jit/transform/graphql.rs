@@ -1,16 +1,16 @@
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::Infallible;
-use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 
+use async_graphql_value::Value;
 use tailcall_valid::Valid;
 
 use crate::core::document::print_directives;
+use crate::core::graphql::{EntitySelection, RequestTemplate, Selection};
 use crate::core::ir::model::{IO, IR};
 use crate::core::jit::{Field, OperationPlan};
-use crate::core::json::JsonLikeOwned;
-use crate::core::{Mustache, Transform};
+use crate::core::Transform;
 
 #[derive(Default)]
 pub struct GraphQL<A>(PhantomData<A>);
@@ -21,30 +21,183 @@ impl<A> GraphQL<A> {
     }
 }
 
-fn compute_selection_set<A: Display + Debug + JsonLikeOwned>(
-    base_field: &mut [Field<A>],
+fn compute_selection_set(
+    base_field: &mut [Field<Value>],
+    parent_type: &str,
     interfaces: &HashSet<String>,
+    variable_definitions: &HashMap<String, String>,
 ) {
     for field in base_field.iter_mut() {
+        if let Some(url) = upstream_url(field) {
+            let via = (parent_type, field.name.as_str());
+            select_along(&mut field.selection, &url, field.type_of.name(), Some(via));
+        }
+
+        let mut is_forwarded = false;
         if let Some(ir) = field.ir.as_mut() {
             ir.modify_io(&mut |io| {
                 if let IO::GraphQL { req_template, .. } = io {
+                    let mut variables = BTreeSet::new();
                     if let Some(v) = format_selection_set(
                         field.selection.iter(),
                         interfaces,
-                        interfaces.contains(field.type_of.name()),
+                        field.type_of.name(),
+                        &mut variables,
                     ) {
-                        req_template.selection = Some(Mustache::parse(&v).into());
+                        req_template.selection = Some(Selection::Resolved(v));
                     }
+                    req_template.variables = variables
+                        .into_iter()
+                        .filter_map(|name| {
+                            let definition = variable_definitions.get(&name)?.clone();
+                            Some((name, definition))
+                        })
+                        .collect();
+                    is_forwarded = true;
                 }
             });
         }
-        compute_selection_set(field.selection.as_mut(), interfaces);
+        if is_forwarded {
+            read_aliases(&mut field.selection);
+        }
+        compute_selection_set(
+            field.selection.as_mut(),
+            field.type_of.name(),
+            interfaces,
+            variable_definitions,
+        );
+    }
+
+    group_entity_queries(base_field);
+}
+
+/// URL of the GraphQL upstream the value of the field comes from.
+fn upstream_url(field: &mut Field<Value>) -> Option<String> {
+    let mut url = None;
+    if let Some(ir) = field.ir.as_mut() {
+        // the last query of a pipe returns the value
+        ir.modify_io(&mut |io| {
+            if let IO::GraphQL { req_template, .. } = io {
+                url = Some(req_template.url.clone());
+            }
+        });
+    }
+
+    url
+}
+
+/// Fields of entities that the upstream at `url` resolves, or provides
+/// through the field `via`, are selected by the query of their parent instead
+/// of being fetched with a query of their own.
+fn select_along(
+    selection: &mut [Field<Value>],
+    url: &str,
+    parent_type: &str,
+    via: Option<(&str, &str)>,
+) {
+    for field in selection.iter_mut() {
+        if let Some(IR::IO(IO::GraphQL { req_template, .. })) = &field.ir {
+            let is_selected = req_template
+                .entity
+                .iter()
+                .flat_map(|entity| &entity.sources)
+                .any(|source| {
+                    source.url == url
+                        && source
+                            .provided_by
+                            .as_ref()
+                            .map_or(true, |(type_name, name)| {
+                                via == Some((type_name.as_str(), name.as_str()))
+                            })
+                });
+            if is_selected {
+                field.ir = None;
+            }
+        }
+
+        if is_forwarded(field) {
+            let parent_type = field.parent_fragment.as_deref().unwrap_or(parent_type);
+            select_along(
+                &mut field.selection,
+                url,
+                field.type_of.name(),
+                Some((parent_type, &field.name)),
+            );
+        }
+    }
+}
+
+/// Sibling queries of the same entity sent to the same upstream, e.g. the
+/// `_entities` queries of fields owned by one subgraph, select the fields of
+/// each other. They're deduplicated, so one request fetches all of them.
+fn group_entity_queries(selection: &mut [Field<Value>]) {
+    let mut groups: Vec<(&RequestTemplate, Vec<usize>)> = vec![];
+    for (index, field) in selection.iter().enumerate() {
+        let Some(IR::IO(IO::GraphQL { req_template, dedupe: true, .. })) = &field.ir else {
+            continue;
+        };
+        let Some(entity) = &req_template.entity else {
+            continue;
+        };
+        // arguments are rendered from the context of each field
+        if !matches!(&entity.selection, EntitySelection::Field { arguments, .. } if arguments.is_empty())
+        {
+            continue;
+        }
+
+        let same_query = |other: &RequestTemplate| {
+            other.url == req_template.url
+                && other.operation_name == req_template.operation_name
+                && other.operation_arguments == req_template.operation_arguments
+                && other.entity.as_ref().map(|entity| &entity.type_name) == Some(&entity.type_name)
+        };
+        match groups.iter_mut().find(|(other, _)| same_query(other)) {
+            Some((_, indices)) => indices.push(index),
+            None => groups.push((req_template, vec![index])),
+        }
+    }
+
+    let groups = groups
+        .into_iter()
+        .map(|(_, indices)| indices)
+        .filter(|indices| indices.len() > 1)
+        .collect::<Vec<_>>();
+
+    for indices in groups {
+        let mut fields = vec![];
+        let mut variables = vec![];
+        for index in indices.iter() {
+            let Some(IR::IO(IO::GraphQL { req_template, .. })) = &selection[*index].ir else {
+                continue;
+            };
+            let name = &selection[*index].name;
+            match &req_template.selection {
+                Some(Selection::Resolved(selection_set)) => {
+                    fields.push(format!("{name} {selection_set}"))
+                }
+                _ => fields.push(name.clone()),
+            }
+            for variable in req_template.variables.iter() {
+                if !variables.contains(variable) {
+                    variables.push(variable.clone());
+                }
+            }
+        }
+        let group = fields.join(" ");
+
+        for index in indices {
+            if let Some(IR::IO(IO::GraphQL { req_template, .. })) = &mut selection[index].ir {
+                req_template.variables = variables.clone();
+                if let Some(entity) = req_template.entity.as_mut() {
+                    entity.group = Some(group.clone());
+                }
+            }
+        }
     }
 }
 
-impl<A: Display + Debug + JsonLikeOwned + Clone> Transform for GraphQL<A> {
-    type Value = OperationPlan<A>;
+impl Transform for GraphQL<Value> {
+    type Value = OperationPlan<Value>;
     type Error = Infallible;
 
     fn transform(&self, mut plan: Self::Value) -> Valid<Self::Value, Self::Error> {
@@ -52,22 +205,143 @@ impl<A: Display + Debug + JsonLikeOwned + Clone> Transform for GraphQL<A> {
             Some(ref interfaces) => interfaces,
             None => &HashSet::new(),
         };
-        compute_selection_set(&mut plan.selection, interfaces);
+        compute_selection_set(
+            &mut plan.selection,
+            &plan.root_name,
+            interfaces,
+            &plan.variable_definitions,
+        );
 
         Valid::succeed(plan)
     }
 }
 
-fn format_selection_set<'a, A: 'a + Display + JsonLikeOwned>(
-    selection_set: impl Iterator<Item = &'a Field<A>>,
+/// Fields resolved from the upstream response, the ones with their own
+/// resolvers are queried separately.
+fn is_forwarded(field: &Field<Value>) -> bool {
+    !matches!(
+        &field.ir,
+        Some(IR::IO(_)) | Some(IR::Dynamic(_)) | Some(IR::Pipe(..))
+    )
+}
+
+/// Aliases are forwarded as they are, except for the fields read by a
+/// resolver of their own, e.g. the ones renamed with `@modify`.
+fn is_aliased(field: &Field<Value>) -> bool {
+    field.ir.is_none() && field.name != "__typename" && field.name != field.output_name
+}
+
+/// The upstream response has the values of the aliased fields under their
+/// aliases, so that's where they're read from.
+fn read_aliases(selection: &mut [Field<Value>]) {
+    for field in selection.iter_mut().filter(|field| is_forwarded(field)) {
+        if is_aliased(field) {
+            field.name = field.output_name.clone();
+        }
+        read_aliases(&mut field.selection);
+    }
+}
+
+/// Collects the names of the variables used in the value.
+fn collect_variables(value: &Value, variables: &mut BTreeSet<String>) {
+    match value {
+        Value::Variable(name) => {
+            variables.insert(name.to_string());
+        }
+        Value::List(list) => list
+            .iter()
+            .for_each(|value| collect_variables(value, variables)),
+        Value::Object(object) => object
+            .values()
+            .for_each(|value| collect_variables(value, variables)),
+        _ => {}
+    }
+}
+
+/// Fields of the parent value the `_entities` queries and the lookups of
+/// stitched types read their keys from, e.g. `id` for `userById(id:
+/// {{.value.id}})`.
+#[derive(Default, Debug)]
+struct EntityKeys(BTreeMap<String, EntityKeys>);
+
+impl EntityKeys {
+    fn collect_ir(&mut self, ir: &IR) {
+        match ir {
+            IR::IO(io) => self.collect_io(io),
+            IR::Cache(cache) => self.collect_io(&cache.io),
+            IR::Path(ir, _) | IR::Protect(_, ir) | IR::Discriminate(_, ir) => self.collect_ir(ir),
+            IR::Pipe(first, second) => {
+                self.collect_ir(first);
+                self.collect_ir(second);
+            }
+            IR::Merge(irs) => irs.iter().for_each(|ir| self.collect_ir(ir)),
+            IR::Map(map) => self.collect_ir(&map.input),
+            _ => {}
+        }
+    }
+
+    fn collect_io(&mut self, io: &IO) {
+        let IO::GraphQL { req_template, .. } = io else {
+            return;
+        };
+        if req_template.entity.is_none() {
+            return;
+        }
+
+        let arguments = req_template.operation_arguments.iter().flatten();
+        for (_, mustache) in arguments {
+            for expression in mustache.expression_segments() {
+                if let Some((root, path)) = expression.split_first() {
+                    if root == "value" {
+                        self.insert(path);
+                    }
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, path: &[String]) {
+        if let Some((name, path)) = path.split_first() {
+            self.0.entry(name.clone()).or_default().insert(path);
+        }
+    }
+
+    fn format(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(name, keys)| {
+                if keys.0.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{} {{ {} }}", name, keys.format().join(" "))
+                }
+            })
+            .collect()
+    }
+}
+
+fn format_selection_set<'a>(
+    selection_set: impl Iterator<Item = &'a Field<Value>> + Clone,
     interfaces: &HashSet<String>,
-    is_parent_interface: bool,
+    parent_type: &str,
+    variables: &mut BTreeSet<String>,
 ) -> Option<String> {
-    let mut fragments_fields = HashMap::new();
+    let is_parent_interface = interfaces.contains(parent_type);
+    let mut fragments_fields = BTreeMap::new();
     let mut normal_fields = vec![];
     let mut is_typename_requested = false;
+
+    // the keys of the entities are selected even if they aren't queried
+    let mut entity_keys = EntityKeys::default();
+    for field in selection_set.clone() {
+        if let Some(ir) = &field.ir {
+            entity_keys.collect_ir(ir);
+        }
+    }
+    let mut selected = HashSet::new();
+
     let set = selection_set
-        .filter(|field| !matches!(&field.ir, Some(IR::IO(_)) | Some(IR::Dynamic(_))))
+        .filter(|field| is_forwarded(field))
         .map(|field| {
             // handle @modify directive scenario.
             let field_name = if let Some(IR::ContextPath(data)) = &field.ir {
@@ -75,12 +349,15 @@ fn format_selection_set<'a, A: 'a + Display + JsonLikeOwned>(
             } else {
                 field.name.to_string()
             };
-            let is_this_field_interface = interfaces.contains(field.type_of.name());
             let formatted_selection_fields =
-                format_selection_field(field, &field_name, interfaces, is_this_field_interface);
+                format_selection_field(field, &field_name, interfaces, variables);
             is_typename_requested = is_typename_requested || field_name == "__typename";
+            if !is_aliased(field) {
+                selected.insert(field_name);
+            }
+            // fields of fragments on other types are only selected on them
             match &field.parent_fragment {
-                Some(fragment) if is_parent_interface => {
+                Some(fragment) if is_parent_interface || fragment != parent_type => {
                     fragments_fields
                         .entry(fragment.to_owned())
                         .or_insert_with(Vec::new)
@@ -93,9 +370,11 @@ fn format_selection_set<'a, A: 'a + Display + JsonLikeOwned>(
         })
         .collect::<Vec<_>>();
 
-    if set.is_empty() {
+    entity_keys.0.retain(|name, _| !selected.contains(name));
+    if set.is_empty() && entity_keys.0.is_empty() {
         return None;
     }
+    normal_fields.extend(entity_keys.format());
 
     let fragments_set: Vec<String> = fragments_fields
         .into_iter()
@@ -105,26 +384,49 @@ fn format_selection_set<'a, A: 'a + Display + JsonLikeOwned>(
         .collect();
 
     //Don't force user to query the type and get it automatically
-    if is_parent_interface && !is_typename_requested {
+    if (is_parent_interface || !fragments_set.is_empty()) && !is_typename_requested {
         normal_fields.push("__typename".to_owned());
     }
     normal_fields.extend(fragments_set);
     Some(format!("{{ {} }}", normal_fields.join(" ")))
 }
 
-fn format_selection_field<A: Display + JsonLikeOwned>(
-    field: &Field<A>,
+fn format_selection_field(
+    field: &Field<Value>,
     name: &str,
     interfaces: &HashSet<String>,
-    is_parent_interface: bool,
+    variables: &mut BTreeSet<String>,
 ) -> String {
-    let arguments = format_selection_field_arguments(field);
-    let selection_set =
-        format_selection_set(field.selection.iter(), interfaces, is_parent_interface);
+    let arguments = format_selection_field_arguments(field, variables);
+    let selection_set = format_selection_set(
+        field.selection.iter(),
+        interfaces,
+        field.type_of.name(),
+        variables,
+    );
+
+    let mut output = if is_aliased(field) {
+        format!("{}: {}{}", field.output_name, name, arguments)
+    } else {
+        format!("{}{}", name, arguments)
+    };
 
-    let mut output = format!("{}{}", name, arguments);
+    // the conditions are checked again by the upstream, so it skips the fields
+    // as well
+    let conditions = [("skip", &field.skip), ("include", &field.include)];
+    for (directive, variable) in conditions {
+        if let Some(variable) = variable {
+            variables.insert(variable.as_str().to_string());
+            output.push_str(&format!(" @{}(if: ${})", directive, variable.as_str()));
+        }
+    }
 
     if !field.directives.is_empty() {
+        for directive in field.directives.iter() {
+            for (_, value) in directive.arguments.iter() {
+                collect_variables(value, variables);
+            }
+        }
         let directives = print_directives(field.directives.iter());
 
         if !directives.is_empty() {
@@ -141,12 +443,18 @@ fn format_selection_field<A: Display + JsonLikeOwned>(
     output
 }
 
-fn format_selection_field_arguments<A: Display>(field: &Field<A>) -> Cow<'static, str> {
+fn format_selection_field_arguments(
+    field: &Field<Value>,
+    variables: &mut BTreeSet<String>,
+) -> Cow<'static, str> {
     let arguments = field
         .args
         .iter()
-        .filter(|a| a.value.is_some())
-        .map(|arg| arg.to_string())
+        .filter_map(|arg| {
+            let value = arg.value.as_ref()?;
+            collect_variables(value, variables);
+            Some(arg.to_string())
+        })
         .collect::<Vec<_>>()
         .join(",");
 
@@ -156,3 +464,257 @@ fn format_selection_field_arguments<A: Display>(field: &Field<A>) -> Cow<'static
         Cow::Owned(format!("({})", arguments.escape_default()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use pretty_assertions::assert_eq;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::stitching::{self, Service};
+    use crate::core::config::supergraph::{compose, Subgraph};
+    use crate::core::config::{Config, ConfigModule, GraphQLOperationType, KeyValue};
+    use crate::core::graphql::{Entity, EntitySelection, RequestTemplate};
+    use crate::core::jit::transform::InputResolver;
+    use crate::core::jit::{Request, Variables};
+
+    fn lookup(entity: Option<Entity>) -> IR {
+        let args = [("id", "{{.value.id}}"), ("org", "{{.value.org.id}}")]
+            .map(|(key, value)| KeyValue { key: key.to_string(), value: value.to_string() })
+            .to_vec();
+        let req_template = RequestTemplate::new(
+            "http://localhost/graphql".to_string(),
+            &GraphQLOperationType::Query,
+            "user",
+            Some(&args),
+            vec![],
+            Default::default(),
+        )
+        .unwrap()
+        .entity(entity);
+
+        IR::IO(IO::GraphQL {
+            req_template,
+            field_name: "user".to_string(),
+            batch: false,
+            dl_id: None,
+            dedupe: false,
+        })
+    }
+
+    #[test]
+    fn test_entity_keys() {
+        let entity = Entity {
+            type_name: None,
+            selection: EntitySelection::Fields(String::new()),
+            sources: vec![],
+            group: None,
+        };
+        let mut keys = EntityKeys::default();
+        keys.collect_ir(&lookup(Some(entity)).pipe(lookup(None)));
+
+        assert_eq!(keys.format(), vec!["id", "org { id }"]);
+    }
+
+    #[test]
+    fn test_entity_keys_without_entity() {
+        let mut keys = EntityKeys::default();
+        keys.collect_ir(&lookup(None));
+
+        assert!(keys.format().is_empty());
+    }
+
+    fn forwarded<A>(field: &Field<A>) -> RequestTemplate {
+        let mut forwarded = None;
+        field.ir.clone().unwrap().modify_io(&mut |io| {
+            if let IO::GraphQL { req_template, .. } = io {
+                forwarded = Some(req_template.clone());
+            }
+        });
+
+        forwarded.unwrap()
+    }
+
+    #[test]
+    fn test_forwarded_selection() {
+        let config = Config::from_sdl(
+            r#"
+            schema { query: Query }
+            type Query {
+              user(id: ID!): User
+                @graphQL(url: "http://upstream/graphql", name: "user", args: [{key: "id", value: "{{.args.id}}"}])
+            }
+            type User { id: ID! name: String pic(size: Int): String feed: [Item] }
+            union Item = Post | Photo
+            type Post { title: String }
+            type Photo { url: String }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let request = Request::new(
+            r#"
+            query($id: ID!, $size: Int = 64, $withFeed: Boolean!) {
+              user(id: $id) {
+                id
+                small: pic(size: $size)
+                big: pic(size: 1024)
+                feed @include(if: $withFeed) { ... on Post { title } ... on Photo { url } }
+              }
+            }
+            "#,
+        );
+        let plan = request.create_plan(&blueprint).unwrap();
+        let user = &plan.selection[0];
+
+        let req_template = forwarded(user);
+
+        let Some(Selection::Resolved(selection)) = req_template.selection else {
+            panic!("selection should be resolved");
+        };
+        assert_eq!(
+            selection,
+            "{ id small: pic(size: $size) big: pic(size: 1024) feed @include(if: $withFeed) { __typename ... on Photo { url } ... on Post { title } } }"
+        );
+        assert_eq!(
+            req_template.variables,
+            vec![
+                ("size".to_string(), "$size: Int = 64".to_string()),
+                ("withFeed".to_string(), "$withFeed: Boolean!".to_string()),
+            ]
+        );
+        // the aliased fields are read from the response by their aliases
+        assert_eq!(
+            user.selection
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "small", "big", "feed"]
+        );
+
+        // the values of the variables are sent along with the query
+        let variables = Variables::from_iter([
+            ("id".to_string(), ConstValue::from(1)),
+            ("size".to_string(), ConstValue::from(32)),
+            ("withFeed".to_string(), ConstValue::Boolean(true)),
+        ]);
+        let plan = InputResolver::new(plan).resolve_input(&variables).unwrap();
+        assert_eq!(
+            forwarded(&plan.selection[0]).variable_values,
+            Some(r#"{"size":32,"withFeed":true}"#.to_string())
+        );
+    }
+
+    fn entity<A>(field: &Field<A>) -> Option<Entity> {
+        field.ir.as_ref().map(|_| forwarded(field).entity.unwrap())
+    }
+
+    #[test]
+    fn test_entity_fields() {
+        let subgraphs = [
+            (
+                "products",
+                r#"
+                type Query { products: [Product] }
+                type Product @key(fields: "upc") { upc: String! name: String }
+                "#,
+            ),
+            (
+                "reviews",
+                r#"
+                type Review { body: String product: Product @provides(fields: "name") }
+                type Product @key(fields: "upc") {
+                    upc: String!
+                    name: String @external
+                    reviews: [Review]
+                    rating: Int
+                }
+                "#,
+            ),
+        ]
+        .map(|(name, sdl)| Subgraph {
+            name: name.to_string(),
+            url: format!("http://{name}/graphql"),
+            sdl: sdl.to_string(),
+        });
+        let config = compose(&subgraphs).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let request =
+            Request::new("{ products { name reviews { body product { name } } rating } }");
+        let plan = request.create_plan(&blueprint).unwrap();
+        let products = &plan.selection[0];
+
+        let Some(Selection::Resolved(selection)) = forwarded(products).selection else {
+            panic!("selection should be resolved");
+        };
+        // the field owned by the subgraph is selected along with the entity
+        assert_eq!(selection, "{ name upc }");
+        assert!(products.selection[0].ir.is_none());
+
+        // the fields owned by the same subgraph are fetched by one query
+        let reviews = entity(&products.selection[1]).unwrap();
+        let rating = entity(&products.selection[2]).unwrap();
+        let group = "reviews { body product { name } } rating";
+        assert_eq!(reviews.group.as_deref(), Some(group));
+        assert_eq!(rating.group.as_deref(), Some(group));
+
+        // the field provided by the subgraph isn't fetched again
+        let product = &products.selection[1].selection[1];
+        assert!(entity(&product.selection[0]).is_none());
+    }
+
+    #[test]
+    fn test_lookup_fields() {
+        let services = [
+            (
+                "users",
+                r#"
+                schema { query: Query }
+                type Query {
+                  user(id: ID!): User
+                    @graphQL(url: "http://users/graphql", name: "user", args: [{key: "id", value: "{{.args.id}}"}])
+                }
+                type User { id: ID! name: String email: String }
+                "#,
+            ),
+            (
+                "posts",
+                r#"
+                schema { query: Query }
+                type Query {
+                  author(id: ID!): User
+                    @graphQL(url: "http://posts/graphql", name: "author", args: [{key: "id", value: "{{.args.id}}"}])
+                }
+                type User { id: ID! posts: [Post] }
+                type Post { title: String }
+                "#,
+            ),
+        ]
+        .map(|(name, sdl)| Service {
+            name: name.to_string(),
+            url: format!("http://{name}/graphql"),
+            config: Config::from_sdl(sdl).to_result().unwrap(),
+        });
+        let config = stitching::compose(&services).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let request = Request::new("{ author(id: 1) { name email posts { title } } }");
+        let plan = request.create_plan(&blueprint).unwrap();
+        let author = &plan.selection[0];
+
+        let Some(Selection::Resolved(selection)) = forwarded(author).selection else {
+            panic!("selection should be resolved");
+        };
+        // the field of the service returning the type isn't looked up
+        assert_eq!(selection, "{ posts { title } id }");
+        assert!(author.selection[2].ir.is_none());
+
+        // the fields of the other service are looked up by one query
+        let name = entity(&author.selection[0]).unwrap();
+        let email = entity(&author.selection[1]).unwrap();
+        assert_eq!(name.group.as_deref(), Some("name email"));
+        assert_eq!(email.group.as_deref(), Some("name email"));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt::Display;
 
 use async_graphql_value::{ConstValue, Value};
@@ -49,7 +50,12 @@ impl<Input> InputResolver<Input> {
 impl<Input, Output> InputResolver<Input>
 where
     Input: Clone + std::fmt::Debug,
-    Output: Clone + JsonLikeOwned + TryFrom<serde_json::Value> + std::fmt::Debug + Display,
+    Output: Clone
+        + JsonLikeOwned
+        + TryFrom<serde_json::Value>
+        + serde::Serialize
+        + std::fmt::Debug
+        + Display,
     Input: InputResolvable<Output = Output>,
     <Output as TryFrom<serde_json::Value>>::Error: std::fmt::Debug,
 {
@@ -87,10 +93,12 @@ where
             interfaces: None,
             selection,
             before: self.plan.before,
+            variable_definitions: self.plan.variable_definitions,
         })
     }
 
-    // resolves the variables in selection set mustache template for graphql query.
+    // resolves the variables in selection set mustache template for graphql query
+    // and sets the values of the variables forwarded along with it.
     fn resolve_graphql_selection_set(
         base_field: &mut [Field<Output>],
         variables: &Variables<Output>,
@@ -102,6 +110,14 @@ where
                         if let Some(selection) = req_template.selection.take() {
                             req_template.selection = Some(selection.resolve(variables));
                         }
+                        if !req_template.variables.is_empty() {
+                            let values = req_template
+                                .variables
+                                .iter()
+                                .filter_map(|(name, _)| Some((name, variables.get(name)?)))
+                                .collect::<BTreeMap<_, _>>();
+                            req_template.variable_values = serde_json::to_string(&values).ok();
+                        }
                     }
                 });
             }
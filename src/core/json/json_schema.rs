@@ -248,6 +248,10 @@ impl TryFrom<&MessageDescriptor> for JsonSchema {
             return Ok(JsonSchema::Any);
         }
 
+        if let Some(schema) = well_known_schema(value) {
+            return Ok(schema);
+        }
+
         let mut map = BTreeMap::new();
         let fields = value.fields();
 
@@ -268,6 +272,24 @@ impl TryFrom<&MessageDescriptor> for JsonSchema {
     }
 }
 
+/// Well-known types have their own proto3 JSON representation instead of the
+/// object of their fields.
+/// see https://protobuf.dev/programming-guides/proto3/#json
+fn well_known_schema(message: &MessageDescriptor) -> Option<JsonSchema> {
+    let schema = match message.full_name().strip_prefix("google.protobuf.")? {
+        "Timestamp" | "Duration" | "FieldMask" => JsonSchema::Str,
+        "DoubleValue" | "FloatValue" | "Int32Value" | "UInt32Value" => JsonSchema::Num,
+        // 64-bit integers are encoded as strings
+        "Int64Value" | "UInt64Value" => JsonSchema::Str,
+        "BoolValue" => JsonSchema::Bool,
+        "StringValue" | "BytesValue" => JsonSchema::Str,
+        "Struct" | "Value" | "ListValue" => JsonSchema::Any,
+        _ => return None,
+    };
+
+    Some(schema)
+}
+
 impl TryFrom<&EnumDescriptor> for JsonSchema {
     type Error = tailcall_valid::ValidationError<String>;
 
@@ -456,7 +478,7 @@ mod tests {
         let service = file.find_service(&grpc_method)?;
         let operation = service.find_operation(&grpc_method)?;
 
-        let schema = JsonSchema::try_from(&operation.output_type)?;
+        let schema = JsonSchema::try_from(&operation.output_type())?;
 
         assert_eq!(
             schema,
@@ -484,6 +506,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_well_known_types_conversion() -> anyhow::Result<()> {
+        let grpc_method = GrpcMethod::try_from("movies.Example.SearchMoviesByCast").unwrap();
+
+        let file = ProtobufSet::from_proto_file(get_proto_file(protobuf::MOVIES).await?)?;
+        let service = file.find_service(&grpc_method)?;
+        let operation = service.find_operation(&grpc_method)?;
+
+        let JsonSchema::Obj(fields) = JsonSchema::try_from(&operation.output_type())? else {
+            panic!("expected an object");
+        };
+
+        assert_eq!(fields["year"], JsonSchema::Opt(JsonSchema::Num.into()));
+        assert_eq!(fields["time"], JsonSchema::Opt(JsonSchema::Str.into()));
+        assert_eq!(fields["duration"], JsonSchema::Opt(JsonSchema::Str.into()));
+
+        let schema = JsonSchema::try_from(&operation.input_type())?;
+        assert_eq!(
+            schema,
+            JsonSchema::from([("castName", JsonSchema::Opt(JsonSchema::Str.into()))])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_64_bit_wrappers_conversion() {
+        let pool = prost_reflect::DescriptorPool::global();
+
+        for name in ["google.protobuf.Int64Value", "google.protobuf.UInt64Value"] {
+            let message = pool.get_message_by_name(name).unwrap();
+            assert_eq!(JsonSchema::try_from(&message).unwrap(), JsonSchema::Str);
+        }
+    }
+
     #[test]
     fn test_compare_enum() {
         let mut en = BTreeSet::new();
@@ -75,8 +75,39 @@ pub trait HttpIO: Sync + Send + 'static {
         &self,
         request: reqwest::Request,
     ) -> anyhow::Result<Response<hyper::body::Bytes>>;
+
+    /// Executes the request and returns the body as a stream of chunks as
+    /// they arrive, followed by the trailers of the response if there are
+    /// any. Implementations that can't stream return the whole body as a
+    /// single chunk.
+    async fn execute_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> anyhow::Result<Response<BodyStream>> {
+        let response = self.execute(request).await?;
+        let body = response.body;
+
+        Ok(Response {
+            status: response.status,
+            headers: response.headers,
+            body: Box::pin(futures_util::stream::once(async move {
+                Ok(BodyFrame::Data(body))
+            })),
+        })
+    }
 }
 
+/// A part of a response body that is consumed while it arrives.
+#[derive(Debug)]
+pub enum BodyFrame {
+    Data(hyper::body::Bytes),
+    /// Headers sent after the body, e.g. the `grpc-status` of gRPC calls.
+    Trailers(hyper::HeaderMap),
+}
+
+/// Body of a response that is consumed while it arrives.
+pub type BodyStream = futures_util::stream::BoxStream<'static, anyhow::Result<BodyFrame>>;
+
 #[async_trait::async_trait]
 pub trait FileIO: Send + Sync {
     async fn write<'a>(&'a self, path: &'a str, content: &'a [u8]) -> anyhow::Result<()>;
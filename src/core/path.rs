@@ -94,6 +94,16 @@ impl<Ctx: ResolverContextLike> EvalContext<'_, Ctx> {
                     ctx.var(tail[0].as_ref())?,
                 ))),
                 "env" => Some(ValueString::String(ctx.env_var(tail[0].as_ref())?)),
+                "client" => {
+                    let client_cert = ctx.client_cert()?;
+                    match tail[0].as_ref() {
+                        "subject" => Some(ValueString::String(Cow::Borrowed(
+                            client_cert.subject.as_str(),
+                        ))),
+                        "san" => Some(ValueString::String(Cow::Owned(client_cert.san.join(",")))),
+                        _ => None,
+                    }
+                }
                 _ => None,
             })
     }
@@ -140,7 +150,7 @@ mod tests {
         use indexmap::IndexMap;
         use once_cell::sync::Lazy;
 
-        use crate::core::http::RequestContext;
+        use crate::core::http::{ClientCert, RequestContext};
         use crate::core::ir::{EvalContext, ResolverContextLike, SelectionField};
         use crate::core::path::{PathGraphql, PathString, PathValue, ValueString};
         use crate::core::EnvIO;
@@ -240,7 +250,13 @@ mod tests {
         }
 
         static REQ_CTX: Lazy<RequestContext> = Lazy::new(|| {
-            let mut req_ctx = RequestContext::default().allowed_headers(TEST_HEADERS.clone());
+            let mut req_ctx = RequestContext::default()
+                .allowed_headers(TEST_HEADERS.clone())
+                .client_cert(Some(ClientCert {
+                    subject: "CN=orders".to_owned(),
+                    san: vec!["DNS:orders.internal".to_owned(), "DNS:orders".to_owned()],
+                    issuers: vec!["CN=Internal CA".to_owned()],
+                }));
 
             req_ctx.server.vars = TEST_VARS.clone();
             req_ctx.runtime.env = Arc::new(Env::init(TEST_ENV_VARS.clone()));
@@ -373,6 +389,19 @@ mod tests {
             );
             assert_eq!(EVAL_CTX.raw_value(&["env", "x-missing"]), None);
 
+            // client certificate
+            assert_eq!(
+                EVAL_CTX.raw_value(&["client", "subject"]),
+                Some(ValueString::String(Cow::Borrowed("CN=orders")))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["client", "san"]),
+                Some(ValueString::String(Cow::Borrowed(
+                    "DNS:orders.internal,DNS:orders"
+                )))
+            );
+            assert_eq!(EVAL_CTX.raw_value(&["client", "missing"]), None);
+
             // other value types
             assert_eq!(EVAL_CTX.raw_value(&["foo", "key"]), None);
             assert_eq!(EVAL_CTX.raw_value(&["bar", "key"]), None);
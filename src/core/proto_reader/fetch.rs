@@ -1,6 +1,7 @@
 use std::str::FromStr;
+use std::sync::OnceLock;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use http::header::HeaderName;
@@ -10,25 +11,19 @@ use prost_reflect::prost_types::{FileDescriptorProto, FileDescriptorSet};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use super::{reflection_file, REFLECTION_V1, REFLECTION_V1ALPHA};
 use crate::core::blueprint::GrpcMethod;
 use crate::core::config::{ConfigReaderContext, KeyValue};
 use crate::core::grpc::protobuf::ProtobufSet;
+use crate::core::grpc::request::GRPC_STATUS;
 use crate::core::grpc::request_template::RequestBody;
 use crate::core::grpc::RequestTemplate;
 use crate::core::mustache::Mustache;
 use crate::core::runtime::TargetRuntime;
 
-///
-/// Loading reflection proto
-/// https://github.com/grpc/grpc/blob/master/src/proto/grpc/reflection/v1alpha/reflection.proto
-const REFLECTION_PROTO: &str = include_str!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/core/proto_reader/proto/reflection.proto"
-));
-
 /// This function is just used for better exception handling
-fn get_protobuf_set() -> Result<ProtobufSet> {
-    let descriptor = protox_parse::parse("reflection", REFLECTION_PROTO)?;
+fn get_protobuf_set(package: &str) -> Result<ProtobufSet> {
+    let descriptor = reflection_file(package)?;
     let mut descriptor_set = FileDescriptorSet::default();
     descriptor_set.file.push(descriptor);
     ProtobufSet::from_proto_file(descriptor_set)
@@ -72,7 +67,9 @@ struct ReflectionResponse {
 }
 
 pub struct GrpcReflection {
-    server_reflection_method: GrpcMethod,
+    /// The reflection package the server supports, negotiated by the first
+    /// request.
+    package: OnceLock<&'static str>,
     url: String,
     headers: Option<Vec<KeyValue>>,
     target_runtime: TargetRuntime,
@@ -84,13 +81,8 @@ impl GrpcReflection {
         headers: Option<Vec<KeyValue>>,
         target_runtime: TargetRuntime,
     ) -> Self {
-        let server_reflection_method = GrpcMethod {
-            package: "grpc.reflection.v1alpha".to_string(),
-            service: "ServerReflection".to_string(),
-            name: "ServerReflectionInfo".to_string(),
-        };
         Self {
-            server_reflection_method,
+            package: OnceLock::new(),
             url: url.as_ref().to_string(),
             headers,
             target_runtime,
@@ -128,9 +120,39 @@ impl GrpcReflection {
         request_proto(resp)
     }
 
+    /// Prefers `grpc.reflection.v1` and falls back to
+    /// `grpc.reflection.v1alpha` for servers that don't implement it.
     async fn execute(&self, body: serde_json::Value) -> Result<ReflectionResponse> {
-        let server_reflection_method = &self.server_reflection_method;
-        let protobuf_set = get_protobuf_set()?;
+        if let Some(package) = self.package.get() {
+            return self.execute_with(package, body).await;
+        }
+
+        let (package, response) = match self.execute_with(REFLECTION_V1, body.clone()).await {
+            Ok(response) => (REFLECTION_V1, response),
+            Err(err) => {
+                tracing::debug!(
+                    "{REFLECTION_V1} is unavailable, falling back to {REFLECTION_V1ALPHA}: {err}"
+                );
+                let response = self.execute_with(REFLECTION_V1ALPHA, body).await?;
+                (REFLECTION_V1ALPHA, response)
+            }
+        };
+        let _ = self.package.set(package);
+
+        Ok(response)
+    }
+
+    async fn execute_with(
+        &self,
+        package: &str,
+        body: serde_json::Value,
+    ) -> Result<ReflectionResponse> {
+        let server_reflection_method = &GrpcMethod {
+            package: package.to_string(),
+            service: "ServerReflection".to_string(),
+            name: "ServerReflectionInfo".to_string(),
+        };
+        let protobuf_set = get_protobuf_set(package)?;
         let reflection_service = protobuf_set.find_service(server_reflection_method)?;
         let operation = reflection_service.find_operation(server_reflection_method)?;
         let mut url: url::Url = self.url.parse()?;
@@ -167,12 +189,19 @@ impl GrpcReflection {
             body: body_,
             operation: operation.clone(),
             operation_type: Default::default(),
+            oauth2: None,
+            timeout: None,
         };
 
         let ctx = ConfigReaderContext::new(&self.target_runtime);
 
         let req = req_template.render(&ctx)?.to_request()?;
         let resp = self.target_runtime.http2_only.execute(req).await?;
+        if let Some(status) = resp.headers.get(GRPC_STATUS) {
+            if status != "0" {
+                bail!("{package}.ServerReflection failed with grpc-status {status:?}");
+            }
+        }
         let body = resp.body.as_bytes();
 
         let response: ReflectionResponse = operation.convert_output(body)?;
@@ -414,4 +443,61 @@ mod grpc_fetch {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_v1_reflection() -> Result<()> {
+        let server = start_mock_server();
+
+        let v1_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/grpc.reflection.v1.ServerReflection/ServerReflectionInfo");
+            then.status(200).body(get_fake_resp());
+        });
+        let v1alpha_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo");
+            then.status(200).body(get_fake_resp());
+        });
+
+        let runtime = crate::core::runtime::test::init(None);
+        let grpc_reflection =
+            GrpcReflection::new(format!("http://localhost:{}", server.port()), None, runtime);
+
+        grpc_reflection.list_all_files().await?;
+        grpc_reflection.list_all_files().await?;
+
+        v1_mock.assert_hits(2);
+        v1alpha_mock.assert_hits(0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_v1alpha_fallback() -> Result<()> {
+        let server = start_mock_server();
+
+        let v1_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/grpc.reflection.v1.ServerReflection/ServerReflectionInfo");
+            then.status(200).header("grpc-status", "12");
+        });
+        let v1alpha_mock = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo");
+            then.status(200).body(get_fake_resp());
+        });
+
+        let runtime = crate::core::runtime::test::init(None);
+        let grpc_reflection =
+            GrpcReflection::new(format!("http://localhost:{}", server.port()), None, runtime);
+
+        let resp = grpc_reflection.list_all_files().await?;
+        assert!(resp.contains(&"news.NewsService".to_string()));
+        grpc_reflection.list_all_files().await?;
+
+        v1_mock.assert_hits(1);
+        v1alpha_mock.assert_hits(2);
+
+        Ok(())
+    }
 }
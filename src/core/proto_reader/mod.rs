@@ -1,3 +1,29 @@
+use prost_reflect::prost_types::FileDescriptorProto;
 pub use reader::*;
+pub use refresh::DescriptorRefresh;
 mod fetch;
 mod reader;
+mod refresh;
+
+///
+/// Loading reflection proto
+/// https://github.com/grpc/grpc/blob/master/src/proto/grpc/reflection/v1alpha/reflection.proto
+pub const REFLECTION_PROTO: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/core/proto_reader/proto/reflection.proto"
+));
+
+pub const REFLECTION_V1: &str = "grpc.reflection.v1";
+pub const REFLECTION_V1ALPHA: &str = "grpc.reflection.v1alpha";
+
+/// Parses the reflection proto for the given package, `v1` and `v1alpha`
+/// only differ by their package.
+pub fn reflection_file(package: &str) -> anyhow::Result<FileDescriptorProto> {
+    let name = format!("{}/reflection.proto", package.replace('.', "/"));
+    let file = protox_parse::parse(
+        &name,
+        &REFLECTION_PROTO.replace(REFLECTION_V1ALPHA, package),
+    )?;
+
+    Ok(file)
+}
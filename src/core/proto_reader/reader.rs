@@ -46,7 +46,9 @@ impl ProtoReader {
         let mut proto_metadata = vec![];
         let service_list = grpc_reflection.list_all_files().await?;
         for service in service_list {
-            if service.eq("grpc.reflection.v1alpha.ServerReflection") {
+            if service.eq("grpc.reflection.v1alpha.ServerReflection")
+                || service.eq("grpc.reflection.v1.ServerReflection")
+            {
                 continue;
             }
             let file_descriptor_proto = grpc_reflection.get_by_service(&service).await?;
@@ -0,0 +1,268 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{ProtoMetadata, ProtoReader};
+use crate::core::app_context::AppContext;
+use crate::core::blueprint::{Blueprint, Definition};
+use crate::core::config::{ConfigModule, Link, LinkType};
+use crate::core::grpc::protobuf::ProtobufSet;
+use crate::core::ir::model::{IO, IR};
+use crate::core::resource_reader::{Cached, ResourceReader};
+use crate::core::Errata;
+
+/// Fetches the descriptors of `Grpc` links with a `refreshInterval` again,
+/// so that new versions of the upstream protos are used without a restart.
+pub struct DescriptorRefresh {
+    proto_reader: ProtoReader,
+    config_module: Mutex<ConfigModule>,
+    protobuf_sets: Vec<ProtobufSet>,
+}
+
+impl DescriptorRefresh {
+    pub fn new(config_module: ConfigModule, app_ctx: &AppContext) -> Self {
+        let runtime = app_ctx.runtime.clone();
+        let reader = ResourceReader::<Cached>::cached(runtime.clone());
+        let protobuf_sets = protobuf_sets(&app_ctx.blueprint);
+
+        Self {
+            proto_reader: ProtoReader::init(reader, runtime),
+            config_module: Mutex::new(config_module),
+            protobuf_sets,
+        }
+    }
+
+    /// Spawns a task per link with a `refreshInterval`.
+    pub fn start(self) {
+        let links: Vec<(Link, u64)> = self
+            .config_module()
+            .config()
+            .links
+            .iter()
+            .filter(|link| link.type_of == LinkType::Grpc)
+            .filter_map(|link| Some((link.clone(), link.refresh_interval?)))
+            .collect();
+
+        let refresh = Arc::new(self);
+        for (link, interval) in links {
+            let refresh = refresh.clone();
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(interval));
+                // the first tick completes immediately
+                interval.tick().await;
+
+                loop {
+                    interval.tick().await;
+                    if let Err(err) = refresh.refresh(&link).await {
+                        tracing::error!("Failed to refresh descriptors from {}: {err}", link.src);
+                    }
+                }
+            });
+        }
+    }
+
+    fn config_module(&self) -> std::sync::MutexGuard<'_, ConfigModule> {
+        self.config_module
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Runs reflection against the link and swaps the descriptors in if they
+    /// are compatible with the schema.
+    pub async fn refresh(&self, link: &Link) -> anyhow::Result<bool> {
+        let metadata = self
+            .proto_reader
+            .fetch(link.src.as_str(), link.headers.clone())
+            .await?;
+
+        self.apply(&link.src, metadata)
+    }
+
+    /// Returns `true` when the descriptors changed and were swapped in.
+    fn apply(&self, src: &str, metadata: Vec<ProtoMetadata>) -> anyhow::Result<bool> {
+        let mut config_module = self.config_module();
+
+        let mut extensions = config_module.extensions().clone();
+        for metadata in metadata {
+            extensions.add_proto(metadata);
+        }
+
+        if extensions.grpc_file_descriptors == config_module.extensions().grpc_file_descriptors {
+            return Ok(false);
+        }
+
+        let candidate = config_module.clone().set_extensions(extensions);
+        if let Err(err) = Blueprint::try_from(&candidate) {
+            tracing::warn!(
+                "Descriptors fetched from {src} are incompatible with the schema, keeping the current ones:\n{}",
+                Errata::from(err)
+            );
+            return Ok(false);
+        }
+
+        let protobuf_set =
+            ProtobufSet::from_proto_file(candidate.extensions().get_file_descriptor_set())?;
+        for set in self.protobuf_sets.iter() {
+            set.swap(&protobuf_set);
+        }
+        *config_module = candidate;
+
+        tracing::info!("Refreshed descriptors from {src}");
+        Ok(true)
+    }
+}
+
+/// Sets of the gRPC operations of every resolver, the operations used by the
+/// data loaders are cloned from them and share their descriptors.
+fn protobuf_sets(blueprint: &Blueprint) -> Vec<ProtobufSet> {
+    let mut sets = vec![];
+
+    for definition in blueprint.definitions.iter() {
+        let fields = match definition {
+            Definition::Object(def) => &def.fields,
+            Definition::Interface(def) => &def.fields,
+            _ => continue,
+        };
+
+        for resolver in fields.iter().filter_map(|field| field.resolver.clone()) {
+            resolver.modify(&mut |ir| {
+                if let IR::IO(IO::Grpc { req_template, .. }) = ir {
+                    sets.push(req_template.operation.protobuf_set().clone());
+                }
+                None
+            });
+        }
+    }
+
+    sets
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_reflect::prost_types::{DescriptorProto, FileDescriptorSet};
+    use tailcall_fixtures::protobuf;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::GrpcMethod;
+    use crate::core::config::reader::ConfigReader;
+    use crate::core::config::Config;
+    use crate::core::rest::EndpointSet;
+
+    const HELLO: &str = r#"hello: HelloReply @grpc(url: "http://localhost:50051", method: "greetings.Greeter.SayHello")"#;
+
+    async fn setup(types: &str) -> (ConfigModule, AppContext) {
+        let sdl = format!(
+            r#"
+            schema @link(id: "greetings", src: "{}", type: Protobuf) {{
+              query: Query
+            }}
+
+            {types}
+
+            type HelloReply {{
+              message: String
+            }}
+            "#,
+            protobuf::GREETINGS
+        );
+        let runtime = crate::core::runtime::test::init(None);
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+        let config_module = ConfigReader::init(runtime.clone())
+            .resolve(config, None)
+            .await
+            .unwrap();
+        let blueprint = Blueprint::try_from(&config_module).unwrap();
+        let app_ctx = AppContext::new(blueprint, runtime, EndpointSet::default()).unwrap();
+
+        (config_module, app_ctx)
+    }
+
+    fn metadata(config_module: &ConfigModule, name: &str) -> ProtoMetadata {
+        let file = config_module
+            .extensions()
+            .grpc_file_descriptors
+            .values()
+            .find(|file| file.name().ends_with(name))
+            .unwrap()
+            .clone();
+
+        ProtoMetadata {
+            descriptor_set: FileDescriptorSet { file: vec![file] },
+            path: name.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compatible_descriptors() {
+        let (config_module, app_ctx) = setup(&format!("type Query {{ {HELLO} }}")).await;
+        let refresh = DescriptorRefresh::new(config_module.clone(), &app_ctx);
+        let operation = app_ctx.grpc_data_loaders[0].loader().operation.clone();
+
+        let unchanged = metadata(&config_module, "greetings_message.proto");
+        assert!(!refresh.apply("test", vec![unchanged.clone()]).unwrap());
+
+        let mut changed = unchanged;
+        changed.descriptor_set.file[0]
+            .message_type
+            .push(DescriptorProto { name: Some("Greeting".into()), ..Default::default() });
+
+        assert!(operation.find_message("greetings.Greeting").is_none());
+        assert!(refresh.apply("test", vec![changed]).unwrap());
+        assert!(operation.find_message("greetings.Greeting").is_some());
+
+        // the method is resolved from the new descriptors
+        let pool = operation.method().parent_pool().clone();
+        assert!(pool.get_message_by_name("greetings.Greeting").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_interface_field_descriptors() {
+        let (config_module, app_ctx) = setup(&format!(
+            "interface Greeter {{ {HELLO} }} type Query implements Greeter {{ {HELLO} }}"
+        ))
+        .await;
+        let refresh = DescriptorRefresh::new(config_module.clone(), &app_ctx);
+        let operation = app_ctx
+            .blueprint
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Interface(def) => match def.fields[0].resolver.as_ref() {
+                    Some(IR::IO(IO::Grpc { req_template, .. })) => {
+                        Some(req_template.operation.clone())
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .unwrap();
+
+        let mut changed = metadata(&config_module, "greetings_message.proto");
+        changed.descriptor_set.file[0]
+            .message_type
+            .push(DescriptorProto { name: Some("Greeting".into()), ..Default::default() });
+
+        assert!(refresh.apply("test", vec![changed]).unwrap());
+        assert!(operation.find_message("greetings.Greeting").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_incompatible_descriptors() {
+        let (config_module, app_ctx) = setup(&format!("type Query {{ {HELLO} }}")).await;
+        let refresh = DescriptorRefresh::new(config_module.clone(), &app_ctx);
+        let method = GrpcMethod::try_from("greetings.Greeter.SayHello").unwrap();
+
+        let mut changed = metadata(&config_module, "greetings.proto");
+        changed.descriptor_set.file[0].service[0].method.clear();
+
+        assert!(!refresh.apply("test", vec![changed]).unwrap());
+
+        let operation = &app_ctx.grpc_data_loaders[0].loader().operation;
+        assert!(operation
+            .protobuf_set()
+            .find_service(&method)
+            .and_then(|service| service.find_operation(&method))
+            .is_ok());
+    }
+}
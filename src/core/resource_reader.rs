@@ -13,7 +13,7 @@ use crate::core::runtime::TargetRuntime;
 use crate::core::Mustache;
 
 /// Response of a file read operation
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FileRead {
     pub content: String,
     pub path: String,
@@ -0,0 +1,330 @@
+use std::convert::Infallible;
+
+use async_graphql_value::{ConstValue, Name};
+use hyper::body::Bytes;
+use indexmap::map::Entry;
+use indexmap::IndexMap;
+use tailcall_valid::{Valid, Validator};
+
+use super::{Request, Result};
+use crate::core::blueprint::Index;
+use crate::core::scalar::Scalar;
+use crate::core::Type;
+
+/// Body of a request, form fields become the fields of an object whose values
+/// are text.
+pub struct Body {
+    pub value: ConstValue,
+    pub is_form: bool,
+}
+
+impl Body {
+    pub async fn read(request: Request) -> Result<Self> {
+        let content_type = request
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        let bytes = hyper::body::to_bytes(request.into_body()).await?;
+
+        match mime.as_str() {
+            "application/x-www-form-urlencoded" => {
+                let fields = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)?;
+                Ok(Self::form(fields))
+            }
+            "multipart/form-data" => {
+                let boundary = multer::parse_boundary(&content_type)?;
+                let stream = futures_util::stream::once(async { Ok::<Bytes, Infallible>(bytes) });
+                let mut multipart = multer::Multipart::new(stream, boundary);
+
+                let mut fields = vec![];
+                while let Some(field) = multipart.next_field().await? {
+                    let name = field.name().unwrap_or_default().to_string();
+                    fields.push((name, field.text().await?));
+                }
+                Ok(Self::form(fields))
+            }
+            _ => Ok(Self { value: serde_json::from_slice(&bytes)?, is_form: false }),
+        }
+    }
+
+    /// Repeated fields are collected into a list.
+    fn form(fields: Vec<(String, String)>) -> Self {
+        let mut object = IndexMap::new();
+        for (name, value) in fields {
+            let value = ConstValue::String(value);
+            match object.entry(Name::new(name)) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+                Entry::Occupied(mut entry) => match entry.get_mut() {
+                    ConstValue::List(list) => list.push(value),
+                    first => *first = ConstValue::List(vec![first.clone(), value]),
+                },
+            }
+        }
+
+        Self { value: ConstValue::Object(object), is_form: true }
+    }
+
+    /// Checks the body against the type of its variable, the scalars of forms
+    /// are parsed from their text. Every invalid value is reported along with
+    /// its path.
+    pub fn coerce(self, type_of: &Type, index: &Index) -> Valid<ConstValue, String> {
+        Coercion { index, is_form: self.is_form }.coerce(self.value, type_of)
+    }
+}
+
+struct Coercion<'a> {
+    index: &'a Index,
+    is_form: bool,
+}
+
+impl Coercion<'_> {
+    fn coerce(&self, value: ConstValue, type_of: &Type) -> Valid<ConstValue, String> {
+        match (value, type_of) {
+            (ConstValue::Null, type_of) if type_of.is_nullable() => {
+                Valid::succeed(ConstValue::Null)
+            }
+            (ConstValue::Null, type_of) => {
+                Valid::fail(format!("expected a value of type {}", type_of.name()))
+            }
+            (value, Type::List { of_type, .. }) => {
+                // a single value is coerced to a list with that value
+                let items = match value {
+                    ConstValue::List(items) => items,
+                    value => vec![value],
+                };
+                Valid::from_iter(items.into_iter().enumerate(), |(i, item)| {
+                    self.coerce(item, of_type).trace(&i.to_string())
+                })
+                .map(ConstValue::List)
+            }
+            (value, Type::Named { name, .. }) => self.named(value, name),
+        }
+    }
+
+    fn named(&self, value: ConstValue, name: &str) -> Valid<ConstValue, String> {
+        if let Some(input) = self.index.get_input_type_definition(name) {
+            let ConstValue::Object(mut fields) = value else {
+                return Valid::fail(format!("expected an object of type {name}"));
+            };
+
+            let unknown = Valid::from_iter(fields.keys(), |key| {
+                if input.fields.iter().any(|field| field.name == key.as_str()) {
+                    Valid::succeed(())
+                } else {
+                    Valid::fail(format!("unknown field of type {name}")).trace(key)
+                }
+            });
+
+            let known = Valid::from_iter(input.fields.iter(), |field| {
+                match fields.swap_remove(field.name.as_str()) {
+                    Some(value) => self
+                        .coerce(value, &field.of_type)
+                        .map(|value| Some((Name::new(&field.name), value))),
+                    None if field.of_type.is_nullable() || field.default_value.is_some() => {
+                        Valid::succeed(None)
+                    }
+                    None => Valid::fail("missing required field".to_string()),
+                }
+                .trace(&field.name)
+            });
+
+            unknown
+                .and(known)
+                .map(|fields| ConstValue::Object(fields.into_iter().flatten().collect()))
+        } else if self.index.type_is_enum(name) {
+            let variant = match &value {
+                ConstValue::String(variant) => Some(variant.as_str()),
+                ConstValue::Enum(variant) => Some(variant.as_str()),
+                _ => None,
+            };
+
+            match variant.filter(|variant| self.index.validate_enum_value(name, variant)) {
+                Some(variant) => Valid::succeed(ConstValue::Enum(Name::new(variant))),
+                None => Valid::fail(format!("expected a value of enum {name}")),
+            }
+        } else if is_scalar_value(name, &value) {
+            Valid::succeed(value)
+        } else {
+            // form fields are text, e.g. `age=42` sets an `Int`
+            let parsed = match &value {
+                ConstValue::String(text) if self.is_form => serde_json::from_str(text).ok(),
+                _ => None,
+            };
+
+            match parsed.filter(|parsed| is_scalar_value(name, parsed)) {
+                Some(parsed) => Valid::succeed(parsed),
+                None => Valid::fail(format!("expected a value of type {name}")),
+            }
+        }
+    }
+}
+
+fn is_scalar_value(name: &str, value: &ConstValue) -> bool {
+    match (name, value) {
+        ("String", ConstValue::String(_)) => true,
+        ("ID", ConstValue::String(_)) => true,
+        ("ID", ConstValue::Number(number)) => number.is_i64() || number.is_u64(),
+        ("Int", ConstValue::Number(number)) => number
+            .as_i64()
+            .is_some_and(|number| i32::try_from(number).is_ok()),
+        ("Float", ConstValue::Number(_)) => true,
+        ("Boolean", ConstValue::Boolean(_)) => true,
+        ("String" | "ID" | "Int" | "Float" | "Boolean", _) => false,
+        // scalars declared in the schema accept any value
+        (name, value) => Scalar::find(name).map_or(true, |scalar| scalar.validate(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+
+    const SCHEMA: &str = r#"
+        schema { query: Query mutation: Mutation }
+        type Query { user: User @expr(body: {name: "John"}) }
+        type Mutation {
+            createUser(input: UserInput!): User @expr(body: {name: "{{.args.input.name}}"})
+        }
+        input UserInput { name: String! age: Int role: Role tags: [String!] address: Address }
+        input Address { city: String! zip: Int! }
+        enum Role { ADMIN MEMBER }
+        type User { name: String }
+    "#;
+
+    fn index() -> Index {
+        let config = Config::from_sdl(SCHEMA).to_result().unwrap();
+        Blueprint::try_from(&ConfigModule::from(config))
+            .unwrap()
+            .index()
+    }
+
+    fn input_type() -> Type {
+        Type::from("UserInput".to_string()).into_required()
+    }
+
+    fn errors(body: Body) -> Vec<String> {
+        body.coerce(&input_type(), &index())
+            .to_result()
+            .unwrap_err()
+            .as_vec()
+            .iter()
+            .map(|cause| format!("{:?}: {}", cause.trace, cause.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_json_body() {
+        let value = ConstValue::from_json(json!({
+            "name": "John",
+            "role": "ADMIN",
+            "tags": "admin",
+            "address": { "city": "Paris", "zip": 75001 }
+        }))
+        .unwrap();
+        let actual = Body { value, is_form: false }
+            .coerce(&input_type(), &index())
+            .to_result()
+            .unwrap();
+
+        assert_eq!(
+            actual.into_json().unwrap(),
+            json!({
+                "name": "John",
+                "role": "ADMIN",
+                "tags": ["admin"],
+                "address": { "city": "Paris", "zip": 75001 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_json_body() {
+        let value = ConstValue::from_json(json!({
+            "age": "42",
+            "role": "OWNER",
+            "tags": ["a", null],
+            "address": { "zip": 75001 },
+            "email": "john@example.com"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            errors(Body { value, is_form: false }),
+            vec![
+                r#"["email"]: unknown field of type UserInput"#,
+                r#"["address", "city"]: missing required field"#,
+                r#"["age"]: expected a value of type Int"#,
+                r#"["name"]: missing required field"#,
+                r#"["role"]: expected a value of enum Role"#,
+                r#"["tags", "1"]: expected a value of type String"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_form_body() {
+        let fields = [
+            ("name", "John"),
+            ("age", "42"),
+            ("tags", "a"),
+            ("tags", "b"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let actual = Body::form(fields.to_vec())
+            .coerce(&input_type(), &index())
+            .to_result()
+            .unwrap();
+
+        assert_eq!(
+            actual.into_json().unwrap(),
+            json!({ "name": "John", "age": 42, "tags": ["a", "b"] })
+        );
+    }
+
+    #[test]
+    fn test_invalid_form_body() {
+        let fields = [("name", "John"), ("age", "forty-two")]
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        assert_eq!(
+            errors(Body::form(fields.to_vec())),
+            vec![r#"["age"]: expected a value of type Int"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_multipart() {
+        let body = "--X\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nJohn\r\n\
+                    --X\r\nContent-Disposition: form-data; name=\"age\"\r\n\r\n42\r\n\
+                    --X--\r\n";
+        let request = http::Request::builder()
+            .header(
+                http::header::CONTENT_TYPE,
+                "multipart/form-data; boundary=X",
+            )
+            .body(hyper::Body::from(body))
+            .unwrap();
+        let body = Body::read(request).await.unwrap();
+
+        assert!(body.is_form);
+        assert_eq!(
+            body.value.into_json().unwrap(),
+            json!({ "name": "John", "age": "42" })
+        );
+    }
+}
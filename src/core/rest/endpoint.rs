@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 use async_graphql::parser::types::{Directive, DocumentOperations, ExecutableDocument};
 use async_graphql::{Positioned, Variables};
@@ -12,6 +13,7 @@ use super::query_params::QueryParams;
 use super::type_map::TypeMap;
 use super::{Request, Result};
 use crate::core::async_graphql_hyper::GraphQLRequest;
+use crate::core::blueprint::Index;
 use crate::core::directive::DirectiveCodec;
 use crate::core::http::Method;
 use crate::core::rest::typed_variables::{UrlParamType, N};
@@ -26,6 +28,8 @@ pub struct Endpoint {
     query_params: QueryParams,
     body: Option<String>,
     pub doc: ExecutableDocument,
+    /// Types of the schema, set once the endpoint is checked.
+    index: Option<Arc<Index>>,
 }
 
 /// Creates a Rest instance from @rest directive
@@ -37,6 +41,15 @@ impl Endpoint {
     pub fn get_path(&self) -> &Path {
         &self.path
     }
+
+    pub fn get_query_params(&self) -> &QueryParams {
+        &self.query_params
+    }
+
+    pub fn get_body(&self) -> Option<&String> {
+        self.body.as_ref()
+    }
+
     pub fn try_new(operations: &str) -> Result<Vec<Self>> {
         let doc = async_graphql::parser::parse_query(operations)?;
         let mut endpoints = Vec::new();
@@ -75,6 +88,7 @@ impl Endpoint {
                         operations: DocumentOperations::Single(op.clone()),
                         fragments: doc.fragments.clone(),
                     },
+                    index: None,
                 };
                 endpoints.push(endpoint);
             }
@@ -136,6 +150,8 @@ impl Endpoint {
         directives.retain(|v| v.node.name.node != name)
     }
 
+    /// Matches the method and the path of the request, query params that
+    /// can't be coerced to their variable's type fail the request instead.
     pub fn matches<'a>(&'a self, request: &Request) -> Option<PartialRequest<'a>> {
         let query_params = request
             .uri()
@@ -154,17 +170,18 @@ impl Endpoint {
         let path = self.path.matches(request.uri().path())?;
 
         // Query
-        let query = self.query_params.matches(query_params)?;
+        let query = self.query_params.matches(query_params);
 
         // TODO: Too much cloning is happening via merge_variables
         variables = merge_variables(variables, path);
-        variables = merge_variables(variables, query);
+        let variables = query.map(|query| merge_variables(variables, query));
 
         Some(PartialRequest {
             body: self.body.as_ref(),
             doc: &self.doc,
             variables,
             path: &self.path,
+            index: self.index.as_deref(),
         })
     }
 }
@@ -304,7 +321,9 @@ mod tests {
             let endpoint = &mut Endpoint::try_new(query).unwrap()[0];
             let request = test_request(method, uri).unwrap();
 
-            endpoint.matches(&request).map(|req| req.variables)
+            endpoint
+                .matches(&request)
+                .and_then(|req| req.variables.ok())
         }
 
         #[test]
@@ -369,12 +388,15 @@ mod tests {
 
         #[test]
         fn test_invalid_query_param() {
-            let actual = test_matches(
-                TEST_QUERY,
-                Method::POST,
-                "http://localhost:8080/foo/1?b=b&c=c",
-            );
-            assert_eq!(actual, None)
+            let endpoint = &Endpoint::try_new(TEST_QUERY).unwrap()[0];
+            let request =
+                test_request(Method::POST, "http://localhost:8080/foo/1?b=b&c=c").unwrap();
+            let error = endpoint.matches(&request).unwrap().variables.unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                "Invalid value of query param c: Parse Boolean Error: provided string was not `true` or `false`"
+            )
         }
 
         #[test]
@@ -34,11 +34,13 @@ impl From<Endpoint> for EndpointSet<Unchecked> {
     }
 }
 
-impl EndpointSet<Unchecked> {
+impl<Status> EndpointSet<Status> {
     pub fn get_endpoints(&self) -> &Vec<Endpoint> {
         &self.endpoints
     }
+}
 
+impl EndpointSet<Unchecked> {
     pub fn add_endpoint(&mut self, endpoint: Endpoint) {
         self.endpoints.push(endpoint);
     }
@@ -75,10 +77,14 @@ impl EndpointSet<Unchecked> {
         super::operation::validate_operations(blueprint, operations)
             .await
             .to_result()?;
-        Ok(EndpointSet {
-            marker: std::marker::PhantomData::<Checked>,
-            endpoints: self.endpoints,
-        })
+        let index = Arc::new(blueprint.index());
+        let endpoints = self
+            .endpoints
+            .into_iter()
+            .map(|endpoint| endpoint.index(Some(index.clone())))
+            .collect();
+
+        Ok(EndpointSet { marker: std::marker::PhantomData::<Checked>, endpoints })
     }
 }
 
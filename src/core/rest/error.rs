@@ -25,6 +25,10 @@ pub enum Error {
     #[error("Undefined query param: {}", _0)]
     UndefinedQueryParam(String),
 
+    #[error("Invalid value of query param {key}: {error}")]
+    #[debug("Invalid value of query param {key}: {error}")]
+    InvalidQueryParam { key: String, error: Box<Error> },
+
     #[error("Parse Integer Error: {}", _0)]
     ParseInteger(ParseIntError),
 
@@ -41,6 +45,16 @@ pub enum Error {
     #[error("Validation Error : {}", _0)]
     Validation(ValidationError<std::string::String>),
 
+    #[error("Invalid Body : {}", _0)]
+    #[from(ignore)]
+    InvalidBody(ValidationError<std::string::String>),
+
+    #[error("Form Error: {}", _0)]
+    Form(serde_urlencoded::de::Error),
+
+    #[error("Multipart Error: {}", _0)]
+    Multipart(multer::Error),
+
     #[error("Async Graphql Parser Error: {}", _0)]
     ParseGraphQL(async_graphql::parser::Error),
 
@@ -57,4 +71,22 @@ pub enum Error {
     GraphQLServer(ServerError),
 }
 
+impl Error {
+    /// Messages of the error, an invalid body has one for every invalid value
+    /// prefixed with its path, e.g. `input.address.zip`.
+    pub fn messages(&self) -> Vec<String> {
+        match self {
+            Error::InvalidBody(error) => error
+                .as_vec()
+                .iter()
+                .map(|cause| {
+                    let path = cause.trace.iter().cloned().collect::<Vec<_>>().join(".");
+                    format!("{path}: {}", cause.message)
+                })
+                .collect(),
+            error => vec![error.to_string()],
+        }
+    }
+}
+
 pub type Result<A> = std::result::Result<A, Error>;
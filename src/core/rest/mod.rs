@@ -1,15 +1,20 @@
+mod body;
 mod directive;
 mod endpoint;
 mod endpoint_set;
 pub mod error;
+mod openapi;
 mod operation;
 mod partial_request;
 mod path;
 mod query_params;
+mod status;
 mod type_map;
 mod typed_variables;
 
 pub use endpoint_set::{Checked, EndpointSet, Unchecked};
+pub use openapi::OpenApi;
+pub use status::ErrorMapping;
 
 type Request = http::Request<hyper::Body>;
 pub use error::{Error, Result};
@@ -0,0 +1,603 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_graphql::parser::types::{
+    DocumentOperations, ExecutableDocument, OperationDefinition, OperationType, Selection,
+    SelectionSet,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::endpoint::Endpoint;
+use super::path::Segment;
+use super::{Checked, EndpointSet};
+use crate::core::blueprint::{Blueprint, Definition};
+use crate::core::http::API_URL_PREFIX;
+use crate::core::Type;
+
+const OPENAPI_VERSION: &str = "3.1.0";
+const SCHEMAS_REF: &str = "#/components/schemas/";
+
+/// The OpenAPI 3.1 document describing the `@rest` endpoints of a
+/// [Blueprint]. Response schemas only contain the fields selected by the
+/// operation behind each endpoint.
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct OpenApi(Value);
+
+impl OpenApi {
+    pub fn new(blueprint: &Blueprint, endpoints: &EndpointSet<Checked>) -> Self {
+        let mut generator = Generator {
+            definitions: blueprint
+                .definitions
+                .iter()
+                .map(|def| (def.name(), def))
+                .collect(),
+            query: blueprint.query(),
+            mutation: blueprint.mutation(),
+            components: BTreeMap::new(),
+        };
+
+        let mut paths = BTreeMap::<String, Map<String, Value>>::new();
+        for endpoint in endpoints.get_endpoints() {
+            let method = endpoint.get_method().clone().to_hyper();
+            let operation = generator.operation(endpoint);
+            paths
+                .entry(path(endpoint))
+                .or_default()
+                .insert(method.as_str().to_lowercase(), operation);
+        }
+
+        let mut document = json!({
+            "openapi": OPENAPI_VERSION,
+            "info": { "title": "Tailcall", "version": "1.0.0" },
+            "paths": paths,
+        });
+
+        if !generator.components.is_empty() {
+            document["components"] = json!({ "schemas": generator.components });
+        }
+
+        Self(document)
+    }
+
+    pub fn as_value(&self) -> &Value {
+        &self.0
+    }
+}
+
+/// Converts the path of the endpoint into an OpenAPI path template, e.g.
+/// `/users/$id` becomes `/api/users/{id}`.
+fn path(endpoint: &Endpoint) -> String {
+    let segments = endpoint
+        .get_path()
+        .segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(literal) => literal.clone(),
+            Segment::Param(param) => format!("{{{}}}", param.name()),
+        })
+        .collect::<Vec<_>>();
+
+    format!("{API_URL_PREFIX}/{}", segments.join("/"))
+}
+
+fn operation_definition(doc: &ExecutableDocument) -> Option<&OperationDefinition> {
+    match &doc.operations {
+        DocumentOperations::Single(op) => Some(&op.node),
+        DocumentOperations::Multiple(ops) => ops.values().next().map(|op| &op.node),
+    }
+}
+
+fn scalar(name: &str) -> Option<Value> {
+    Some(match name {
+        "String" | "ID" | "PhoneNumber" => json!({ "type": "string" }),
+        "Int" => json!({ "type": "integer", "format": "int32" }),
+        "Int8" | "Int16" | "Int32" | "UInt8" | "UInt16" | "UInt32" => {
+            json!({ "type": "integer", "format": name.to_lowercase() })
+        }
+        // 64 and 128 bit integers are transferred as strings
+        "Int64" | "UInt64" | "Int128" | "UInt128" => {
+            json!({ "type": "string", "format": name.to_lowercase() })
+        }
+        "Float" => json!({ "type": "number", "format": "double" }),
+        "Boolean" => json!({ "type": "boolean" }),
+        "Date" => json!({ "type": "string", "format": "date" }),
+        "DateTime" => json!({ "type": "string", "format": "date-time" }),
+        "Email" => json!({ "type": "string", "format": "email" }),
+        "Url" => json!({ "type": "string", "format": "uri" }),
+        "Bytes" => json!({ "type": "string", "format": "byte" }),
+        "Upload" => json!({
+            "type": "object",
+            "properties": {
+                "filename": { "type": "string" },
+                "contentType": { "type": "string" },
+                "content": { "type": "string", "format": "byte" }
+            },
+            "required": ["filename", "content"]
+        }),
+        "Empty" => json!({ "type": "null" }),
+        "JSON" => json!({}),
+        _ => return None,
+    })
+}
+
+/// Allows `null` next to the values described by the schema.
+fn nullable(schema: Value) -> Value {
+    match schema.get("type") {
+        Some(Value::String(ty)) if ty != "null" => {
+            let mut schema = schema.clone();
+            schema["type"] = json!([ty, "null"]);
+            schema
+        }
+        Some(_) => schema,
+        // Any value is allowed already
+        None if schema.as_object().is_some_and(Map::is_empty) => schema,
+        None => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+}
+
+struct Generator<'a> {
+    definitions: HashMap<&'a str, &'a Definition>,
+    query: String,
+    mutation: Option<String>,
+    components: BTreeMap<String, Value>,
+}
+
+impl Generator<'_> {
+    fn operation(&mut self, endpoint: &Endpoint) -> Value {
+        let Some(op) = operation_definition(&endpoint.doc) else {
+            return json!({});
+        };
+
+        let variables = op
+            .variable_definitions
+            .iter()
+            .map(|var| (var.node.name.node.as_str(), &var.node))
+            .collect::<HashMap<_, _>>();
+
+        let mut parameters = vec![];
+        for segment in endpoint.get_path().segments.iter() {
+            if let Segment::Param(param) = segment {
+                let schema = variables
+                    .get(param.name())
+                    .map(|var| self.input(&Type::from(&var.var_type.node).into_required()))
+                    .unwrap_or_default();
+                parameters.push(json!({
+                    "name": param.name(),
+                    "in": "path",
+                    "required": true,
+                    "schema": schema,
+                }));
+            }
+        }
+
+        for (key, param) in endpoint.get_query_params().iter() {
+            let Some(var) = variables.get(param.name()) else {
+                continue;
+            };
+            parameters.push(json!({
+                "name": key,
+                "in": "query",
+                "required": !var.var_type.node.nullable && var.default_value.is_none(),
+                "schema": self.input(&Type::from(&var.var_type.node)),
+            }));
+        }
+
+        let mut operation = Map::new();
+        let root = match op.ty {
+            OperationType::Mutation => self.mutation.clone().unwrap_or_default(),
+            _ => self.query.clone(),
+        };
+        let (properties, _) = self.selection_set(&root, &op.selection_set.node, &endpoint.doc);
+        if let [(name, _)] = properties.as_slice() {
+            if let Some(description) = self.field_description(&root, name) {
+                operation.insert("summary".into(), description.into());
+            }
+        }
+
+        if !parameters.is_empty() {
+            operation.insert("parameters".into(), parameters.into());
+        }
+
+        if let Some(var) = endpoint
+            .get_body()
+            .and_then(|body| variables.get(body.as_str()))
+        {
+            operation.insert(
+                "requestBody".into(),
+                json!({
+                    "required": !var.var_type.node.nullable,
+                    "content": {
+                        "application/json": { "schema": self.input(&Type::from(&var.var_type.node)) }
+                    }
+                }),
+            );
+        }
+
+        // Responses with a single root field are flattened to its value
+        let schema = match properties.as_slice() {
+            [(_, schema)] => schema.clone(),
+            _ => json!({
+                "type": "object",
+                "properties": properties.into_iter().collect::<Map<_, _>>(),
+            }),
+        };
+        operation.insert(
+            "responses".into(),
+            json!({
+                "200": {
+                    "description": "Successful response",
+                    "content": { "application/json": { "schema": schema } }
+                }
+            }),
+        );
+
+        Value::Object(operation)
+    }
+
+    fn field_description(&self, type_name: &str, field_name: &str) -> Option<String> {
+        match self.definitions.get(type_name)? {
+            Definition::Object(def) => def.fields.iter().find(|f| f.name == field_name),
+            Definition::Interface(def) => def.fields.iter().find(|f| f.name == field_name),
+            _ => None,
+        }?
+        .description
+        .clone()
+    }
+
+    /// Wraps the schema of the named type into the lists and nullability of
+    /// `of_type`.
+    fn wrap(&mut self, of_type: &Type, named: &mut impl FnMut(&mut Self, &str) -> Value) -> Value {
+        let schema = match of_type {
+            Type::Named { name, .. } => named(self, name),
+            Type::List { of_type, .. } => {
+                json!({ "type": "array", "items": self.wrap(of_type, named) })
+            }
+        };
+
+        if of_type.is_nullable() {
+            nullable(schema)
+        } else {
+            schema
+        }
+    }
+
+    fn input(&mut self, of_type: &Type) -> Value {
+        self.wrap(of_type, &mut |this, name| this.named(name))
+    }
+
+    /// Schema of a scalar, enum or input type, the latter two are added to the
+    /// components.
+    fn named(&mut self, name: &str) -> Value {
+        if let Some(schema) = scalar(name) {
+            return schema;
+        }
+
+        let reference = json!({ "$ref": format!("{SCHEMAS_REF}{name}") });
+        if self.components.contains_key(name) {
+            return reference;
+        }
+
+        let schema = match self.definitions.get(name).copied() {
+            Some(Definition::Enum(def)) => {
+                let values = def
+                    .enum_values
+                    .iter()
+                    .map(|value| value.name.clone())
+                    .collect::<Vec<_>>();
+                json!({ "type": "string", "enum": values })
+            }
+            Some(Definition::InputObject(def)) => {
+                // Reserve the name first, input types can be recursive
+                self.components.insert(name.to_string(), json!({}));
+
+                let mut properties = Map::new();
+                let mut required = vec![];
+                for field in def.fields.iter() {
+                    let mut schema = self.input(&field.of_type);
+                    if let Some(description) = &field.description {
+                        schema["description"] = description.clone().into();
+                    }
+                    if let Some(default) = &field.default_value {
+                        schema["default"] = default.clone();
+                    }
+                    if !field.of_type.is_nullable() && field.default_value.is_none() {
+                        required.push(field.name.clone());
+                    }
+                    properties.insert(field.name.clone(), schema);
+                }
+
+                json!({ "type": "object", "properties": properties, "required": required })
+            }
+            // Custom scalars and any other type accept any value
+            _ => return json!({}),
+        };
+
+        let schema = match self.definitions.get(name).and_then(|def| def.description()) {
+            Some(description) => {
+                let mut schema = schema;
+                schema["description"] = description.clone().into();
+                schema
+            }
+            None => schema,
+        };
+
+        self.components.insert(name.to_string(), schema);
+        reference
+    }
+
+    fn output(
+        &mut self,
+        of_type: &Type,
+        selection_set: &SelectionSet,
+        doc: &ExecutableDocument,
+    ) -> Value {
+        self.wrap(of_type, &mut |this, name| {
+            if selection_set.items.is_empty() {
+                return this.named(name);
+            }
+
+            let (properties, required) = this.selection_set(name, selection_set, doc);
+            json!({
+                "type": "object",
+                "properties": properties.into_iter().collect::<Map<_, _>>(),
+                "required": required,
+            })
+        })
+    }
+
+    /// Schemas of the fields selected on the type, along with the names of
+    /// the fields that are always present.
+    fn selection_set(
+        &mut self,
+        type_name: &str,
+        selection_set: &SelectionSet,
+        doc: &ExecutableDocument,
+    ) -> (Vec<(String, Value)>, Vec<String>) {
+        let mut properties = vec![];
+        let mut required = vec![];
+
+        for selection in selection_set.items.iter() {
+            let (nested, condition) = match &selection.node {
+                Selection::Field(field) => {
+                    let field = &field.node;
+                    let key = field.response_key().node.to_string();
+                    if properties.iter().any(|(name, _)| *name == key) {
+                        continue;
+                    }
+
+                    let name = field.name.node.as_str();
+                    let schema = if name == "__typename" {
+                        required.push(key.clone());
+                        json!({ "type": "string" })
+                    } else {
+                        match self.field_type(type_name, name) {
+                            Some(of_type) => {
+                                if !of_type.is_nullable() {
+                                    required.push(key.clone());
+                                }
+                                self.output(&of_type, &field.selection_set.node, doc)
+                            }
+                            None => json!({}),
+                        }
+                    };
+
+                    properties.push((key, schema));
+                    continue;
+                }
+                Selection::FragmentSpread(spread) => {
+                    let Some(fragment) = doc.fragments.get(&spread.node.fragment_name.node) else {
+                        continue;
+                    };
+                    let condition = fragment.node.type_condition.node.on.node.to_string();
+                    (&fragment.node.selection_set.node, Some(condition))
+                }
+                Selection::InlineFragment(fragment) => {
+                    let condition = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .map(|condition| condition.node.on.node.to_string());
+                    (&fragment.node.selection_set.node, condition)
+                }
+            };
+
+            // Fields of fragments on other types are only present for some values
+            let condition = condition.unwrap_or_else(|| type_name.to_string());
+            let (nested_properties, nested_required) = self.selection_set(&condition, nested, doc);
+            for (key, schema) in nested_properties {
+                if !properties.iter().any(|(name, _)| *name == key) {
+                    properties.push((key, schema));
+                }
+            }
+            if condition == type_name {
+                for key in nested_required {
+                    if !required.contains(&key) {
+                        required.push(key);
+                    }
+                }
+            }
+        }
+
+        (properties, required)
+    }
+
+    fn field_type(&self, type_name: &str, field_name: &str) -> Option<Type> {
+        let fields = match self.definitions.get(type_name)? {
+            Definition::Object(def) => &def.fields,
+            Definition::Interface(def) => &def.fields,
+            _ => return None,
+        };
+
+        fields
+            .iter()
+            .find(|field| field.name == field_name)
+            .map(|field| field.of_type.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::rest::Unchecked;
+
+    const CONFIG: &str = r#"
+        schema @server(port: 8000) {
+          query: Query
+          mutation: Mutation
+        }
+
+        type Query {
+          "Finds a user by id"
+          user(id: Int!): User @http(url: "http://localhost:3000/users/{{.args.id}}")
+          users(active: Boolean): [User!] @http(url: "http://localhost:3000/users")
+        }
+
+        type Mutation {
+          createUser(input: UserInput): User @http(url: "http://localhost:3000/users", method: POST, body: "{{.args.input}}")
+        }
+
+        input UserInput {
+          name: String!
+          role: Role = USER
+        }
+
+        enum Role {
+          USER
+          ADMIN
+        }
+
+        type User {
+          id: Int!
+          name: String!
+          email: Email
+          role: Role
+        }
+    "#;
+
+    const OPERATIONS: &str = r#"
+        query user($id: Int!) @rest(method: GET, path: "/users/$id") {
+          user(id: $id) { id name email }
+        }
+
+        query users($active: Boolean) @rest(method: GET, path: "/users", query: {active: $active}) {
+          users(active: $active) { ... on User { id name } }
+        }
+
+        mutation createUser($input: UserInput) @rest(method: POST, path: "/users", body: $input) {
+          createUser(input: $input) { id role }
+        }
+    "#;
+
+    async fn openapi() -> Value {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let endpoints = EndpointSet::<Unchecked>::try_new(OPERATIONS)
+            .unwrap()
+            .into_checked(&blueprint, crate::core::runtime::test::init(None))
+            .await
+            .unwrap();
+
+        OpenApi::new(&blueprint, &endpoints).as_value().clone()
+    }
+
+    #[tokio::test]
+    async fn test_path_params() {
+        let openapi = openapi().await;
+        let operation = &openapi["paths"]["/api/users/{id}"]["get"];
+
+        assert_eq!(operation["summary"], "Finds a user by id");
+        assert_eq!(
+            operation["parameters"],
+            json!([{
+                "name": "id",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "integer", "format": "int32" }
+            }])
+        );
+        assert_eq!(
+            operation["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({
+                "type": ["object", "null"],
+                "properties": {
+                    "id": { "type": "integer", "format": "int32" },
+                    "name": { "type": "string" },
+                    "email": { "type": ["string", "null"], "format": "email" }
+                },
+                "required": ["id", "name"]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_params_and_fragments() {
+        let openapi = openapi().await;
+        let operation = &openapi["paths"]["/api/users"]["get"];
+
+        assert_eq!(
+            operation["parameters"],
+            json!([{
+                "name": "active",
+                "in": "query",
+                "required": false,
+                "schema": { "type": ["boolean", "null"] }
+            }])
+        );
+        assert_eq!(
+            operation["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({
+                "type": ["array", "null"],
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "integer", "format": "int32" },
+                        "name": { "type": "string" }
+                    },
+                    "required": ["id", "name"]
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_body() {
+        let openapi = openapi().await;
+        let operation = &openapi["paths"]["/api/users"]["post"];
+
+        assert_eq!(
+            operation["requestBody"],
+            json!({
+                "required": false,
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "anyOf": [{ "$ref": "#/components/schemas/UserInput" }, { "type": "null" }]
+                        }
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            openapi["components"]["schemas"],
+            json!({
+                "Role": { "type": "string", "enum": ["ADMIN", "USER"] },
+                "UserInput": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "role": {
+                            "anyOf": [{ "$ref": "#/components/schemas/Role" }, { "type": "null" }],
+                            "default": "USER"
+                        }
+                    },
+                    "required": ["name"]
+                }
+            })
+        );
+    }
+}
@@ -1,26 +1,49 @@
-use async_graphql::parser::types::ExecutableDocument;
+use async_graphql::parser::types::{ExecutableDocument, OperationType};
 use async_graphql::{Name, Variables};
-use async_graphql_value::ConstValue;
+use tailcall_valid::Validator;
 
+use super::body::Body;
 use super::path::Path;
-use super::{Request, Result};
+use super::{Error, Request, Result};
 use crate::core::async_graphql_hyper::GraphQLRequest;
+use crate::core::blueprint::Index;
+use crate::core::Type;
 
 /// A partial GraphQLRequest that contains a parsed executable GraphQL document.
 #[derive(Debug)]
 pub struct PartialRequest<'a> {
     pub body: Option<&'a String>,
     pub doc: &'a ExecutableDocument,
-    pub variables: Variables,
+    /// Variables of the path and the query, fails when a query param can't
+    /// be coerced to the type of its variable.
+    pub variables: Result<Variables>,
     pub path: &'a Path,
+    /// Types of the schema, the body is validated once the endpoint is
+    /// checked.
+    pub index: Option<&'a Index>,
 }
 
 impl PartialRequest<'_> {
+    /// Checks if the operations of the endpoint are queries.
+    pub fn is_query(&self) -> bool {
+        self.doc
+            .operations
+            .iter()
+            .all(|(_, operation)| operation.node.ty == OperationType::Query)
+    }
+
     pub async fn into_request(self, request: Request) -> Result<GraphQLRequest> {
-        let mut variables = self.variables;
+        let mut variables = self.variables?;
         if let Some(key) = self.body {
-            let bytes = hyper::body::to_bytes(request.into_body()).await?;
-            let body: ConstValue = serde_json::from_slice(&bytes)?;
+            let body = Body::read(request).await?;
+            let body = match (self.index, variable_type(self.doc, key)) {
+                (Some(index), Some(type_of)) => body
+                    .coerce(&type_of, index)
+                    .trace(key)
+                    .to_result()
+                    .map_err(Error::InvalidBody)?,
+                _ => body.value,
+            };
             variables.insert(Name::new(key), body);
         }
 
@@ -30,3 +53,11 @@ impl PartialRequest<'_> {
         Ok(GraphQLRequest(req))
     }
 }
+
+fn variable_type(doc: &ExecutableDocument, name: &str) -> Option<Type> {
+    doc.operations
+        .iter()
+        .flat_map(|(_, operation)| &operation.node.variable_definitions)
+        .find(|variable| variable.node.name.node == name)
+        .map(|variable| Type::from(&variable.node.var_type.node))
+}
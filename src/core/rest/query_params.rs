@@ -32,14 +32,24 @@ impl QueryParams {
         Ok(Self { params })
     }
 
-    pub fn matches(&self, query_params: BTreeMap<String, String>) -> Option<Variables> {
+    pub fn iter(&self) -> impl Iterator<Item = &(String, TypedVariable)> {
+        self.params.iter()
+    }
+
+    pub fn matches(&self, query_params: BTreeMap<String, String>) -> Result<Variables> {
         let mut variables = Variables::default();
         for (key, t_var) in &self.params {
             if let Some(query_param) = query_params.get(key) {
-                let value = t_var.to_value(query_param).ok()?;
+                let value =
+                    t_var
+                        .to_value(query_param)
+                        .map_err(|error| Error::InvalidQueryParam {
+                            key: key.to_string(),
+                            error: Box::new(error),
+                        })?;
                 variables.insert(Name::new(t_var.name()), value);
             }
         }
-        Some(variables)
+        Ok(variables)
     }
 }
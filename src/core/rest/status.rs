@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::core::config::{RestError, RestErrorFormat};
+
+/// Maps the failures of `@rest` endpoints to status codes, and builds the
+/// body of their responses.
+#[derive(Clone, Debug, Default)]
+pub struct ErrorMapping {
+    format: RestErrorFormat,
+    overrides: HashMap<RestError, StatusCode>,
+}
+
+impl ErrorMapping {
+    pub fn new(
+        format: RestErrorFormat,
+        overrides: impl IntoIterator<Item = (RestError, StatusCode)>,
+    ) -> Self {
+        Self { format, overrides: overrides.into_iter().collect() }
+    }
+
+    pub fn http_status(&self, error: RestError) -> StatusCode {
+        self.overrides
+            .get(&error)
+            .copied()
+            .unwrap_or_else(|| default_http_status(error))
+    }
+
+    pub fn format(&self) -> RestErrorFormat {
+        self.format
+    }
+
+    /// RFC 7807 problem for the GraphQL errors of a failed request.
+    pub fn problem(&self, status: StatusCode, errors: &Value) -> Value {
+        let mut problem = json!({
+            "type": "about:blank",
+            "title": status.canonical_reason().unwrap_or_default(),
+            "status": status.as_u16(),
+        });
+
+        let messages = errors
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|error| error["message"].as_str())
+            .collect::<Vec<_>>();
+        if !messages.is_empty() {
+            problem["detail"] = messages.join("; ").into();
+            problem["errors"] = errors.clone();
+        }
+
+        problem
+    }
+}
+
+fn default_http_status(error: RestError) -> StatusCode {
+    match error {
+        RestError::NotFound => StatusCode::NOT_FOUND,
+        RestError::BadRequest => StatusCode::BAD_REQUEST,
+        RestError::Unauthenticated => StatusCode::UNAUTHORIZED,
+        RestError::Forbidden => StatusCode::FORBIDDEN,
+        RestError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
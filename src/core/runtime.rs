@@ -3,6 +3,7 @@ use std::sync::Arc;
 use async_graphql_value::ConstValue;
 
 use super::ir::model::IoId;
+use crate::core::http::OAuth2Tokens;
 use crate::core::schema_extension::SchemaExtension;
 use crate::core::worker::{Command, Event};
 use crate::core::{Cache, EnvIO, FileIO, HttpIO, WorkerIO};
@@ -25,6 +26,8 @@ pub struct TargetRuntime {
     /// Cache for storing and retrieving entity data, improving performance and
     /// reducing external calls.
     pub cache: Arc<dyn Cache<Key = IoId, Value = ConstValue>>,
+    /// Access tokens of the OAuth2 providers of the upstreams.
+    pub oauth2_tokens: Arc<OAuth2Tokens>,
     /// A list of extensions that can be used to extend the runtime's
     /// functionality or integrate additional features.
     pub extensions: Arc<Vec<SchemaExtension>>,
@@ -187,6 +190,7 @@ pub mod test {
             env: Arc::new(env),
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
+            oauth2_tokens: Default::default(),
             extensions: Arc::new(vec![]),
             cmd_worker: match &script {
                 Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
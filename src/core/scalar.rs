@@ -6,7 +6,7 @@ use schemars::schema::{InstanceType, Schema, SchemaObject};
 use strum::IntoEnumIterator;
 use tailcall_macros::{gen_doc, Doc};
 
-use crate::core::json::JsonLike;
+use crate::core::json::{JsonLike, JsonObjectLike};
 
 const PREDEFINED_SCALARS: &[&str] = &["Boolean", "Float", "ID", "Int", "String"];
 
@@ -73,6 +73,12 @@ pub enum Scalar {
     /// Field whose value is a sequence of bytes.
     #[gen_doc(ty = "String")]
     Bytes,
+    /// Field whose value is a file of a GraphQL multipart request
+    /// (https://github.com/jaydenseric/graphql-multipart-request-spec), passed
+    /// on as an object with its `filename`, `contentType` and base64 encoded
+    /// `content`.
+    #[gen_doc(ty = "Object")]
+    Upload,
 }
 
 fn eval_str<'a, Value: JsonLike<'a>, F: Fn(&str) -> bool>(val: &'a Value, fxn: F) -> bool {
@@ -128,6 +134,13 @@ impl Scalar {
             }
             Scalar::Url => eval_str(value, |s| url::Url::parse(s).is_ok()),
             Scalar::Bytes => value.as_str().is_some(),
+            Scalar::Upload => value.as_object().is_some_and(|object| {
+                object
+                    .get_key("filename")
+                    .and_then(|v| v.as_str())
+                    .is_some()
+                    && object.get_key("content").and_then(|v| v.as_str()).is_some()
+            }),
 
             Scalar::Int64 => eval_str(value, |s| s.parse::<i64>().is_ok()),
             Scalar::UInt64 => eval_str(value, |s| s.parse::<u64>().is_ok()),
@@ -228,6 +241,25 @@ mod test {
         }
     }
 
+    mod upload {
+        use super::{ConstValue, Scalar};
+
+        test_scalar_valid! {
+            Scalar::Upload,
+            ConstValue::from_json(serde_json::json!({
+                "filename": "a.txt",
+                "contentType": "text/plain",
+                "content": "YQ=="
+            })).unwrap()
+        }
+        test_scalar_invalid! {
+            Scalar::Upload,
+            ConstValue::Null,
+            ConstValue::String("a.txt".to_string()),
+            ConstValue::from_json(serde_json::json!({ "filename": "a.txt" })).unwrap()
+        }
+    }
+
     mod date {
         use super::{ConstValue, Scalar};
         test_scalar_valid! {
@@ -42,7 +42,7 @@ async fn main() -> Result<(), Error> {
         .into_checked(&blueprint, runtime.clone())
         .await?;
 
-    let app_ctx = Arc::new(AppContext::new(blueprint, runtime, endpoints));
+    let app_ctx = Arc::new(AppContext::new(blueprint, runtime, endpoints)?);
 
     run(service_fn(|event| async {
         let resp = handle_request::<GraphQLRequest>(to_request(event)?, app_ctx.clone()).await?;
@@ -59,6 +59,7 @@ pub fn init_runtime() -> TargetRuntime {
         file: init_file(),
         env: init_env(),
         cache: init_cache(),
+        oauth2_tokens: Default::default(),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
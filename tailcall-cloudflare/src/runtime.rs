@@ -40,6 +40,7 @@ pub fn init(env: Rc<worker::Env>) -> anyhow::Result<TargetRuntime> {
         env: init_env(env.clone()),
         file: init_file(env.clone(), &bucket_id)?,
         cache: init_cache(env),
+        oauth2_tokens: Default::default(),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
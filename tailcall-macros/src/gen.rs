@@ -58,7 +58,8 @@ pub fn doc(input: TokenStream) -> TokenStream {
                 None
             })
             .collect::<Vec<_>>()
-            .join("\n");
+            // lines of a doc comment are wrapped, not separate paragraphs
+            .join(" ");
 
         quote! {
             #name::#variant_name => #docs.to_string(),
@@ -1,4 +1,5 @@
 mod error;
+mod reflection;
 
 use std::sync::{Arc, Mutex};
 
@@ -12,6 +13,7 @@ use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::{runtime, Resource};
+use reflection::ReflectionV1;
 use tonic::metadata::MetadataMap;
 use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Server as TonicServer;
@@ -242,7 +244,8 @@ async fn main() -> Result<(), Error> {
     let tonic_service = TonicServer::builder()
         .layer(server::OtelGrpcLayer::default())
         .add_service(NewsServiceServer::new(news_service))
-        .add_service(InterceptedService::new(service, intercept))
+        .add_service(InterceptedService::new(service.clone(), intercept))
+        .add_service(ReflectionV1(InterceptedService::new(service, intercept)))
         .into_service();
     let make_svc = Shared::new(tonic_service);
     println!("Server listening on grpc://{}", addr);
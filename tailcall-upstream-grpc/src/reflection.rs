@@ -0,0 +1,42 @@
+use std::task::{Context, Poll};
+
+use http::uri::PathAndQuery;
+use http::{Request, Uri};
+use tonic::server::NamedService;
+use tower::Service;
+
+const V1: &str = "grpc.reflection.v1.ServerReflection";
+const V1ALPHA: &str = "grpc.reflection.v1alpha.ServerReflection";
+
+/// Serves `grpc.reflection.v1` with the `v1alpha` service of
+/// `tonic-reflection`, the messages of both versions are identical.
+#[derive(Clone)]
+pub struct ReflectionV1<S>(pub S);
+
+impl<S> NamedService for ReflectionV1<S> {
+    const NAME: &'static str = V1;
+}
+
+impl<S, B> Service<Request<B>> for ReflectionV1<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let path = req.uri().path().replacen(V1, V1ALPHA, 1);
+        let mut parts = req.uri().clone().into_parts();
+        parts.path_and_query = PathAndQuery::try_from(path).ok();
+        if let Ok(uri) = Uri::from_parts(parts) {
+            *req.uri_mut() = uri;
+        }
+
+        self.0.call(req)
+    }
+}
@@ -63,7 +63,7 @@ impl TailcallBuilder {
         self.rt.env = Arc::new(self.env);
 
         let blueprint = Blueprint::try_from(&self.module)?;
-        let app_context = Arc::new(AppContext::new(blueprint, self.rt, EndpointSet::default()));
+        let app_context = Arc::new(AppContext::new(blueprint, self.rt, EndpointSet::default())?);
 
         Ok(TailcallExecutor { app_context })
     }
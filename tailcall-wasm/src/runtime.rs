@@ -38,6 +38,7 @@ pub fn init_rt() -> TargetRuntime {
         env,
         file,
         cache,
+        oauth2_tokens: Default::default(),
         extensions: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
@@ -6,11 +6,13 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use anyhow::anyhow;
-use http::header::{HeaderName, HeaderValue};
+use futures_util::stream::{self, StreamExt};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
 use hyper::body::Bytes;
 use tailcall::core::http::Response;
-use tailcall::core::HttpIO;
+use tailcall::core::{BodyFrame, BodyStream, HttpIO};
 
+use super::model::APIResponse;
 use super::runtime::{ExecutionMock, ExecutionSpec};
 
 #[derive(Clone, Debug)]
@@ -52,9 +54,13 @@ impl Http {
     }
 }
 
-#[async_trait::async_trait]
-impl HttpIO for Http {
-    async fn execute(&self, req: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+impl Http {
+    /// Finds the mock matching the request and returns a response with its
+    /// status and headers, together with the mocked upstream response.
+    async fn respond(
+        &self,
+        req: &reqwest::Request,
+    ) -> anyhow::Result<(Response<Bytes>, APIResponse)> {
         // Try to find a matching mock for the incoming request.
         let execution_mock = self
             .mocks
@@ -121,17 +127,45 @@ impl HttpIO for Http {
         let mut response = Response { status: status_code, ..Default::default() };
 
         // Insert headers from the mock into the response.
-        for (key, value) in mock_response.0.headers {
-            let header_name = HeaderName::from_str(&key)?;
-            let header_value = HeaderValue::from_str(&value)?;
+        for (key, value) in mock_response.0.headers.iter() {
+            let header_name = HeaderName::from_str(key)?;
+            let header_value = HeaderValue::from_str(value)?;
             response.headers.insert(header_name, header_value);
         }
 
         // Special Handling for GRPC
-        if let Some(body) = mock_response.0.body {
+        if let Some(body) = mock_response.0.body.as_ref() {
             response.body = Bytes::from(body.to_bytes());
         }
 
+        Ok((response, mock_response.0))
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpIO for Http {
+    async fn execute(&self, req: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+        let (response, _) = self.respond(&req).await?;
         Ok(response)
     }
+
+    async fn execute_stream(&self, req: reqwest::Request) -> anyhow::Result<Response<BodyStream>> {
+        let (response, mock_response) = self.respond(&req).await?;
+
+        // The body is sent as a single frame, followed by the mocked trailers.
+        let mut frames = vec![Ok(BodyFrame::Data(response.body))];
+        if !mock_response.trailers.is_empty() {
+            let mut trailers = HeaderMap::new();
+            for (key, value) in mock_response.trailers.iter() {
+                trailers.insert(HeaderName::from_str(key)?, HeaderValue::from_str(value)?);
+            }
+            frames.push(Ok(BodyFrame::Trailers(trailers)));
+        }
+
+        Ok(Response {
+            status: response.status,
+            headers: response.headers,
+            body: stream::iter(frames).boxed(),
+        })
+    }
 }
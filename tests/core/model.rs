@@ -76,6 +76,8 @@ pub struct APIResponse {
     pub headers: BTreeMap<String, String>,
     #[serde(flatten, default)]
     pub body: Option<APIBody>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub trailers: BTreeMap<String, String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -309,6 +309,7 @@ impl ExecutionSpec {
             file: Arc::new(File::new(self.clone())),
             env: Arc::new(Env::init(env)),
             cache: Arc::new(InMemoryCache::default()),
+            oauth2_tokens: Default::default(),
             extensions: Arc::new(vec![]),
             cmd_worker: http_worker,
             worker,
@@ -322,6 +323,6 @@ impl ExecutionSpec {
             .await
             .unwrap();
 
-        Arc::new(AppContext::new(blueprint, runtime, endpoints))
+        Arc::new(AppContext::new(blueprint, runtime, endpoints).unwrap())
     }
 }
@@ -80,6 +80,7 @@ pub fn create_runtime(
         env: Arc::new(env),
         file: Arc::new(file),
         cache: Arc::new(InMemoryCache::default()),
+        oauth2_tokens: Default::default(),
         extensions: Arc::new(vec![]),
         cmd_worker: match &script {
             Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
@@ -162,15 +162,22 @@ async fn run_query_tests_on_spec(
                 headers.insert(key.to_string(), value.to_str().unwrap().to_string());
             }
 
+            let status = response.status().as_u16();
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+            // event streams are kept as they were sent
+            let body =
+                if headers.get("content-type").map(String::as_str) == Some("text/event-stream") {
+                    APIBody::Text(String::from_utf8_lossy(&bytes).into_owned())
+                } else {
+                    APIBody::Value(serde_json::from_slice(&bytes).unwrap_or_default())
+                };
+
             let response: APIResponse = APIResponse {
-                status: response.status().clone().as_u16(),
+                status,
                 headers,
-                body: Some(APIBody::Value(
-                    serde_json::from_slice(
-                        &hyper::body::to_bytes(response.into_body()).await.unwrap(),
-                    )
-                    .unwrap_or_default(),
-                )),
+                body: Some(body),
+                trailers: Default::default(),
             };
 
             let snapshot_name = format!("{}_{}", spec.safe_name, i);